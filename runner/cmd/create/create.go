@@ -14,19 +14,15 @@ import (
 	"path/filepath"
 	"strings"
 
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
+
 	"github.com/moby/moby/api/types/container"
 	"github.com/moby/moby/api/types/network"
 	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
 )
 
-type CreateResult struct {
-	ContainerID   string `json:"container_id"`
-	ContainerName string `json:"container_name"`
-	Status        string `json:"status"`
-	Error         string `json:"error,omitempty"`
-}
-
 var (
 	imageName     string
 	containerName string
@@ -45,6 +41,7 @@ var CreateCmd = &cobra.Command{
 	Short: "Create a Docker container",
 	Long:  `Create a Docker container from docker-compose configuration`,
 	Run: func(cmd *cobra.Command, args []string) {
+		defer protocol.Done()
 		createContainer()
 	},
 }
@@ -63,11 +60,6 @@ func init() {
 	CreateCmd.MarkFlagRequired("image")
 }
 
-func outputJSON(result CreateResult) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(result)
-}
-
 func parsePortBindings(portsStr string) (network.PortSet, network.PortMap) {
 	exposedPorts := make(network.PortSet)
 	portBindings := make(network.PortMap)
@@ -171,19 +163,11 @@ func imageExists(cli *client.Client, imageName string) bool {
 }
 
 func buildImage(cli *client.Client, imageName string, contextPath string) error {
-	outputPullProgress(PullProgress{
-		Image:  imageName,
-		Status: "building",
-	})
+	protocol.Progress(protocol.StageBuilding, imageName, "")
 
 	tarReader, err := createTarFromDir(contextPath)
 	if err != nil {
-		outputPullProgress(PullProgress{
-			Image:  imageName,
-			Status: "error",
-			Error:  fmt.Sprintf("failed to create build context: %v", err),
-		})
-		return err
+		return fmt.Errorf("failed to create build context: %w", err)
 	}
 
 	buildOptions := client.ImageBuildOptions{
@@ -194,11 +178,6 @@ func buildImage(cli *client.Client, imageName string, contextPath string) error
 
 	resp, err := cli.ImageBuild(context.Background(), tarReader, buildOptions)
 	if err != nil {
-		outputPullProgress(PullProgress{
-			Image:  imageName,
-			Status: "error",
-			Error:  fmt.Sprintf("failed to build image: %v", err),
-		})
 		return err
 	}
 	defer resp.Body.Close()
@@ -213,26 +192,14 @@ func buildImage(cli *client.Client, imageName string, contextPath string) error
 			break
 		}
 		if stream, ok := progress["stream"].(string); ok {
-			outputPullProgress(PullProgress{
-				Image:    imageName,
-				Status:   "building",
-				Progress: strings.TrimSpace(stream),
-			})
+			protocol.Progress(protocol.StageBuilding, imageName, strings.TrimSpace(stream))
 		}
 		if errMsg, ok := progress["error"].(string); ok {
-			outputPullProgress(PullProgress{
-				Image:  imageName,
-				Status: "error",
-				Error:  errMsg,
-			})
 			return fmt.Errorf("build error: %s", errMsg)
 		}
 	}
 
-	outputPullProgress(PullProgress{
-		Image:  imageName,
-		Status: "completed",
-	})
+	protocol.Progress(protocol.StageCompleted, imageName, "")
 
 	return nil
 }
@@ -292,25 +259,14 @@ func createTarFromDir(srcDir string) (io.Reader, error) {
 func pullImage(cli *client.Client, imageName string) error {
 	// Skip if image already exists
 	if imageExists(cli, imageName) {
-		outputPullProgress(PullProgress{
-			Image:  imageName,
-			Status: "exists",
-		})
+		protocol.Progress(protocol.StageExists, imageName, "")
 		return nil
 	}
 
-	outputPullProgress(PullProgress{
-		Image:  imageName,
-		Status: "pulling",
-	})
+	protocol.Progress(protocol.StagePulling, imageName, "")
 
 	out, err := cli.ImagePull(context.Background(), imageName, client.ImagePullOptions{})
 	if err != nil {
-		outputPullProgress(PullProgress{
-			Image:  imageName,
-			Status: "error",
-			Error:  err.Error(),
-		})
 		return err
 	}
 	defer out.Close()
@@ -323,43 +279,29 @@ func pullImage(cli *client.Client, imageName string) error {
 			break
 		}
 		if status, ok := progress["status"].(string); ok {
-			outputPullProgress(PullProgress{
-				Image:    imageName,
-				Status:   "downloading",
-				Progress: status,
-			})
+			protocol.Progress(protocol.StageDownloading, imageName, status)
+		}
+		if errMsg, ok := progress["error"].(string); ok {
+			return fmt.Errorf("pull error: %s", errMsg)
 		}
 	}
 
-	outputPullProgress(PullProgress{
-		Image:  imageName,
-		Status: "completed",
-	})
+	protocol.Progress(protocol.StageCompleted, imageName, "")
 
 	return nil
 }
 
-type PullProgress struct {
-	Image    string `json:"image"`
-	Status   string `json:"status"`
-	Progress string `json:"progress,omitempty"`
-	Error    string `json:"error,omitempty"`
+func fail(message string, cause error) {
+	protocol.Error(message, cause)
+	protocol.Result(1, "error", "")
 }
 
-func outputPullProgress(p PullProgress) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(p)
-}
 func createContainer() {
 	ctx := context.Background()
 
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
-		outputJSON(CreateResult{
-			ContainerName: containerName,
-			Status:        "error",
-			Error:         fmt.Sprintf("failed to create docker client: %v", err),
-		})
+		fail("failed to create docker client", err)
 		return
 	}
 	defer cli.Close()
@@ -394,42 +336,33 @@ func createContainer() {
 	// Build or pull image before creating container
 	if buildContext != "" {
 		if err := buildImage(cli, imageName, buildContext); err != nil {
-			outputJSON(CreateResult{
-				ContainerName: containerName,
-				Status:        "error",
-				Error:         fmt.Sprintf("failed to build image: %v", err),
-			})
+			fail("failed to build image", err)
 			return
 		}
-	} else {
-		pullImage(cli, imageName)
+	} else if err := pullImage(cli, imageName); err != nil {
+		fail("failed to pull image", err)
+		return
 	}
 
+	protocol.Progress(protocol.StageCreating, imageName, containerName)
 	resp, err := cli.ContainerCreate(ctx, opts)
 	if err != nil {
-		outputJSON(CreateResult{
-			ContainerName: containerName,
-			Status:        "error",
-			Error:         fmt.Sprintf("failed to create container: %v", err),
-		})
+		fail("failed to create container", err)
 		return
 	}
 
-	result := CreateResult{
-		ContainerID:   resp.ID,
-		ContainerName: containerName,
-		Status:        "created",
+	if !autoStart {
+		protocol.Result(0, "created", resp.ID)
+		return
 	}
 
-	if autoStart {
-		_, err = cli.ContainerStart(ctx, resp.ID, client.ContainerStartOptions{})
-		if err != nil {
-			result.Status = "created"
-			result.Error = fmt.Sprintf("container created but failed to start: %v", err)
-		} else {
-			result.Status = "running"
-		}
+	protocol.Progress(protocol.StageStarting, imageName, containerName)
+	_, err = cli.ContainerStart(ctx, resp.ID, client.ContainerStartOptions{})
+	if err != nil {
+		protocol.Error("container created but failed to start", err)
+		protocol.Result(1, "created", resp.ID)
+		return
 	}
 
-	outputJSON(result)
+	protocol.Result(0, "running", resp.ID)
 }
@@ -5,8 +5,9 @@ package delete
 
 import (
 	"context"
-	"fmt"
-	"log"
+
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
 
 	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
@@ -18,6 +19,7 @@ var DeleteContainerCmd = &cobra.Command{
 	Long:  `Delete a Docker container by container ID`,
 	Args:  cobra.ExactArgs(1),
 	Run: func(cmd *cobra.Command, args []string) {
+		defer protocol.Done()
 		containerID := args[0]
 		DeleteContainer(containerID)
 	},
@@ -25,9 +27,10 @@ var DeleteContainerCmd = &cobra.Command{
 
 func DeleteContainer(containerID string) {
 	ctx := context.Background()
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
-		fmt.Printf("[Error] Error creating Docker client: %v\n", err)
+		protocol.Error("failed to create docker client", err)
+		protocol.Result(1, "error", containerID)
 		return
 	}
 	defer cli.Close()
@@ -37,13 +40,15 @@ func DeleteContainer(containerID string) {
 		RemoveVolumes: false,
 	}
 
+	protocol.Progress(protocol.StageRemoving, "", containerID)
 	_, err = cli.ContainerRemove(ctx, containerID, options)
 	if err != nil {
-		fmt.Printf("[Error] Error removing container %s: %v\n", containerID, err)
+		protocol.Error("failed to remove container "+containerID, err)
+		protocol.Result(1, "error", containerID)
 		return
 	}
 
-	fmt.Printf("[Success] Container %s has been removed\n", containerID)
+	protocol.Result(0, "removed", containerID)
 }
 
 var DeleteImageCmd = &cobra.Command{
@@ -52,6 +57,7 @@ var DeleteImageCmd = &cobra.Command{
 	Long:  `Delete image based by image id`,
 	Args:  cobra.ExactArgs(1),
 	Run: func(cmd *cobra.Command, args []string) {
+		defer protocol.Done()
 		imageID := args[0]
 		DeleteExistingImage(imageID)
 	},
@@ -59,10 +65,13 @@ var DeleteImageCmd = &cobra.Command{
 
 func DeleteExistingImage(imageID string) {
 	ctx := context.Background()
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
-		log.Fatalf("Error creating Docker client: %v", err)
+		protocol.Error("failed to create docker client", err)
+		protocol.Result(1, "error", imageID)
+		return
 	}
+	defer cli.Close()
 
 	options := client.ImageRemoveOptions{
 		// TODO:: Changing to false after implementing "Force Delete" menu
@@ -70,18 +79,21 @@ func DeleteExistingImage(imageID string) {
 		PruneChildren: true,
 	}
 
+	protocol.Progress(protocol.StageRemoving, imageID, "")
 	dels, err := cli.ImageRemove(ctx, imageID, options)
 	if err != nil {
-		log.Fatalf("Error removing image %s: %v", imageID, err)
+		protocol.Error("failed to remove image "+imageID, err)
+		protocol.Result(1, "error", imageID)
+		return
 	}
 
-	fmt.Printf("Image(s) removed successfully:\n")
 	for _, del := range dels.Items {
 		if del.Deleted != "" {
-			fmt.Printf("Deleted: %s\n", del.Deleted)
+			protocol.Log("Deleted: " + del.Deleted)
 		}
 		if del.Untagged != "" {
-			fmt.Printf("Untagged: %s\n", del.Untagged)
+			protocol.Log("Untagged: " + del.Untagged)
 		}
 	}
+	protocol.Result(0, "removed", imageID)
 }
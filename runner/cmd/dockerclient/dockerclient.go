@@ -0,0 +1,87 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+package dockerclient
+
+import (
+	"context"
+	"io"
+	"net"
+	"os"
+	"os/exec"
+	"strings"
+	"time"
+
+	"github.com/moby/moby/client"
+)
+
+/*
+* New returns an API client configured from the environment, like
+* client.New(client.FromEnv), but also understands ssh:// hosts from docker
+* contexts by tunnelling through `ssh <host> docker system dial-stdio`, the
+* same way the docker CLI does.
+* */
+func New() (*client.Client, error) {
+	host := os.Getenv("DOCKER_HOST")
+	if !strings.HasPrefix(host, "ssh://") {
+		return client.New(client.FromEnv)
+	}
+
+	return client.New(
+		client.FromEnv,
+		// Only used to build request URLs; every connection goes through ssh.
+		client.WithHost("http://docker.example.com"),
+		client.WithDialContext(func(ctx context.Context, network, addr string) (net.Conn, error) {
+			return dialSSH(host)
+		}),
+	)
+}
+
+func dialSSH(host string) (net.Conn, error) {
+	// BatchMode: a password prompt would land in the middle of the TUI.
+	cmd := exec.Command("ssh", "-T", "-o", "BatchMode=yes", "--", host, "docker", "system", "dial-stdio")
+	stdin, err := cmd.StdinPipe()
+	if err != nil {
+		return nil, err
+	}
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		return nil, err
+	}
+	cmd.Stderr = os.Stderr
+	if err := cmd.Start(); err != nil {
+		return nil, err
+	}
+	return &commandConn{cmd: cmd, stdin: stdin, stdout: stdout}, nil
+}
+
+// commandConn is a net.Conn over a child process's stdin and stdout.
+type commandConn struct {
+	cmd    *exec.Cmd
+	stdin  io.WriteCloser
+	stdout io.ReadCloser
+}
+
+func (c *commandConn) Read(p []byte) (int, error)  { return c.stdout.Read(p) }
+func (c *commandConn) Write(p []byte) (int, error) { return c.stdin.Write(p) }
+
+func (c *commandConn) Close() error {
+	c.stdin.Close()
+	c.stdout.Close()
+	if c.cmd.Process != nil {
+		c.cmd.Process.Kill()
+	}
+	c.cmd.Wait()
+	return nil
+}
+
+func (c *commandConn) LocalAddr() net.Addr                { return dummyAddr{} }
+func (c *commandConn) RemoteAddr() net.Addr               { return dummyAddr{} }
+func (c *commandConn) SetDeadline(t time.Time) error      { return nil }
+func (c *commandConn) SetReadDeadline(t time.Time) error  { return nil }
+func (c *commandConn) SetWriteDeadline(t time.Time) error { return nil }
+
+type dummyAddr struct{}
+
+func (dummyAddr) Network() string { return "ssh" }
+func (dummyAddr) String() string  { return "ssh" }
@@ -4,11 +4,15 @@
 package images
 
 import (
-	"encoding/json"
-	"os"
-	"os/exec"
+	"context"
 	"strings"
+	"time"
 
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
+
+	"github.com/docker/go-units"
+	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
 )
 
@@ -30,30 +34,53 @@ var ImagesCmd = &cobra.Command{
 }
 
 func outputJSON(img DockerImage) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(img)
+	protocol.Data(img)
 }
 
+/*
+* Goes through the API rather than the docker CLI so it works against any
+* DOCKER_HOST, including a Podman socket on hosts without a docker binary.
+* Fields are formatted the way `docker images` prints them.
+* */
 func listImages() {
-	cmd := exec.Command("docker", "images", "--format", "{{.Repository}}\t{{.Tag}}\t{{.ID}}\t{{.CreatedSince}}\t{{.Size}}")
-	output, err := cmd.Output()
+	ctx := context.Background()
+
+	cli, err := dockerclient.New()
+	if err != nil {
+		protocol.Fail("failed to create docker client", err)
+		return
+	}
+	defer cli.Close()
+
+	result, err := cli.ImageList(ctx, client.ImageListOptions{})
 	if err != nil {
+		protocol.Fail("failed to list images", err)
 		return
 	}
 
-	lines := strings.Split(string(output), "\n")
-	for _, line := range lines {
-		if line == "" {
-			continue
+	for _, img := range result.Items {
+		id := strings.TrimPrefix(img.ID, "sha256:")
+		if len(id) > 12 {
+			id = id[:12]
+		}
+		created := units.HumanDuration(time.Since(time.Unix(img.Created, 0))) + " ago"
+		size := units.HumanSizeWithPrecision(float64(img.Size), 3)
+
+		tags := img.RepoTags
+		if len(tags) == 0 {
+			tags = []string{"<none>:<none>"}
 		}
-		parts := strings.Split(line, "\t")
-		if len(parts) >= 5 {
+		for _, repoTag := range tags {
+			repository, tag := repoTag, "<none>"
+			if i := strings.LastIndex(repoTag, ":"); i > strings.LastIndex(repoTag, "/") {
+				repository, tag = repoTag[:i], repoTag[i+1:]
+			}
 			outputJSON(DockerImage{
-				Repository: parts[0],
-				Tag:        parts[1],
-				ImageID:    parts[2],
-				Created:    parts[3],
-				Size:       parts[4],
+				Repository: repository,
+				Tag:        tag,
+				ImageID:    id,
+				Created:    created,
+				Size:       size,
 			})
 		}
 	}
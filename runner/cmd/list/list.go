@@ -5,8 +5,11 @@ package list
 
 import (
 	"context"
-	"encoding/json"
-	"os"
+	"strings"
+	"sync"
+
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
 
 	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
@@ -21,6 +24,19 @@ type DockerContainer struct {
 	State   string   `json:"state"`
 	Ports   []Port   `json:"ports"`
 	Names   []string `json:"names"`
+	Health  *Health  `json:"health,omitempty"`
+}
+
+/*
+* Health of a container with a healthcheck
+* Status: starting, healthy or unhealthy
+* ExitCode, Output: result of the most recent probe
+* */
+type Health struct {
+	Status        string `json:"status"`
+	FailingStreak int    `json:"failing_streak"`
+	ExitCode      int    `json:"exit_code"`
+	Output        string `json:"output"`
 }
 
 type Port struct {
@@ -30,6 +46,11 @@ type Port struct {
 	Type        string `json:"type"`
 }
 
+// How many containers are inspected at once for --health.
+const inspectConcurrency = 8
+
+var withHealth bool
+
 var ListCmd = &cobra.Command{
 	Use:   "list",
 	Short: "List Docker containers",
@@ -39,26 +60,72 @@ var ListCmd = &cobra.Command{
 	},
 }
 
+func init() {
+	ListCmd.Flags().BoolVar(&withHealth, "health", false, "inspect containers with a healthcheck for their probe results")
+}
+
 func outputJSON(container DockerContainer) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(container)
+	protocol.Data(container)
+}
+
+/*
+* The list only carries health in the status text ("Up 5 minutes
+* (healthy)"), so with --health containers with a healthcheck are
+* inspected for the probe results, several at a time.
+* */
+func health(ctx context.Context, cli *client.Client, id string) *Health {
+	inspect, err := cli.ContainerInspect(ctx, id, client.ContainerInspectOptions{})
+	if err != nil || inspect.Container.State == nil || inspect.Container.State.Health == nil {
+		return nil
+	}
+	state := inspect.Container.State.Health
+	result := &Health{
+		Status:        string(state.Status),
+		FailingStreak: state.FailingStreak,
+	}
+	if n := len(state.Log); n > 0 && state.Log[n-1] != nil {
+		result.ExitCode = state.Log[n-1].ExitCode
+		result.Output = strings.TrimSpace(state.Log[n-1].Output)
+	}
+	return result
 }
 
 func listContainers() {
 	ctx := context.Background()
 
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
+		protocol.Fail("failed to create docker client", err)
 		return
 	}
 	defer cli.Close()
 
 	result, err := cli.ContainerList(ctx, client.ContainerListOptions{All: true})
 	if err != nil {
+		protocol.Fail("failed to list containers", err)
 		return
 	}
 
-	for _, c := range result.Items {
+	healths := make([]*Health, len(result.Items))
+	if withHealth {
+		var wg sync.WaitGroup
+		slots := make(chan struct{}, inspectConcurrency)
+		for i, c := range result.Items {
+			if !strings.Contains(c.Status, "health") {
+				continue
+			}
+			wg.Add(1)
+			go func(i int, id string) {
+				defer wg.Done()
+				slots <- struct{}{}
+				defer func() { <-slots }()
+				healths[i] = health(ctx, cli, id)
+			}(i, c.ID)
+		}
+		wg.Wait()
+	}
+
+	for i, c := range result.Items {
 		var ports []Port
 		for _, p := range c.Ports {
 			ports = append(ports, Port{
@@ -78,6 +145,7 @@ func listContainers() {
 			State:   string(c.State),
 			Ports:   ports,
 			Names:   c.Names,
+			Health:  healths[i],
 		})
 	}
 }
@@ -0,0 +1,116 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+package protocol
+
+import (
+	"encoding/json"
+	"os"
+)
+
+/*
+* JSON-lines protocol spoken by every command the TUI runs. Each line on
+* stdout is one envelope. Lifecycle commands (create, start, stop, rm, rmi)
+* report what they do and finish with done:
+*
+*   {"v":1,"type":"progress","stage":"pulling","image":"nginx"}
+*   {"v":1,"type":"log","line":"Deleted: sha256:..."}
+*   {"v":1,"type":"result","exit_code":0,"status":"running","id":"abc"}
+*   {"v":1,"type":"error","message":"failed to start container","cause":"..."}
+*   {"v":1,"type":"done"}
+*
+* Queries (list, images, search, read) send one data envelope per item, or
+* an error when they fail, and end with the process:
+*
+*   {"v":1,"type":"data","data":{"id":"abc","image":"nginx",...}}
+*
+* stream sends a data envelope per stats sample until the container stops,
+* then done; when it cannot read the stats it sends an error before done.
+*
+* Bump Version whenever a field changes meaning; the TUI rejects envelopes
+* with a version it does not know.
+*
+* The process exit code mirrors the last result, so a failed operation is
+* visible even to callers that ignore stdout.
+* */
+const Version = 1
+
+var exitCode int
+
+type Stage string
+
+const (
+	StagePulling     Stage = "pulling"
+	StageDownloading Stage = "downloading"
+	StageExists      Stage = "exists"
+	StageBuilding    Stage = "building"
+	StageCompleted   Stage = "completed"
+	StageCreating    Stage = "creating"
+	StageStarting    Stage = "starting"
+	StageStopping    Stage = "stopping"
+	StageRemoving    Stage = "removing"
+)
+
+type Envelope struct {
+	V        int    `json:"v"`
+	Type     string `json:"type"`
+	Stage    Stage  `json:"stage,omitempty"`
+	Image    string `json:"image,omitempty"`
+	Detail   string `json:"detail,omitempty"`
+	Line     string `json:"line,omitempty"`
+	ExitCode *int   `json:"exit_code,omitempty"`
+	Status   string `json:"status,omitempty"`
+	ID       string `json:"id,omitempty"`
+	Message  string `json:"message,omitempty"`
+	Cause    string `json:"cause,omitempty"`
+	Data     any    `json:"data,omitempty"`
+}
+
+func emit(e Envelope) {
+	e.V = Version
+	encoder := json.NewEncoder(os.Stdout)
+	encoder.Encode(e)
+}
+
+func Progress(stage Stage, image string, detail string) {
+	emit(Envelope{Type: "progress", Stage: stage, Image: image, Detail: detail})
+}
+
+func Log(line string) {
+	emit(Envelope{Type: "log", Line: line})
+}
+
+func Result(code int, status string, id string) {
+	exitCode = code
+	emit(Envelope{Type: "result", ExitCode: &code, Status: status, ID: id})
+}
+
+func Error(message string, cause error) {
+	e := Envelope{Type: "error", Message: message}
+	if cause != nil {
+		e.Cause = cause.Error()
+	}
+	emit(e)
+}
+
+// Data emits one item of a query's reply.
+func Data(value any) {
+	emit(Envelope{Type: "data", Data: value})
+}
+
+// Fail reports why a query failed and makes the process exit with 1.
+func Fail(message string, cause error) {
+	exitCode = 1
+	Error(message, cause)
+}
+
+// Done emits the final envelope.
+func Done() {
+	emit(Envelope{Type: "done"})
+}
+
+// ExitCode is the code of the last Result, 0 without one. main exits with
+// it after the command returned, so deferred cleanup still runs.
+func ExitCode() int {
+	return exitCode
+}
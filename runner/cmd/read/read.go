@@ -4,13 +4,18 @@
 package read
 
 import (
-	"encoding/json"
 	"fmt"
 	"os"
 	"path/filepath"
+	"sort"
+	"strings"
+
+	"easydocker/runner/cmd/protocol"
 
 	"github.com/moby/buildkit/frontend/dockerfile/parser"
 
+	"github.com/compose-spec/compose-go/cli"
+	"github.com/compose-spec/compose-go/dotenv"
 	"github.com/compose-spec/compose-go/loader"
 	"github.com/compose-spec/compose-go/types"
 )
@@ -20,29 +25,58 @@ type DockerFile struct {
 	Original string `json:"original"`
 }
 
+type Dependency struct {
+	Service   string `json:"service"`
+	Condition string `json:"condition"`
+}
+
+/*
+* A service's healthcheck as the compose files define it
+* Durations are empty when not set, leaving Docker's defaults.
+* */
+type Healthcheck struct {
+	Test        []string `json:"test"`
+	Interval    string   `json:"interval"`
+	Timeout     string   `json:"timeout"`
+	StartPeriod string   `json:"start_period"`
+	Retries     uint64   `json:"retries"`
+	Disable     bool     `json:"disable"`
+}
+
 type DockerCompose struct {
-	Name          string   `json:"name"`
-	Service       string   `json:"service"`
-	Image         string   `json:"image"`
-	Ports         string   `json:"ports"`
-	ContainerName string   `json:"container_name"`
-	Hostname      string   `json:"hostname"`
-	BuildContext  string   `json:"build_context"`
-	Dockerfile    string   `json:"dockerfile"`
-	Environment   []string `json:"environment"`
-	Volumes       []string `json:"volumes"`
-	Networks      []string `json:"networks"`
-	Restart       string   `json:"restart"`
+	Name            string       `json:"name"`
+	Service         string       `json:"service"`
+	Image           string       `json:"image"`
+	Ports           string       `json:"ports"`
+	ContainerName   string       `json:"container_name"`
+	Hostname        string       `json:"hostname"`
+	BuildContext    string       `json:"build_context"`
+	Dockerfile      string       `json:"dockerfile"`
+	Environment     []string     `json:"environment"`
+	Volumes         []string     `json:"volumes"`
+	Binds           []string     `json:"binds"`
+	Networks        []string     `json:"networks"`
+	ProjectNetworks []string     `json:"project_networks"`
+	NamedVolumes    []string     `json:"named_volumes"`
+	ProjectVolumes  []string     `json:"project_volumes"`
+	Secrets         []string     `json:"secrets"`
+	ProjectSecrets  []string     `json:"project_secrets"`
+	Configs         []string     `json:"configs"`
+	ProjectConfigs  []string     `json:"project_configs"`
+	Restart         string       `json:"restart"`
+	Files           []string     `json:"files"`
+	References      []string     `json:"references"`
+	DependsOn       []Dependency `json:"depends_on"`
+	Healthcheck     *Healthcheck `json:"healthcheck"`
+	Profiles        []string     `json:"profiles"`
 }
 
 func outputDockerJSON(o DockerFile) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(o)
+	protocol.Data(o)
 }
 
 func outputJSON(o DockerCompose) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(o)
+	protocol.Data(o)
 }
 
 func ReadDocker(path string) {
@@ -60,31 +94,248 @@ func ReadDocker(path string) {
 	}
 }
 
+/*
+* Compose files `docker compose` would load from dir without -f: the first
+* of the standard names that exists, then the first existing override file.
+* Later files are merged over earlier ones by the loader.
+* */
+func composeFiles(dir string) []string {
+	var files []string
+	for _, names := range [][]string{cli.DefaultFileNames, cli.DefaultOverrideFileNames} {
+		for _, name := range names {
+			file := filepath.Join(dir, name)
+			if _, err := os.Stat(file); err == nil {
+				files = append(files, file)
+				break
+			}
+		}
+		if len(files) == 0 {
+			break
+		}
+	}
+	return files
+}
+
+/*
+* Variables used for interpolation, as `docker compose` sees them: the
+* project's .env file, overridden by the process environment.
+* */
+func environment(workdir string) map[string]string {
+	env := map[string]string{}
+
+	dotEnv := filepath.Join(workdir, ".env")
+	if _, err := os.Stat(dotEnv); err == nil {
+		values, err := dotenv.Read(dotEnv)
+		if err != nil {
+			panic(fmt.Errorf("reading %s: %w", dotEnv, err))
+		}
+		for key, value := range values {
+			env[key] = value
+		}
+	}
+
+	for _, kv := range os.Environ() {
+		if key, value, ok := strings.Cut(kv, "="); ok {
+			env[key] = value
+		}
+	}
+	return env
+}
+
+/*
+* Files besides the compose, override and .env files of the project
+* directory that change what is read: env_file of services, files services
+* extend and included compose files with their env_file, followed through
+* extended and included files. Paths are absolute and listed whether they
+* exist or not, so the TUI's index notices one being created, edited or
+* removed.
+* */
+func references(files []string) []string {
+	refs := []string{}
+	seen := map[string]bool{}
+	for _, file := range files {
+		seen[file] = true
+	}
+	add := func(path string) bool {
+		if seen[path] {
+			return false
+		}
+		seen[path] = true
+		refs = append(refs, path)
+		return true
+	}
+
+	var walk func(file string)
+	walk = func(file string) {
+		source, err := os.ReadFile(file)
+		if err != nil {
+			return
+		}
+		dict, err := loader.ParseYAML(source)
+		if err != nil {
+			return
+		}
+		resolve := func(path string) string {
+			if filepath.IsAbs(path) {
+				return path
+			}
+			return filepath.Join(filepath.Dir(file), path)
+		}
+
+		for _, item := range list(dict["include"]) {
+			include, ok := item.(map[string]interface{})
+			if !ok {
+				include = map[string]interface{}{"path": item}
+			}
+			for _, path := range stringList(include["path"]) {
+				if path := resolve(path); add(path) {
+					walk(path)
+				}
+			}
+			for _, path := range stringList(include["env_file"]) {
+				add(resolve(path))
+			}
+		}
+
+		services, _ := dict["services"].(map[string]interface{})
+		for _, item := range services {
+			service, ok := item.(map[string]interface{})
+			if !ok {
+				continue
+			}
+			for _, envFile := range list(service["env_file"]) {
+				switch envFile := envFile.(type) {
+				case string:
+					add(resolve(envFile))
+				case map[string]interface{}:
+					if path, ok := envFile["path"].(string); ok {
+						add(resolve(path))
+					}
+				}
+			}
+			if extends, ok := service["extends"].(map[string]interface{}); ok {
+				if path, ok := extends["file"].(string); ok {
+					if path := resolve(path); add(path) {
+						walk(path)
+					}
+				}
+			}
+		}
+	}
+	for _, file := range files {
+		walk(file)
+	}
+	sort.Strings(refs)
+	return refs
+}
+
+// A YAML value that may be a single item or a list of them, as a list.
+func list(value interface{}) []interface{} {
+	switch value := value.(type) {
+	case nil:
+		return nil
+	case []interface{}:
+		return value
+	default:
+		return []interface{}{value}
+	}
+}
+
+func stringList(value interface{}) []string {
+	var items []string
+	for _, item := range list(value) {
+		if s, ok := item.(string); ok {
+			items = append(items, s)
+		}
+	}
+	return items
+}
+
+// The names of a top-level section (networks, volumes, ...), sorted.
+func keys[T any](section map[string]T) []string {
+	names := []string{}
+	for name := range section {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+func duration(d *types.Duration) string {
+	if d == nil {
+		return ""
+	}
+	return d.String()
+}
+
+func healthcheck(config *types.HealthCheckConfig) *Healthcheck {
+	if config == nil {
+		return nil
+	}
+	check := &Healthcheck{
+		Test:        config.Test,
+		Interval:    duration(config.Interval),
+		Timeout:     duration(config.Timeout),
+		StartPeriod: duration(config.StartPeriod),
+		Disable:     config.Disable,
+	}
+	if config.Retries != nil {
+		check.Retries = *config.Retries
+	}
+	return check
+}
+
 func ReadCompose(path string) {
 	abs, err := filepath.Abs(path)
 	if err != nil {
 		panic(err)
 	}
 
-	workdir := filepath.Dir(abs)
+	var workdir string
+	var files []string
+	if info, err := os.Stat(abs); err == nil && info.IsDir() {
+		workdir = abs
+		files = composeFiles(abs)
+		if len(files) == 0 {
+			panic(fmt.Errorf("no compose file in %s", abs))
+		}
+	} else {
+		workdir = filepath.Dir(abs)
+		files = []string{abs}
+	}
+
+	configFiles := []types.ConfigFile{}
+	for _, file := range files {
+		configFiles = append(configFiles, types.ConfigFile{Filename: file})
+	}
 
 	config := types.ConfigDetails{
-		WorkingDir: workdir,
-		ConfigFiles: []types.ConfigFile{
-			{
-				Filename: abs,
-			},
-		},
-		Environment: map[string]string{},
+		WorkingDir:  workdir,
+		ConfigFiles: configFiles,
+		Environment: environment(workdir),
 	}
 
-	project, err := loader.Load(config)
+	// Named after the directory unless the files set a top-level name.
+	// References to undefined services, networks, volumes, secrets and
+	// configs are let through so the lint view can point at them instead
+	// of the project vanishing; the defined ones are reported with each
+	// service for that.
+	// Every profile is enabled; which ones are active is up to the TUI.
+	project, err := loader.Load(config, func(o *loader.Options) {
+		o.SetProjectName(loader.NormalizeProjectName(filepath.Base(workdir)), false)
+		o.SkipConsistencyCheck = true
+		o.Profiles = []string{"*"}
+	})
 	if err != nil {
 		panic(err)
 	}
-	project.Name = loader.NormalizeProjectName(
-		filepath.Base(filepath.Dir(path)),
-	)
+
+	refs := references(files)
+
+	projectNetworks := keys(project.Networks)
+	projectVolumes := keys(project.Volumes)
+	projectSecrets := keys(project.Secrets)
+	projectConfigs := keys(project.Configs)
 
 	for _, svc := range project.Services {
 		envVars := []string{}
@@ -97,8 +348,25 @@ func ReadCompose(path string) {
 		}
 
 		volumes := []string{}
+		binds := []string{}
+		namedVolumes := []string{}
 		for _, vol := range svc.Volumes {
 			volumes = append(volumes, vol.String())
+			switch {
+			case vol.Type == types.VolumeTypeBind:
+				binds = append(binds, vol.Source)
+			case vol.Type == types.VolumeTypeVolume && vol.Source != "":
+				namedVolumes = append(namedVolumes, vol.Source)
+			}
+		}
+
+		secrets := []string{}
+		for _, secret := range svc.Secrets {
+			secrets = append(secrets, secret.Source)
+		}
+		configs := []string{}
+		for _, config := range svc.Configs {
+			configs = append(configs, config.Source)
 		}
 
 		networks := []string{}
@@ -127,24 +395,51 @@ func ReadCompose(path string) {
 			}
 		}
 
+		dependsOn := []Dependency{}
+		for name, dep := range svc.DependsOn {
+			dependsOn = append(dependsOn, Dependency{
+				Service:   name,
+				Condition: dep.Condition,
+			})
+		}
+		sort.Slice(dependsOn, func(i, j int) bool {
+			return dependsOn[i].Service < dependsOn[j].Service
+		})
+
+		profiles := []string{}
+		profiles = append(profiles, svc.Profiles...)
+
 		image := svc.Image
 		if image == "" && svc.Build != nil {
 			image = fmt.Sprintf("%s-%s", project.Name, svc.Name)
 		}
 
 		outputJSON(DockerCompose{
-			Name:          project.Name,
-			Service:       svc.Name,
-			Image:         image,
-			Ports:         portsStr,
-			ContainerName: svc.ContainerName,
-			Hostname:      svc.Hostname,
-			BuildContext:  buildContext,
-			Dockerfile:    dockerfile,
-			Environment:   envVars,
-			Volumes:       volumes,
-			Networks:      networks,
-			Restart:       svc.Restart,
+			Name:            project.Name,
+			Service:         svc.Name,
+			Image:           image,
+			Ports:           portsStr,
+			ContainerName:   svc.ContainerName,
+			Hostname:        svc.Hostname,
+			BuildContext:    buildContext,
+			Dockerfile:      dockerfile,
+			Environment:     envVars,
+			Volumes:         volumes,
+			Binds:           binds,
+			Networks:        networks,
+			ProjectNetworks: projectNetworks,
+			NamedVolumes:    namedVolumes,
+			ProjectVolumes:  projectVolumes,
+			Secrets:         secrets,
+			ProjectSecrets:  projectSecrets,
+			Configs:         configs,
+			ProjectConfigs:  projectConfigs,
+			Restart:         svc.Restart,
+			Files:           files,
+			References:      refs,
+			DependsOn:       dependsOn,
+			Healthcheck:     healthcheck(svc.HealthCheck),
+			Profiles:        profiles,
 		})
 	}
 }
@@ -11,6 +11,7 @@ import (
 	"easydocker/runner/cmd/delete"
 	"easydocker/runner/cmd/images"
 	"easydocker/runner/cmd/list"
+	"easydocker/runner/cmd/protocol"
 	"easydocker/runner/cmd/start"
 	"easydocker/runner/cmd/stop"
 	"easydocker/runner/cmd/stream"
@@ -28,11 +29,13 @@ var rootCmd = &cobra.Command{
 	},
 }
 
-func Execute() {
+// Execute runs the command line and returns the code to exit with.
+func Execute() int {
 	if err := rootCmd.Execute(); err != nil {
 		fmt.Fprintln(os.Stderr, err)
-		os.Exit(1)
+		return 1
 	}
+	return protocol.ExitCode()
 }
 
 func init() {
@@ -4,6 +4,7 @@
 package cmd
 
 import (
+	"easydocker/runner/cmd/protocol"
 	"easydocker/runner/cmd/read"
 	runjob "easydocker/runner/cmd/run-job"
 	searchmatch "easydocker/runner/cmd/search-match"
@@ -28,6 +29,10 @@ var runCmd = &cobra.Command{
 * WIP -- Function
 * Reading Docker build system file
 * @param: main.go read {dockerfile, compose} $pathfile
+*
+* compose also takes a project directory, and then loads it the way
+* `docker compose` does with no -f: the first standard compose file found
+* plus its override file.
 * */
 var readCmd = &cobra.Command{
 	Use:   "read [type] [path]",
@@ -48,17 +53,24 @@ var readCmd = &cobra.Command{
 	},
 }
 
+var searchScope searchmatch.Scope
+
 var searchMatchesCmd = &cobra.Command{
-	Use:   "search $PREFIX",
+	Use:   "search [--root DIR]... [--max-depth N] [--ignore GLOB]... $NAME...",
 	Short: "Search matches files",
-	Args:  cobra.MaximumNArgs(1),
+	Args:  cobra.MinimumNArgs(1),
 	Run: func(cmd *cobra.Command, args []string) {
-		prefix := args[0]
-		searchmatch.SearchMatchesFile(prefix)
+		if _, err := searchmatch.SearchMatchesFile(searchScope, args); err != nil {
+			protocol.Fail("search failed", err)
+		}
 	},
 }
 
 func init() {
+	searchMatchesCmd.Flags().StringArrayVar(&searchScope.Roots, "root", nil, "directory to search (repeatable, default: home directory)")
+	searchMatchesCmd.Flags().IntVar(&searchScope.MaxDepth, "max-depth", 0, "directories to descend below a root, 0 for no limit")
+	searchMatchesCmd.Flags().StringArrayVar(&searchScope.Ignore, "ignore", nil, "glob of directories to skip (repeatable)")
+
 	rootCmd.AddCommand(runCmd)
 	rootCmd.AddCommand(readCmd)
 	rootCmd.AddCommand(searchMatchesCmd)
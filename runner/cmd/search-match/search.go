@@ -4,10 +4,11 @@
 package searchmatch
 
 import (
-	"encoding/json"
 	"os"
 	"path/filepath"
 	"strings"
+
+	"easydocker/runner/cmd/protocol"
 )
 
 type Results struct {
@@ -15,53 +16,101 @@ type Results struct {
 }
 
 func outputJSON(o Results) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(o)
+	protocol.Data(o)
 }
 
 /*
-* Searching matching file with prefix
-* @param SearchMatchesFile(string) -> nil, nil
+* Where and how deep to search
+* Roots: directories to walk; the home directory when empty
+* MaxDepth: directories below a root to descend into, 0 for no limit
+* Ignore: globs matched against a directory's name and its full path;
+* matching directories are not entered
+* */
+type Scope struct {
+	Roots    []string
+	MaxDepth int
+	Ignore   []string
+}
+
+func (s Scope) ignored(path string, name string) bool {
+	for _, pattern := range s.Ignore {
+		if ok, _ := filepath.Match(pattern, name); ok {
+			return true
+		}
+		if ok, _ := filepath.Match(pattern, path); ok {
+			return true
+		}
+	}
+	return false
+}
+
+func depth(root string, path string) int {
+	rel, err := filepath.Rel(root, path)
+	if err != nil || rel == "." {
+		return 0
+	}
+	return len(strings.Split(rel, string(filepath.Separator)))
+}
+
+/*
+* Searching directories holding a file named exactly one of the names
+* @param SearchMatchesFile(Scope, []string) -> nil, nil
+*
+* Each directory is reported once, however many of its files match or
+* however many roots reach it. Names are matched whole, so backups and
+* editor files (compose.yaml.bak, compose.yaml~) do not count.
 *
 * Output:
 * {
 *	 filepath: string
 *	}
 * */
-func SearchMatchesFile(prefix string) ([]string, error) {
-	home, err := os.UserHomeDir()
-	if err != nil {
-		return nil, err
-	}
-
-	parent := filepath.Dir(home)
-
-	users, err := os.ReadDir(parent)
-	if err != nil {
-		return nil, err
+func SearchMatchesFile(scope Scope, names []string) ([]string, error) {
+	roots := scope.Roots
+	if len(roots) == 0 {
+		home, err := os.UserHomeDir()
+		if err != nil {
+			return nil, err
+		}
+		roots = []string{home}
 	}
 
 	var results []string
+	seen := map[string]bool{}
 
-	for _, u := range users {
-		if !u.IsDir() {
-			continue
+	for _, root := range roots {
+		root, err := filepath.Abs(root)
+		if err != nil {
+			return nil, err
 		}
 
-		userHome := filepath.Join(parent, u.Name())
-
-		filepath.WalkDir(userHome, func(path string, d os.DirEntry, err error) error {
+		filepath.WalkDir(root, func(path string, d os.DirEntry, err error) error {
 			if err != nil {
 				return nil
 			}
 
 			if d.IsDir() {
+				if path == root {
+					return nil
+				}
+				if scope.ignored(path, d.Name()) {
+					return filepath.SkipDir
+				}
+				if scope.MaxDepth > 0 && depth(root, path) > scope.MaxDepth {
+					return filepath.SkipDir
+				}
 				return nil
 			}
 
-			if strings.HasPrefix(d.Name(), prefix) {
-				results = append(results, filepath.Dir(path))
-				return filepath.SkipDir
+			for _, name := range names {
+				if d.Name() == name {
+					dir := filepath.Dir(path)
+					if !seen[dir] {
+						seen[dir] = true
+						results = append(results, dir)
+					}
+					return filepath.SkipDir
+				}
 			}
 
 			return nil
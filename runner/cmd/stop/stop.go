@@ -5,7 +5,9 @@ package stop
 
 import (
 	"context"
-	"fmt"
+
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
 
 	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
@@ -17,6 +19,7 @@ var StopCmd = &cobra.Command{
 	Long:  `Stopping a stopped Docker container using container ID`,
 	Args:  cobra.ExactArgs(1),
 	Run: func(cmd *cobra.Command, args []string) {
+		defer protocol.Done()
 		containerID := args[0]
 		StopContainer(containerID)
 	},
@@ -24,18 +27,21 @@ var StopCmd = &cobra.Command{
 
 func StopContainer(containerID string) {
 	ctx := context.Background()
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
-		fmt.Printf("Container with ID %s has an error %s", containerID, err.Error())
+		protocol.Error("failed to create docker client", err)
+		protocol.Result(1, "error", containerID)
 		return
 	}
 	defer cli.Close()
 
+	protocol.Progress(protocol.StageStopping, "", containerID)
 	_, err = cli.ContainerStop(ctx, containerID, client.ContainerStopOptions{})
 	if err != nil {
-		fmt.Printf("Container with ID %s has an error %s", containerID, err.Error())
+		protocol.Error("failed to stop container "+containerID, err)
+		protocol.Result(1, "error", containerID)
 		return
 	}
 
-	fmt.Printf("Container with ID %s has been stopped", containerID)
+	protocol.Result(0, "stopped", containerID)
 }
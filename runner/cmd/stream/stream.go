@@ -6,9 +6,10 @@ package stream
 import (
 	"context"
 	"encoding/json"
-	"fmt"
 	"io"
-	"os"
+
+	"easydocker/runner/cmd/dockerclient"
+	"easydocker/runner/cmd/protocol"
 
 	"github.com/moby/moby/client"
 	"github.com/spf13/cobra"
@@ -20,7 +21,6 @@ type StatsOutput struct {
 	MemUsage       uint64  `json:"mem_usage"`
 	MemLimit       uint64  `json:"mem_limit"`
 	MemPercent     float64 `json:"mem_percent"`
-	Error          string  `json:"error,omitempty"`
 	Net_Rx         uint64  `json:"net_rx"`
 	Net_Tx         uint64  `json:"net_tx"`
 	DiskImages     uint64  `json:"disk_images"`
@@ -62,14 +62,14 @@ var StreamCmd = &cobra.Command{
 	Long:  `Stream real-time container statistics including CPU and memory usage`,
 	Args:  cobra.ExactArgs(1),
 	Run: func(cmd *cobra.Command, args []string) {
+		defer protocol.Done()
 		containerID := args[0]
 		streamStats(containerID)
 	},
 }
 
 func outputJSON(result StatsOutput) {
-	encoder := json.NewEncoder(os.Stdout)
-	encoder.Encode(result)
+	protocol.Data(result)
 }
 
 func getDiskUsage(cli *client.Client, ctx context.Context) (uint64, uint64, uint64, uint64) {
@@ -112,22 +112,16 @@ func getDiskUsage(cli *client.Client, ctx context.Context) (uint64, uint64, uint
 func streamStats(containerID string) {
 	ctx := context.Background()
 
-	cli, err := client.New(client.FromEnv)
+	cli, err := dockerclient.New()
 	if err != nil {
-		outputJSON(StatsOutput{
-			ContainerID: containerID,
-			Error:       fmt.Sprintf("failed to create docker client: %v", err),
-		})
+		protocol.Fail("failed to create docker client", err)
 		return
 	}
 	defer cli.Close()
 
 	stats, err := cli.ContainerStats(ctx, containerID, client.ContainerStatsOptions{Stream: true})
 	if err != nil {
-		outputJSON(StatsOutput{
-			ContainerID: containerID,
-			Error:       fmt.Sprintf("failed to get container stats: %v", err),
-		})
+		protocol.Fail("failed to get container stats", err)
 		return
 	}
 	defer stats.Body.Close()
@@ -143,10 +137,7 @@ func streamStats(containerID string) {
 			if err == io.EOF {
 				break
 			}
-			outputJSON(StatsOutput{
-				ContainerID: containerID,
-				Error:       fmt.Sprintf("failed to decode stats: %v", err),
-			})
+			protocol.Fail("failed to decode stats", err)
 			return
 		}
 
@@ -10,7 +10,7 @@ import (
 )
 
 var (
-	Version   = "1.0"
+	Version   = "5.0"
 	GitCommit = "unknown"
 )
 
@@ -4,9 +4,11 @@
 package main
 
 import (
+	"os"
+
 	"easydocker/runner/cmd"
 )
 
 func main() {
-	cmd.Execute()
+	os.Exit(cmd.Execute())
 }
@@ -1,29 +1,74 @@
 // Copyright 2026 Fitrian Musya
 // SPDX-License-Identifier: MIT
 
+use futures::StreamExt;
 use ratatui::widgets::ListState;
-use serde::Deserialize;
-use std::collections::VecDeque;
-use std::process::Stdio;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::JoinHandle;
+
+use crate::backend::context::{self, DockerContext};
+use crate::backend::runner::{self, RunnerBackend};
+use crate::backend::runtime::{self, Target};
+use crate::backend::{self, CreateOptions, DockerBackend, Job, Step};
+use crate::compose::{self, FileEdit, RawService};
+use crate::config::{self, Config};
+use crate::dotenv;
+use crate::event::{self, AppEvent, EventSender};
+use crate::form::Form;
+use crate::index::{self, Index};
+use crate::jobs::{JobManager, JobStatus};
+use crate::lint::{self, Problem};
 use crate::log::log::{LogList, LogType};
+use crate::ports::{self, Conflict, Owner};
+use crate::project::{self, ContainerRow, Project};
+use crate::protocol::{Message, Stage};
+use crate::secrets::Masker;
+use crate::watch;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tab {
     Containers,
     Images,
     Deployments,
+    Jobs,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MenuAction {
     BuildAndStart,
     Start,
     Stop,
     DeleteContainer,
+    UpAll,
+    DownAll,
+    RebuildAll,
+}
+
+const SERVICE_MENU: &[(&str, MenuAction)] = &[
+    ("Build & Start", MenuAction::BuildAndStart),
+    ("Start", MenuAction::Start),
+    ("Stop", MenuAction::Stop),
+    ("Delete Container", MenuAction::DeleteContainer),
+];
+
+const PROJECT_MENU: &[(&str, MenuAction)] = &[
+    ("Up All", MenuAction::UpAll),
+    ("Down All", MenuAction::DownAll),
+    ("Rebuild All", MenuAction::RebuildAll),
+];
+
+/// What the environment editor writes back to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvTarget {
+    /// The selected service's `environment` in its compose file.
+    Service,
+    /// A project's .env file.
+    DotEnv(PathBuf),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,7 +76,7 @@ pub enum ImageMenuAction {
     Delete,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DockerCompose {
     pub name: String,
     pub service: String,
@@ -43,8 +88,156 @@ pub struct DockerCompose {
     pub dockerfile: String,
     pub environment: Vec<String>,
     pub volumes: Vec<String>,
+    /// Host paths of the bind mounts among `volumes`.
+    #[serde(default)]
+    pub binds: Vec<String>,
     pub networks: Vec<String>,
+    /// Networks the project defines. None from runners that do not report
+    /// them.
+    #[serde(default)]
+    pub project_networks: Option<Vec<String>>,
+    /// Named volumes among `volumes`.
+    #[serde(default)]
+    pub named_volumes: Vec<String>,
+    /// Volumes the project defines. None from runners that do not report
+    /// them, as are the secrets and configs.
+    #[serde(default)]
+    pub project_volumes: Option<Vec<String>>,
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    #[serde(default)]
+    pub project_secrets: Option<Vec<String>>,
+    #[serde(default)]
+    pub configs: Vec<String>,
+    #[serde(default)]
+    pub project_configs: Option<Vec<String>>,
     pub restart: String,
+    /// Compose files the service was loaded from, overrides last.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Other files reading the project depends on: env_file, extended and
+    /// included files.
+    #[serde(default)]
+    pub references: Vec<String>,
+    #[serde(default)]
+    pub depends_on: Vec<Dependency>,
+    #[serde(default)]
+    pub healthcheck: Option<Healthcheck>,
+    /// Profiles that enable the service; empty when it is always enabled.
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Image, ports and environment as written, before interpolation.
+    #[serde(skip)]
+    pub raw: RawService,
+    /// What `${VAR}` resolves to in this service's project.
+    #[serde(skip)]
+    pub variables: Arc<HashMap<String, String>>,
+}
+
+/// One `depends_on` entry of a service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub service: String,
+    /// service_started, service_healthy or service_completed_successfully.
+    #[serde(default)]
+    pub condition: String,
+}
+
+/// A service's healthcheck as the compose files define it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Healthcheck {
+    /// `["CMD", ...]`, `["CMD-SHELL", "..."]` or `["NONE"]`.
+    pub test: Vec<String>,
+    /// Durations as Go prints them (`30s`, `1m30s`); empty when left to
+    /// Docker's default.
+    pub interval: String,
+    pub timeout: String,
+    pub start_period: String,
+    /// 0 when left to Docker's default.
+    pub retries: u64,
+    pub disable: bool,
+}
+
+impl Healthcheck {
+    /// The probe and its timing, Docker's defaults filled in.
+    pub fn describe(&self) -> String {
+        let command = match self.test.split_first() {
+            _ if self.disable => return "disabled".to_string(),
+            Some((kind, _)) if kind == "NONE" => return "disabled".to_string(),
+            Some((kind, args)) if kind == "CMD" || kind == "CMD-SHELL" => args.join(" "),
+            _ => self.test.join(" "),
+        };
+        let or = |value: &str, default: &str| {
+            if value.is_empty() {
+                default.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+        let retries = if self.retries == 0 { 3 } else { self.retries };
+        let mut timing = format!(
+            "every {}, timeout {}, {} retries",
+            or(&self.interval, "30s"),
+            or(&self.timeout, "30s"),
+            retries
+        );
+        if !self.start_period.is_empty() {
+            timing.push_str(&format!(", start period {}", self.start_period));
+        }
+        format!("{}  ({})", command, timing)
+    }
+}
+
+impl DockerCompose {
+    /// Name the container gets when created from this service.
+    pub fn target_name(&self) -> String {
+        if !self.container_name.is_empty() {
+            self.container_name.clone()
+        } else if !self.service.is_empty() {
+            format!("{}-{}", self.name, self.service)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// `raw` followed by what it interpolates to, when it uses a variable.
+    pub fn interpolated(&self, raw: &str) -> String {
+        if raw.contains('$') {
+            format!("{}  ⇒  {}", raw, dotenv::interpolate(raw, &self.variables))
+        } else {
+            raw.to_string()
+        }
+    }
+
+    /// `service` of the project in `dir` as the runner reports it, with
+    /// the JSON `fields` set on top.
+    #[cfg(test)]
+    pub fn sample(dir: &str, service: &str, fields: serde_json::Value) -> Self {
+        let name = Path::new(dir)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let mut json = serde_json::json!({
+            "name": name,
+            "service": service,
+            "image": "alpine",
+            "ports": "",
+            "container_name": "",
+            "hostname": "",
+            "build_context": "",
+            "dockerfile": "",
+            "environment": [],
+            "volumes": [],
+            "networks": [],
+            "restart": "",
+            "files": [format!("{}/compose.yaml", dir)],
+        });
+        for (key, value) in fields.as_object().into_iter().flatten() {
+            json[key] = value.clone();
+        }
+        serde_json::from_value(json).unwrap()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -56,55 +249,92 @@ pub struct DockerImage {
     pub size: String,
 }
 
-#[derive(Deserialize)]
-struct ImageJson {
-    repository: String,
-    tag: String,
-    image_id: String,
-    created: String,
-    size: String,
-}
-
-#[derive(Deserialize, Default)]
-#[serde(default)]
-pub struct ImageStatus {
-    pub image: String,
-    pub status: String,
-    pub progress: Option<String>,
-    pub error: String,
-}
-
-#[derive(Deserialize)]
-pub struct FilePath {
-    filepath: String,
-}
-
 #[derive(Debug, Clone, Deserialize)]
 pub struct RunningContainer {
     pub id: String,
     pub image: String,
+    // Reported by the backends but not shown anywhere yet.
+    #[allow(dead_code)]
     pub command: String,
+    #[allow(dead_code)]
     pub created: i64,
     pub status: String,
     pub state: String,
     pub ports: Option<Vec<ContainerPort>>,
     pub names: Vec<String>,
+    /// Only for containers with a healthcheck.
+    #[serde(default)]
+    pub health: Option<Health>,
+}
+
+/// A container's health and its last probe.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Health {
+    /// starting, healthy or unhealthy.
+    pub status: String,
+    /// Probes failed in a row.
+    pub failing_streak: u32,
+    pub exit_code: i64,
+    /// What the last probe printed.
+    pub output: String,
+}
+
+impl RunningContainer {
+    /// starting, healthy or unhealthy; None without a healthcheck. Falls
+    /// back to the status text ("Up 2 minutes (healthy)") when the backend
+    /// did not report the probes.
+    pub fn health_status(&self) -> Option<&str> {
+        if let Some(health) = &self.health
+            && !health.status.is_empty()
+        {
+            return Some(health.status.as_str());
+        }
+        if self.status.contains("(unhealthy)") {
+            Some("unhealthy")
+        } else if self.status.contains("(healthy)") {
+            Some("healthy")
+        } else if self.status.contains("(health: starting)") {
+            Some("starting")
+        } else {
+            None
+        }
+    }
+
+    pub fn is_unhealthy(&self) -> bool {
+        self.health_status() == Some("unhealthy")
+    }
+
+    /// Whether the container is named exactly `name`, the way containers
+    /// are matched to services everywhere in the app. The API reports names
+    /// with a leading `/`.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n.trim_start_matches('/') == name)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ContainerPort {
+    // Reported by the backends but not shown anywhere yet.
+    #[allow(dead_code)]
     pub ip: String,
+    #[allow(dead_code)]
     pub private_port: u16,
     pub public_port: u16,
+    #[allow(dead_code)]
     #[serde(rename = "type")]
     pub port_type: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct CPUUsage {
+    // Reported by the backends but not shown anywhere yet.
+    #[allow(dead_code)]
     pub container_id: String,
     pub cpu_percent: f64,
+    #[allow(dead_code)]
     pub mem_usage: f64,
+    #[allow(dead_code)]
     pub mem_limit: u64,
     pub mem_percent: f64,
     pub net_rx: u64,
@@ -122,34 +352,102 @@ pub struct NetData {
     pub net_tx: u64,
 }
 
-#[derive(Deserialize)]
-pub struct StartContainer {
-    pub container_id: String,
-    pub status: String,
+/// Result of a background fetch, one variant per tab.
+pub enum Fetched {
+    /// Services found, or why the search failed, plus compose files that
+    /// could not be read and how many projects were read rather than taken
+    /// from the index.
+    Containers(
+        anyhow::Result<Vec<DockerCompose>>,
+        Vec<anyhow::Error>,
+        usize,
+    ),
+    /// Services stored in the workspace index, shown until the search
+    /// lands.
+    Indexed(Vec<DockerCompose>),
+    Images(anyhow::Result<Vec<DockerImage>>),
+    Running(anyhow::Result<Vec<RunningContainer>>),
+}
+
+/// An action waiting for the list of every container, which it needs to
+/// find ids, check ports and see what already exists. Services and projects
+/// are named by directory so a reload in between cannot retarget them.
+pub enum Listing {
+    /// A service menu action: project directory, service, action.
+    Service(String, String, MenuAction),
+    /// A project menu action: project directory, action.
+    Project(String, MenuAction),
+    PortConflicts,
+}
+
+/// Optimistic list changes made when a job starts, undone if it fails.
+enum Rollback {
+    RemovedImage { index: usize, image: DockerImage },
 }
 
 const MAX_POINT: usize = 60;
 
+/// Compose projects read at the same time when the index is out of date.
+const READ_CONCURRENCY: usize = 8;
+
+/// File names `docker compose` looks for in a project directory, in its
+/// order of preference. Override files are picked up by the runner.
+pub const COMPOSE_FILE_NAMES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
 pub struct App {
+    pub config: Config,
+    // Masks secret values on screen; the log keeps a copy.
+    pub masker: Masker,
+    pub backend: Arc<dyn DockerBackend>,
+    pub target: Target,
+    runner_path: Option<PathBuf>,
+    // Set when the runner is missing or incompatible; blocks the whole UI.
+    pub runner_error: Option<String>,
     pub current_tab: Tab,
     pub containers: Vec<DockerCompose>,
+    pub projects: Vec<Project>,
+    // What the Containers list shows; `container_state` indexes this.
+    pub container_rows: Vec<ContainerRow>,
+    collapsed_projects: HashSet<String>,
     pub container_state: ListState,
+    // Selected service, None while a project row is selected.
     pub container_idx: Option<usize>,
-    pub loading: bool,
+    // Tabs with a fetch in flight.
+    loading: HashSet<Tab>,
+    // Bumped on context switch so fetches against the old host are dropped.
+    generation: u64,
+    // Projects reloaded while a workspace search was in flight; the search
+    // may have read them before they changed, so they are read again once
+    // it lands.
+    reloaded_during_search: BTreeSet<String>,
+    events: EventSender,
+    event_rx: Option<UnboundedReceiver<AppEvent>>,
+    // Reports compose file changes in the workspace; aborting it stops
+    // watching.
+    watcher: Option<JoinHandle<()>>,
     pub log: LogList,
     pub expanded_index: Option<usize>,
     pub menu_selection: usize,
-    pub details_state: bool,
     pub images: Vec<DockerImage>,
     pub image_state: ListState,
     pub image_idx: Option<usize>,
     pub image_expanded_index: Option<usize>,
     pub image_menu_selection: usize,
-    pub log_rx: Option<tokio::sync::mpsc::Receiver<String>>,
+    pub jobs: JobManager,
+    pub job_state: ListState,
+    rollbacks: HashMap<u64, Rollback>,
     pub log_scroll: u16,
 
     // Analytics
-    pub analytics_rx: Option<tokio::sync::mpsc::Receiver<CPUUsage>>,
+    // Forwards the current stats stream into `events`; aborting it drops
+    // the backend receiver, which ends the stream.
+    analytics_task: Option<JoinHandle<()>>,
+    analytics_stream: u64,
     pub analytics: CPUUsage,
     pub cpu_data: VecDeque<u64>,
     pub mem_data: VecDeque<u64>,
@@ -170,28 +468,82 @@ pub struct App {
     pub env_editor_selected: usize,
     pub env_editor_editing: bool,
     pub env_editor_buffer: String,
+    pub env_editor_target: EnvTarget,
+    // What the editor was opened with, to tell edits apart.
+    env_editor_original: Vec<String>,
+    // Lines whose secret value is shown.
+    pub env_editor_revealed: HashSet<usize>,
+    pub form: Option<Form>,
+    // Diffs waiting for confirmation before the files are written; empty
+    // when nothing is being saved.
+    pub save_preview: Vec<FileEdit>,
+    pub save_preview_scroll: u16,
+
+    pub context_picker_open: bool,
+    pub contexts: Vec<DockerContext>,
+    pub context_selected: usize,
+
+    pub port_conflicts_open: bool,
+    pub port_conflicts: Vec<Conflict>,
+
+    pub workspace_open: bool,
+    pub workspace_selected: usize,
+    // Text of a root being added, while the prompt is open.
+    pub workspace_input: Option<String>,
+
+    pub profiles_open: bool,
+    // Directory of the project whose profiles are being picked.
+    pub profiles_dir: String,
+    pub profiles_selected: usize,
+
+    pub lint_open: bool,
+    pub lint_problems: Vec<Problem>,
+    pub lint_state: ListState,
 }
 
 impl Default for App {
     fn default() -> Self {
+        Self::with_backend(Arc::new(RunnerBackend::default()))
+    }
+}
+
+impl App {
+    pub fn with_backend(backend: Arc<dyn DockerBackend>) -> Self {
+        let (events, event_rx) = event::channel();
         Self {
+            config: Config::default(),
+            masker: Masker::default(),
+            backend,
+            target: runtime::resolve(&Config::default()),
+            runner_path: None,
+            runner_error: None,
             current_tab: Tab::Containers,
             containers: Vec::new(),
+            projects: Vec::new(),
+            container_rows: Vec::new(),
+            collapsed_projects: HashSet::new(),
             container_state: ListState::default(),
-            container_idx: Some(0),
-            loading: false,
+            container_idx: None,
+            loading: HashSet::new(),
+            generation: 0,
+            reloaded_during_search: BTreeSet::new(),
+            events,
+            event_rx: Some(event_rx),
+            watcher: None,
             log: LogList::default(),
             expanded_index: None,
             menu_selection: 0,
-            details_state: false,
             images: Vec::new(),
             image_state: ListState::default(),
             image_idx: None,
             image_expanded_index: None,
             image_menu_selection: 0,
-            log_rx: None,
+            jobs: JobManager::default(),
+            job_state: ListState::default(),
+            rollbacks: HashMap::new(),
             log_scroll: 0,
-            analytics_rx: None,
+            analytics_task: None,
+            analytics_stream: 0,
             cpu_data: VecDeque::with_capacity(MAX_POINT),
             last_scroll: Instant::now(),
             scroll_offset: 0,
@@ -223,260 +575,886 @@ impl Default for App {
             env_editor_selected: 0,
             env_editor_editing: false,
             env_editor_buffer: String::new(),
+            env_editor_target: EnvTarget::Service,
+            env_editor_original: Vec::new(),
+            env_editor_revealed: HashSet::new(),
+            form: None,
+            save_preview: Vec::new(),
+            save_preview_scroll: 0,
+            context_picker_open: false,
+            contexts: Vec::new(),
+            context_selected: 0,
+            port_conflicts_open: false,
+            port_conflicts: Vec::new(),
+            workspace_open: false,
+            workspace_selected: 0,
+            workspace_input: None,
+            profiles_open: false,
+            profiles_dir: String::new(),
+            profiles_selected: 0,
+            lint_open: false,
+            lint_problems: Vec::new(),
+            lint_state: ListState::default(),
         }
     }
-}
 
-impl App {
-    pub async fn fetch_containers(&mut self) {
-        let mut results: Vec<FilePath> = vec![];
-        self.loading = true;
-        self.containers.clear();
-        if let Ok(output) = Command::new("easydocker-runner")
-            .args(["search", "docker-compose.yml"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-        {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if let Ok(parse) = serde_json::from_str::<FilePath>(line) {
-                    results.push(parse);
-                }
-            }
-        }
+    /// Takes `config` in, with the secret patterns it sets.
+    pub fn set_config(&mut self, config: Config) {
+        self.masker = Masker::new(&config.secrets);
+        self.config = config;
+        self.remember_secrets();
+    }
 
-        for file_path in &results {
-            let full_path = format!("{}/docker-compose.yml", file_path.filepath);
-            if let Ok(output) = Command::new("easydocker-runner")
-                .args(["read", "compose", &full_path])
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
+    /// Passes the secret values of the workspace's environments and .env
+    /// files on to the masker, and the masker on to the log.
+    fn remember_secrets(&mut self) {
+        let services = self
+            .containers
+            .iter()
+            .flat_map(|s| s.environment.iter().filter_map(|e| e.split_once('=')));
+        // Projects share their variables with every service.
+        let variables = self
+            .projects
+            .iter()
+            .filter_map(|p| p.services.iter().chain(&p.disabled).next())
+            .flat_map(|&s| self.containers[s].variables.iter())
+            .map(|(key, value)| (key.as_str(), value.as_str()));
+        self.masker.set_values(services.chain(variables));
+        self.log.set_masker(self.masker.clone());
+    }
+
+    /// Locates the runner and checks its version, rebuilding the backend
+    /// around it. Returns false (and sets `runner_error`) when it is unusable.
+    pub async fn connect_runner(&mut self) -> bool {
+        let result = match runner::locate(&self.config) {
+            Ok(path) => runner::handshake(&path)
                 .await
-            {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                for line in stdout.lines() {
-                    self.log.print_mes(LogType::Info, line);
-                    if let Ok(parsed) = serde_json::from_str::<DockerCompose>(line) {
-                        self.containers.push(parsed);
-                    }
-                }
+                .map(|version| (path, version)),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok((path, version)) => {
+                self.target = runtime::resolve(&self.config);
+                self.log.print_mes(
+                    LogType::Info,
+                    &format!(
+                        "Runner v{} at {}, using {} at {}",
+                        version,
+                        path.display(),
+                        self.target.runtime,
+                        self.target.endpoint.host()
+                    ),
+                );
+                self.backend = backend::build(&self.config, path.clone(), self.target.clone());
+                self.runner_path = Some(path);
+                self.runner_error = None;
+                true
+            }
+            Err(e) => {
+                self.runner_error = Some(e.to_string());
+                false
             }
         }
-
-        self.loading = false;
     }
 
-    pub async fn fetch_images(&mut self) {
-        self.loading = true;
-        self.images.clear();
+    pub fn open_context_picker(&mut self) {
+        self.contexts = context::list();
+        self.context_selected = self
+            .contexts
+            .iter()
+            .position(|c| c.name == self.target.context)
+            .unwrap_or(0);
+        self.context_picker_open = true;
+    }
 
-        if let Ok(output) = Command::new("easydocker-runner")
-            .args(["images"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-        {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if let Ok(parsed) = serde_json::from_str::<ImageJson>(line) {
-                    let image = DockerImage {
-                        repository: parsed.repository,
-                        tag: parsed.tag,
-                        image_id: parsed.image_id,
-                        created: parsed.created,
-                        size: parsed.size,
-                    };
-                    self.images.push(image);
-                }
-            }
+    pub fn context_next(&mut self) {
+        if self.context_selected + 1 < self.contexts.len() {
+            self.context_selected += 1;
         }
+    }
 
-        if self.images.is_empty() {
-            self.log.print_mes(LogType::Info, "No Docker images found");
-        } else {
-            self.log.print_mes(
-                LogType::Info,
-                &format!("Found {} images", self.images.len()),
-            );
-            self.image_state.select(Some(0));
-            self.image_idx = Some(0);
+    pub fn context_prev(&mut self) {
+        self.context_selected = self.context_selected.saturating_sub(1);
+    }
+
+    /*
+     * Switching docker context
+     *
+     * Everything tied to the old host goes: running jobs are cancelled and
+     * the job list cleared, the analytics stream stops, and fetches still in
+     * flight are ignored when they land. Compose projects are local files
+     * and stay; images and containers are fetched again from the new host.
+     * */
+    pub fn switch_context(&mut self) {
+        self.context_picker_open = false;
+        let Some(selected) = self.contexts.get(self.context_selected) else {
+            return;
+        };
+        if selected.name == self.target.context {
+            return;
         }
+        let Some(runner_path) = self.runner_path.clone() else {
+            return;
+        };
 
-        self.loading = false;
-    }
+        self.target = runtime::resolve_context(&self.config, &selected.name);
+        self.backend = backend::build(&self.config, runner_path, self.target.clone());
+        self.generation += 1;
+        self.loading.clear();
+        self.reloaded_during_search.clear();
 
-    pub async fn fetch_running_containers(&mut self) {
-        self.loading = true;
-        self.running_containers.clear();
+        self.jobs.clear();
+        self.job_state.select(None);
+        self.rollbacks.clear();
 
-        if let Ok(output) = Command::new("easydocker-runner")
-            .args(["list"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-        {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if let Ok(parsed) = serde_json::from_str::<RunningContainer>(line) {
-                    // Only include running containers
-                    if parsed.state == "running" {
-                        self.running_containers.push(parsed);
-                    }
-                }
-            }
+        if let Some(task) = self.analytics_task.take() {
+            task.abort();
         }
+        self.selected_container_id = None;
+        self.analytics = CPUUsage::default();
+        self.cpu_data.clear();
+        self.mem_data.clear();
+        self.net_data.clear();
 
-        if self.running_containers.is_empty() {
-            self.log
-                .print_mes(LogType::Info, "No running containers found");
-        } else {
-            self.log.print_mes(
-                LogType::Info,
-                &format!("Found {} running containers", self.running_containers.len()),
-            );
-            self.running_container_state.select(Some(0));
-            self.running_container_idx = Some(0);
-        }
+        self.images.clear();
+        clamp_selection(&mut self.image_state, &mut self.image_idx, 0);
+        self.image_expanded_index = None;
+        self.running_containers.clear();
+        clamp_selection(
+            &mut self.running_container_state,
+            &mut self.running_container_idx,
+            0,
+        );
+
+        self.log.print_mes(
+            LogType::Info,
+            &format!(
+                "Switched to context {} ({})",
+                self.target.context,
+                self.target.endpoint.host()
+            ),
+        );
+        self.fetch_images();
+        self.fetch_running_containers();
+    }
 
-        self.loading = false;
+    pub fn is_loading(&self, tab: Tab) -> bool {
+        self.loading.contains(&tab)
     }
 
-    pub fn select_next_running_container(&mut self) {
-        if self.running_containers.is_empty() {
+    /// Starts a background fetch for `tab` unless one is already running.
+    /// The current data stays on screen until `poll_fetches` applies the
+    /// result.
+    fn spawn_fetch<F>(&mut self, tab: Tab, fetch: F)
+    where
+        F: Future<Output = Fetched> + Send + 'static,
+    {
+        if !self.loading.insert(tab) {
             return;
         }
-        let i = match self.running_container_state.selected() {
-            Some(i) => {
-                if i >= self.running_containers.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.running_container_state.select(Some(i));
-        self.running_container_idx = Some(i);
+        let events = self.events.clone();
+        let generation = self.generation;
+        tokio::spawn(async move {
+            let _ = events.send(AppEvent::Fetched(generation, fetch.await));
+        });
     }
 
-    pub fn select_prev_running_container(&mut self) {
-        if self.running_containers.is_empty() {
-            return;
-        }
-        let i = match self.running_container_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.running_containers.len() - 1
-                } else {
-                    i - 1
-                }
+    /// Shows the services stored in the workspace index, if any, while
+    /// `fetch_containers` checks them. The index and the compose files
+    /// behind it are read in the background.
+    pub fn load_index(&mut self) {
+        let events = self.events.clone();
+        let generation = self.generation;
+        tokio::task::spawn_blocking(move || {
+            let containers: Vec<DockerCompose> = Index::load()
+                .projects
+                .into_iter()
+                .flat_map(|entry| annotate(&entry.dir, entry.services))
+                .collect();
+            if !containers.is_empty() {
+                let _ = events.send(AppEvent::Fetched(generation, Fetched::Indexed(containers)));
             }
-            None => self.running_containers.len() - 1,
-        };
-        self.running_container_state.select(Some(i));
-        self.running_container_idx = Some(i);
+        });
     }
 
-    pub fn select_running_container(&mut self) {
-        if let Some(idx) = self.running_container_idx {
-            if let Some(container) = self.running_containers.get(idx) {
-                let container_id = container.id.clone();
-                self.log.print_mes(
-                    LogType::Info,
-                    &format!("Selected container: {}", container_id),
-                );
-                // Stop current analytics stream if any
-                self.analytics_rx = None;
-                self.selected_container_id = Some(container_id);
+    /*
+     * Workspace search
+     *
+     * Searches the workspace roots, then takes each project from the index
+     * when none of its files changed since it was stored and reads the rest,
+     * several at a time, keeping the order the search found them in. The
+     * index is rewritten with the result.
+     * */
+    pub fn fetch_containers(&mut self) {
+        let backend = self.backend.clone();
+        let scope = self.config.workspace.clone();
+        self.spawn_fetch(Tab::Containers, async move {
+            let dirs = match backend.search(&scope, COMPOSE_FILE_NAMES).await {
+                Ok(dirs) => dirs,
+                Err(e) => return Fetched::Containers(Err(e), Vec::new(), 0),
+            };
+
+            let index = Arc::new(Index::load());
+            let read: Vec<_> = futures::stream::iter(dirs)
+                .map(|dir| {
+                    let backend = backend.clone();
+                    let index = index.clone();
+                    async move {
+                        if let Some(entry) = index.fresh(&dir) {
+                            return (Ok(entry.clone()), false);
+                        }
+                        let stamps = index::stamps(&dir);
+                        let read = backend.read_compose(&dir).await;
+                        (
+                            read.map(|services| index::Entry::new(dir, stamps, services)),
+                            true,
+                        )
+                    }
+                })
+                .buffered(READ_CONCURRENCY)
+                .collect()
+                .await;
+
+            let mut containers = Vec::new();
+            let mut errors = Vec::new();
+            let mut entries = Vec::new();
+            let mut reread = 0;
+            for (entry, was_read) in read {
+                match entry {
+                    Ok(entry) => {
+                        reread += usize::from(was_read);
+                        containers.extend(annotate(&entry.dir, entry.services.clone()));
+                        entries.push(entry);
+                    }
+                    Err(e) => errors.push(e),
+                }
             }
-        }
+            if let Err(e) = Index::save(entries) {
+                errors.push(e.context("Cannot update the workspace index"));
+            }
+            Fetched::Containers(Ok(containers), errors, reread)
+        });
     }
 
-    pub fn next_tab(&mut self) {
-        self.current_tab = match self.current_tab {
-            Tab::Containers => Tab::Images,
-            Tab::Images => Tab::Deployments,
-            Tab::Deployments => Tab::Containers,
-        };
+    pub fn fetch_images(&mut self) {
+        let backend = self.backend.clone();
+        self.spawn_fetch(Tab::Images, async move {
+            Fetched::Images(backend.images().await)
+        });
     }
 
-    pub fn prev_tab(&mut self) {
-        self.current_tab = match self.current_tab {
-            Tab::Containers => Tab::Deployments,
-            Tab::Images => Tab::Containers,
-            Tab::Deployments => Tab::Images,
-        };
+    pub fn fetch_running_containers(&mut self) {
+        let backend = self.backend.clone();
+        self.spawn_fetch(Tab::Deployments, async move {
+            Fetched::Running(backend.list_with_health().await)
+        });
     }
 
-    pub async fn delete(&mut self) {
-        self.containers.pop();
+    /// Hands out the receiving end of the app's event channel, once.
+    pub fn take_events(&mut self) -> Option<UnboundedReceiver<AppEvent>> {
+        self.event_rx.take()
     }
 
-    pub fn select_next_container(&mut self) {
-        if self.containers.is_empty() {
-            return;
-        }
-        let i = match self.container_state.selected() {
-            Some(i) => {
-                if i >= self.containers.len() - 1 {
-                    0
+    /// Applies something a background task reported.
+    pub fn handle_event(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::Job(id, message) => self.handle_message(id, message),
+            AppEvent::Fetched(generation, fetched) => {
+                if generation == self.generation {
+                    self.apply_fetched(fetched);
+                }
+            }
+            AppEvent::Analytics(stream, usage) => {
+                // Late samples from a stream that was already replaced.
+                if stream == self.analytics_stream && self.analytics_task.is_some() {
+                    // The task is kept so a broken stream is not restarted
+                    // on every frame; selecting another container retries.
+                    match usage {
+                        Ok(usage) => self.push_usage(usage),
+                        Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+                    }
+                }
+            }
+            AppEvent::Listed(generation, listing, result) => {
+                if generation == self.generation {
+                    self.apply_listing(listing, result);
+                }
+            }
+            AppEvent::ProjectsChanged(dirs) => self.reload_projects(dirs),
+            AppEvent::Reloaded(generation, dir, result) => {
+                if generation == self.generation {
+                    self.apply_reloaded(dir, result);
                 } else {
-                    i + 1
+                    // Read through the old backend; read it again.
+                    self.reload_projects(vec![dir]);
                 }
             }
-            None => 0,
-        };
-        self.container_state.select(Some(i));
-        self.container_idx = Some(i);
+            AppEvent::WatchError(message) => self.log.print_mes(LogType::Warning, &message),
+        }
     }
 
-    pub fn select_prev_container(&mut self) {
-        if self.containers.is_empty() {
-            return;
+    /// Starts watching the workspace roots for compose file changes,
+    /// replacing any watcher already running.
+    pub fn watch_workspace(&mut self) {
+        if let Some(task) = self.watcher.take() {
+            task.abort();
         }
-        let i = match self.container_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.containers.len() - 1
+        match watch::spawn(self.config.workspace.clone(), self.events.clone()) {
+            Ok(task) => self.watcher = Some(task),
+            Err(e) => self.log.print_mes(LogType::Warning, &format!("{:#}", e)),
+        }
+    }
+
+    /// Reads the projects in `dirs` again in the background; each one is
+    /// applied on its own as it lands.
+    fn reload_projects(&mut self, dirs: Vec<String>) {
+        let backend = self.backend.clone();
+        let events = self.events.clone();
+        let generation = self.generation;
+        tokio::spawn(async move {
+            for dir in dirs {
+                let has_compose = COMPOSE_FILE_NAMES
+                    .iter()
+                    .any(|name| Path::new(&dir).join(name).is_file());
+                let result = if has_compose {
+                    load_project(backend.as_ref(), &dir).await
                 } else {
-                    i - 1
-                }
+                    Ok(Vec::new())
+                };
+                let _ = events.send(AppEvent::Reloaded(generation, dir, result));
             }
-            None => self.containers.len() - 1,
-        };
-        self.container_state.select(Some(i));
-        self.container_idx = Some(i);
+        });
     }
 
-    pub fn select_next_image(&mut self) {
-        if self.images.is_empty() {
-            return;
+    /// The selected row as (project directory, service), which still means
+    /// the same thing after the service list is replaced.
+    fn selection_key(&self) -> Option<(String, Option<String>)> {
+        match self.selected_row()? {
+            ContainerRow::Service(idx) => {
+                let service = &self.containers[idx];
+                Some((project::project_dir(service), Some(service.service.clone())))
+            }
+            ContainerRow::Project(p) => Some((self.projects[p].dir.clone(), None)),
         }
-        let i = match self.image_state.selected() {
-            Some(i) => {
-                if i >= self.images.len() - 1 {
-                    0
-                } else {
-                    i + 1
+    }
+
+    /// Selects the row `key` named again, or its project when the service
+    /// is gone or has no row. True when the same row is selected as before.
+    fn restore_selection(&mut self, key: Option<(String, Option<String>)>) -> bool {
+        let Some((dir, service)) = key else {
+            return false;
+        };
+        let project = || {
+            self.projects
+                .iter()
+                .position(|p| p.dir == dir)
+                .map(ContainerRow::Project)
+        };
+        let (target, same) = match service {
+            Some(service) => match self.find_service(&dir, &service) {
+                Some(idx) if self.container_rows.contains(&ContainerRow::Service(idx)) => {
+                    (Some(ContainerRow::Service(idx)), true)
                 }
-            }
-            None => 0,
+                _ => (project(), false),
+            },
+            None => (project(), true),
         };
-        self.image_state.select(Some(i));
-        self.image_idx = Some(i);
+        let row = target.and_then(|t| self.container_rows.iter().position(|r| *r == t));
+        if row.is_some() {
+            self.container_state.select(row);
+        }
+        self.sync_container_idx();
+        same && row.is_some()
     }
 
-    pub fn select_prev_image(&mut self) {
-        if self.images.is_empty() {
+    fn find_service(&self, dir: &str, service: &str) -> Option<usize> {
+        self.containers
+            .iter()
+            .position(|s| project::project_dir(s) == dir && s.service == service)
+    }
+
+    /*
+     * Patching one project
+     *
+     * The services of the project in `dir` are replaced by `services` in
+     * place, or added at the end for a project not seen before. When `dir`
+     * itself is gone, so are the projects below it. The selected row, the
+     * open menu and the form stay on their service; an editor whose
+     * service went away is closed, and a pending save touching the project
+     * is dropped since its diff no longer matches the file. A project that
+     * cannot be read keeps its old services.
+     * */
+    fn apply_reloaded(&mut self, dir: String, result: anyhow::Result<Vec<DockerCompose>>) {
+        let services = match result {
+            Ok(services) => services,
+            Err(e) => {
+                self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                return;
+            }
+        };
+        if self.is_loading(Tab::Containers) {
+            self.reloaded_during_search.insert(dir.clone());
+        }
+        let gone = !Path::new(&dir).exists();
+        let old: Vec<usize> = (0..self.containers.len())
+            .filter(|&i| {
+                let project = project::project_dir(&self.containers[i]);
+                project == dir || (gone && Path::new(&project).starts_with(&dir))
+            })
+            .collect();
+        if old.is_empty() && services.is_empty() {
             return;
         }
-        let i = match self.image_state.selected() {
-            Some(i) => {
+        let summary = {
+            let before: Vec<&DockerCompose> = old.iter().map(|&i| &self.containers[i]).collect();
+            reload_summary(&dir, &before, &services)
+        };
+
+        // Where the selection and the form were, by project and service.
+        let selected = self.selection_key();
+        let form_key = self.form.as_ref().map(|f| {
+            let service = &self.containers[f.service];
+            (project::project_dir(service), service.service.clone())
+        });
+
+        let at = old.first().copied();
+        let mut containers: Vec<DockerCompose> = std::mem::take(&mut self.containers)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !old.contains(i))
+            .map(|(_, s)| s)
+            .collect();
+        let at = at.unwrap_or(containers.len());
+        containers.splice(at..at, services);
+        self.containers = containers;
+        self.rebuild_tree();
+
+        let had_service = selected.as_ref().is_some_and(|(_, s)| s.is_some());
+        let same_row = self.restore_selection(selected);
+        let service_gone = had_service && !same_row;
+        if self.expanded_index.is_some() {
+            self.expanded_index = same_row.then(|| self.container_state.selected()).flatten();
+            if self.expanded_index.is_none() {
+                self.menu_selection = 0;
+            }
+        }
+
+        if let Some((dir, service)) = form_key {
+            match self.find_service(&dir, &service) {
+                Some(idx) => {
+                    if let Some(form) = self.form.as_mut() {
+                        form.service = idx;
+                    }
+                }
+                None => {
+                    self.form = None;
+                    self.log.print_mes(
+                        LogType::Warning,
+                        &format!("Service {} is gone; closed the form", service),
+                    );
+                }
+            }
+        }
+        if self.env_editor_open && self.env_editor_target == EnvTarget::Service && service_gone {
+            self.close_env_editor();
+            self.log.print_mes(
+                LogType::Warning,
+                "The edited service is gone; closed the editor",
+            );
+        }
+        if self
+            .save_preview
+            .iter()
+            .any(|edit| edit.path.parent() == Some(Path::new(&dir)))
+        {
+            self.save_preview.clear();
+            self.log.print_mes(
+                LogType::Warning,
+                &format!("{} changed on disk; save again to see the new diff", dir),
+            );
+        }
+
+        if let Some(summary) = summary {
+            self.log.print_mes(LogType::Info, &summary);
+        }
+        if let Some(project) = self.projects.iter().find(|p| p.dir == dir)
+            && let Err(e) = project::start_order(&self.containers, project, &project.services)
+        {
+            self.log.print_mes(LogType::Error, &format!("{:#}", e));
+        }
+        // Both views point into the service list.
+        self.port_conflicts_open = false;
+        if self.lint_open {
+            self.open_lint();
+        }
+    }
+
+    /// Applies a finished background fetch. A failed fetch is logged and
+    /// leaves the previous data in place.
+    fn apply_fetched(&mut self, fetched: Fetched) {
+        match fetched {
+            Fetched::Containers(result, errors, reread) => {
+                self.loading.remove(&Tab::Containers);
+                let reloaded = std::mem::take(&mut self.reloaded_during_search);
+                for e in &errors {
+                    self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                }
+                match result {
+                    Ok(containers) => {
+                        let selected = self.selection_key();
+                        self.containers = containers;
+                        if errors.is_empty() {
+                            self.log.print_mes(
+                                LogType::Info,
+                                &format!(
+                                    "Loaded {} containers, {} project(s) read again",
+                                    self.containers.len(),
+                                    reread
+                                ),
+                            );
+                        }
+                        self.rebuild_tree();
+                        // A menu opened on the indexed list stays open on
+                        // the same row.
+                        if self.restore_selection(selected) && self.expanded_index.is_some() {
+                            self.expanded_index = self.container_state.selected();
+                        } else {
+                            self.expanded_index = None;
+                            self.menu_selection = 0;
+                        }
+                        for project in &self.projects {
+                            let services = &project.services;
+                            if let Err(e) =
+                                project::start_order(&self.containers, project, services)
+                            {
+                                self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                            }
+                        }
+                        // Both views point into the service list.
+                        self.port_conflicts_open = false;
+                        if self.lint_open {
+                            self.open_lint();
+                        }
+                        // Project rows summarise what is running.
+                        self.fetch_running_containers();
+                        // The search may have read these before they changed.
+                        if !reloaded.is_empty() {
+                            self.reload_projects(reloaded.into_iter().collect());
+                        }
+                    }
+                    Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+                }
+            }
+            Fetched::Indexed(containers) => {
+                // The search or a reload got there first.
+                if !self.containers.is_empty() {
+                    return;
+                }
+                self.containers = containers;
+                self.rebuild_tree();
+                self.log.print_mes(
+                    LogType::Info,
+                    &format!(
+                        "Showing {} indexed containers, checking for changes",
+                        self.containers.len()
+                    ),
+                );
+            }
+            Fetched::Images(result) => {
+                self.loading.remove(&Tab::Images);
+                match result {
+                    Ok(images) => {
+                        self.images = images;
+                        if self.images.is_empty() {
+                            self.log.print_mes(LogType::Info, "No Docker images found");
+                        } else {
+                            self.log.print_mes(
+                                LogType::Info,
+                                &format!("Found {} images", self.images.len()),
+                            );
+                        }
+                        clamp_selection(
+                            &mut self.image_state,
+                            &mut self.image_idx,
+                            self.images.len(),
+                        );
+                    }
+                    Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+                }
+            }
+            Fetched::Running(result) => {
+                self.loading.remove(&Tab::Deployments);
+                match result {
+                    Ok(containers) => {
+                        self.running_containers = containers
+                            .into_iter()
+                            // Only include running containers
+                            .filter(|c| c.state == "running")
+                            .collect();
+                        if self.running_containers.is_empty() {
+                            self.log
+                                .print_mes(LogType::Info, "No running containers found");
+                        } else {
+                            self.log.print_mes(
+                                LogType::Info,
+                                &format!(
+                                    "Found {} running containers",
+                                    self.running_containers.len()
+                                ),
+                            );
+                        }
+                        clamp_selection(
+                            &mut self.running_container_state,
+                            &mut self.running_container_idx,
+                            self.running_containers.len(),
+                        );
+                    }
+                    Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+                }
+            }
+        }
+    }
+
+    pub fn select_next_running_container(&mut self) {
+        if self.running_containers.is_empty() {
+            return;
+        }
+        let i = match self.running_container_state.selected() {
+            Some(i) => {
+                if i >= self.running_containers.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.running_container_state.select(Some(i));
+        self.running_container_idx = Some(i);
+    }
+
+    pub fn select_prev_running_container(&mut self) {
+        if self.running_containers.is_empty() {
+            return;
+        }
+        let i = match self.running_container_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.running_containers.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => self.running_containers.len() - 1,
+        };
+        self.running_container_state.select(Some(i));
+        self.running_container_idx = Some(i);
+    }
+
+    pub fn select_running_container(&mut self) {
+        if let Some(idx) = self.running_container_idx
+            && let Some(container) = self.running_containers.get(idx)
+        {
+            let container_id = container.id.clone();
+            self.log.print_mes(
+                LogType::Info,
+                &format!("Selected container: {}", container_id),
+            );
+            // Stop current analytics stream if any
+            if let Some(task) = self.analytics_task.take() {
+                task.abort();
+            }
+            self.selected_container_id = Some(container_id);
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.current_tab = match self.current_tab {
+            Tab::Containers => Tab::Images,
+            Tab::Images => Tab::Deployments,
+            Tab::Deployments => Tab::Jobs,
+            Tab::Jobs => Tab::Containers,
+        };
+    }
+
+    pub fn prev_tab(&mut self) {
+        self.current_tab = match self.current_tab {
+            Tab::Containers => Tab::Jobs,
+            Tab::Images => Tab::Containers,
+            Tab::Deployments => Tab::Images,
+            Tab::Jobs => Tab::Deployments,
+        };
+    }
+
+    pub fn delete(&mut self) {
+        self.containers.pop();
+        self.rebuild_tree();
+    }
+
+    /// Regroups `containers` into projects and rows, keeping the selection
+    /// where it was as far as the new rows allow.
+    fn rebuild_tree(&mut self) {
+        self.projects = project::group(&self.containers, |s| self.is_enabled(s));
+        self.container_rows = project::rows(&self.projects, &self.collapsed_projects);
+        self.remember_secrets();
+        let mut row = None;
+        clamp_selection(
+            &mut self.container_state,
+            &mut row,
+            self.container_rows.len(),
+        );
+        self.sync_container_idx();
+    }
+
+    fn sync_container_idx(&mut self) {
+        self.container_idx = match self.selected_row() {
+            Some(ContainerRow::Service(idx)) => Some(idx),
+            _ => None,
+        };
+    }
+
+    pub fn selected_row(&self) -> Option<ContainerRow> {
+        self.container_state
+            .selected()
+            .and_then(|i| self.container_rows.get(i).copied())
+    }
+
+    /// Project the selected row belongs to.
+    fn selected_project(&self) -> Option<usize> {
+        match self.selected_row()? {
+            ContainerRow::Project(p) => Some(p),
+            ContainerRow::Service(s) => self.project_of(s),
+        }
+    }
+
+    fn project_of(&self, service: usize) -> Option<usize> {
+        self.projects
+            .iter()
+            .position(|p| p.services.contains(&service))
+    }
+
+    /// Selects service `idx` on the Containers tab, unfolding its project.
+    pub fn select_service(&mut self, idx: usize) {
+        if let Some(p) = self.project_of(idx) {
+            self.collapsed_projects.remove(&self.projects[p].dir);
+        }
+        self.current_tab = Tab::Containers;
+        self.expanded_index = None;
+        self.menu_selection = 0;
+        self.container_rows = project::rows(&self.projects, &self.collapsed_projects);
+        let row = self
+            .container_rows
+            .iter()
+            .position(|r| *r == ContainerRow::Service(idx));
+        self.container_state.select(row);
+        self.sync_container_idx();
+    }
+
+    /// Folds or unfolds the selected project, leaving its row selected.
+    pub fn toggle_project(&mut self) {
+        let Some(p) = self.selected_project() else {
+            return;
+        };
+        let dir = self.projects[p].dir.clone();
+        if !self.collapsed_projects.remove(&dir) {
+            self.collapsed_projects.insert(dir);
+        }
+        self.expanded_index = None;
+        self.menu_selection = 0;
+        self.container_rows = project::rows(&self.projects, &self.collapsed_projects);
+        let row = self
+            .container_rows
+            .iter()
+            .position(|r| *r == ContainerRow::Project(p));
+        self.container_state.select(row);
+        self.sync_container_idx();
+    }
+
+    pub fn is_collapsed(&self, project: &Project) -> bool {
+        self.collapsed_projects.contains(&project.dir)
+    }
+
+    /// How many of the project's services have a running container.
+    pub fn running_services(&self, project: &Project) -> usize {
+        project
+            .services
+            .iter()
+            .filter_map(|&s| self.containers.get(s))
+            .filter(|service| self.is_running(service))
+            .count()
+    }
+
+    fn is_running(&self, service: &DockerCompose) -> bool {
+        self.container_of(service).is_some()
+    }
+
+    /// The container created from `service`, if there is one.
+    pub fn container_of(&self, service: &DockerCompose) -> Option<&RunningContainer> {
+        let target = service.target_name();
+        self.running_containers.iter().find(|c| c.has_name(&target))
+    }
+
+    pub fn select_next_container(&mut self) {
+        if self.container_rows.is_empty() {
+            return;
+        }
+        let i = match self.container_state.selected() {
+            Some(i) => {
+                if i >= self.container_rows.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.container_state.select(Some(i));
+        self.sync_container_idx();
+    }
+
+    pub fn select_prev_container(&mut self) {
+        if self.container_rows.is_empty() {
+            return;
+        }
+        let i = match self.container_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.container_rows.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => self.container_rows.len() - 1,
+        };
+        self.container_state.select(Some(i));
+        self.sync_container_idx();
+    }
+
+    pub fn select_next_image(&mut self) {
+        if self.images.is_empty() {
+            return;
+        }
+        let i = match self.image_state.selected() {
+            Some(i) => {
+                if i >= self.images.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.image_state.select(Some(i));
+        self.image_idx = Some(i);
+    }
+
+    pub fn select_prev_image(&mut self) {
+        if self.images.is_empty() {
+            return;
+        }
+        let i = match self.image_state.selected() {
+            Some(i) => {
                 if i == 0 {
                     self.images.len() - 1
                 } else {
@@ -526,61 +1504,44 @@ impl App {
         }
     }
 
-    pub async fn execute_image_menu_action(&mut self) {
-        if let Some(idx) = self.image_expanded_index {
-            if idx < self.images.len() {
-                let action = self.get_image_menu_action();
-                let image = self.images[idx].clone();
-
-                match action {
-                    Some(ImageMenuAction::Delete) => {
-                        self.log.print_mes(
-                            LogType::Info,
-                            &format!("Deleting image: {}:{}", image.repository, image.tag),
-                        );
-
-                        let image_id = image.image_id.clone();
-
-                        let (tx, rx) = tokio::sync::mpsc::channel::<String>(100);
-                        self.log_rx = Some(rx);
-                        self.loading = true;
-
-                        tokio::spawn(async move {
-                            if let Ok(mut child) = Command::new("easydocker-runner")
-                                .args(["rmi", image_id.as_str()])
-                                .stdout(Stdio::piped())
-                                .stderr(Stdio::piped())
-                                .spawn()
-                            {
-                                if let Some(stdout) = child.stdout.take() {
-                                    let mut reader = BufReader::new(stdout).lines();
-
-                                    while let Ok(Some(line)) = reader.next_line().await {
-                                        let _ = tx.send(line).await;
-                                    }
-                                }
-                                let _ = child.wait().await;
-                            }
-                            let _ = tx.send("__DONE__".to_string()).await;
-                        });
-
-                        self.images.remove(idx);
-                        if self.image_idx.is_some() && idx > 0 {
-                            self.image_idx = Some(idx - 1);
-                            self.image_state.select(Some(idx - 1));
-                        } else if !self.images.is_empty() {
-                            self.image_idx = Some(0);
-                            self.image_state.select(Some(0));
-                        } else {
-                            self.image_idx = None;
-                            self.image_state.select(None);
-                        }
+    pub fn execute_image_menu_action(&mut self) {
+        if let Some(idx) = self.image_expanded_index
+            && idx < self.images.len()
+        {
+            let action = self.get_image_menu_action();
+            let image = self.images[idx].clone();
+
+            match action {
+                Some(ImageMenuAction::Delete) => {
+                    self.log.print_mes(
+                        LogType::Info,
+                        &format!("Deleting image: {}:{}", image.repository, image.tag),
+                    );
+
+                    let id = self.start_job(
+                        format!("rmi {}:{}", image.repository, image.tag),
+                        self.backend.remove_image(&image.image_id),
+                    );
+
+                    // Optimistically drop it from the list; restored if rmi fails.
+                    self.images.remove(idx);
+                    self.rollbacks
+                        .insert(id, Rollback::RemovedImage { index: idx, image });
+                    if self.image_idx.is_some() && idx > 0 {
+                        self.image_idx = Some(idx - 1);
+                        self.image_state.select(Some(idx - 1));
+                    } else if !self.images.is_empty() {
+                        self.image_idx = Some(0);
+                        self.image_state.select(Some(0));
+                    } else {
+                        self.image_idx = None;
+                        self.image_state.select(None);
                     }
-                    None => {}
                 }
-                self.image_expanded_index = None;
-                self.image_menu_selection = 0;
+                None => {}
             }
+            self.image_expanded_index = None;
+            self.image_menu_selection = 0;
         }
     }
 
@@ -596,16 +1557,28 @@ impl App {
         }
     }
 
+    /// Entries of the open menu: project actions on a project row,
+    /// container actions on a service row.
+    pub fn menu_items(&self) -> &'static [(&'static str, MenuAction)] {
+        match self.expanded_index.and_then(|i| self.container_rows.get(i)) {
+            Some(ContainerRow::Project(_)) => PROJECT_MENU,
+            Some(ContainerRow::Service(_)) => SERVICE_MENU,
+            None => &[],
+        }
+    }
+
     pub fn menu_next(&mut self) {
-        if self.expanded_index.is_some() {
-            self.menu_selection = (self.menu_selection + 1) % 4;
+        let len = self.menu_items().len();
+        if len > 0 {
+            self.menu_selection = (self.menu_selection + 1) % len;
         }
     }
 
     pub fn menu_prev(&mut self) {
-        if self.expanded_index.is_some() {
+        let len = self.menu_items().len();
+        if len > 0 {
             self.menu_selection = if self.menu_selection == 0 {
-                3
+                len - 1
             } else {
                 self.menu_selection - 1
             };
@@ -613,396 +1586,761 @@ impl App {
     }
 
     pub fn get_menu_action(&self) -> Option<MenuAction> {
-        if self.expanded_index.is_some() {
-            Some(match self.menu_selection {
-                0 => MenuAction::BuildAndStart,
-                1 => MenuAction::Start,
-                2 => MenuAction::Stop,
-                3 => MenuAction::DeleteContainer,
-                _ => unreachable!(),
-            })
-        } else {
-            None
+        self.menu_items()
+            .get(self.menu_selection)
+            .map(|(_, action)| *action)
+    }
+
+    /// Looks up a container (running or not) among `existing` whose name
+    /// matches `target_name`, logging when there is none.
+    fn find_container(
+        &mut self,
+        existing: &[RunningContainer],
+        target_name: &str,
+    ) -> Option<RunningContainer> {
+        let found = existing.iter().find(|c| c.has_name(target_name)).cloned();
+        if found.is_none() {
+            self.log.print_mes(
+                LogType::Error,
+                &format!("Container not found: {}", target_name),
+            );
         }
+        found
     }
 
-    pub async fn execute_menu_action(&mut self) {
-        if let Some(idx) = self.expanded_index {
-            if idx < self.containers.len() {
-                let action = self.get_menu_action();
-                let container = self.containers[idx].clone();
+    /// Runs the selected menu entry. Every action needs the list of
+    /// containers, so it only logs what it is about to do here and finishes
+    /// in `apply_listing` once the list arrives.
+    pub fn execute_menu_action(&mut self) {
+        let row = self
+            .expanded_index
+            .and_then(|i| self.container_rows.get(i).copied());
+        if let Some(ContainerRow::Project(p)) = row {
+            if let Some(action) = self.get_menu_action() {
+                let dir = self.projects[p].dir.clone();
+                self.spawn_listing(Listing::Project(dir, action));
+            }
+        } else if let Some(ContainerRow::Service(idx)) = row
+            && idx < self.containers.len()
+            && let Some(action) = self.get_menu_action()
+        {
+            let container = &self.containers[idx];
+            let (verb, name) = match action {
+                MenuAction::BuildAndStart => ("Creating", container.name.clone()),
+                MenuAction::Start => ("Starting", container.target_name()),
+                MenuAction::Stop => ("Stopping", container.target_name()),
+                MenuAction::DeleteContainer => ("Deleting", container.target_name()),
+                MenuAction::UpAll | MenuAction::DownAll | MenuAction::RebuildAll => return,
+            };
+            let listing = Listing::Service(
+                project::project_dir(container),
+                container.service.clone(),
+                action,
+            );
+            self.log
+                .print_mes(LogType::Info, &format!("{} container: {}", verb, name));
+            if matches!(action, MenuAction::Stop | MenuAction::DeleteContainer) {
+                self.warn_dependents(idx);
+            }
+            self.spawn_listing(listing);
+        }
+        self.expanded_index = None;
+        self.menu_selection = 0;
+    }
 
-                match action {
-                    Some(MenuAction::BuildAndStart) => {
-                        self.log.print_mes(
-                            LogType::Info,
-                            &format!("Creating container: {}", container.name),
-                        );
+    /// Lists every container in the background and finishes `listing` with
+    /// the result, so the UI never waits on the engine.
+    fn spawn_listing(&mut self, listing: Listing) {
+        let backend = self.backend.clone();
+        let events = self.events.clone();
+        let generation = self.generation;
+        tokio::spawn(async move {
+            let result = backend.list().await;
+            let _ = events.send(AppEvent::Listed(generation, listing, result));
+        });
+    }
 
-                        let mut args = vec![
-                            "create".to_string(),
-                            "--image".to_string(),
-                            container.image.clone(),
-                            "--start".to_string(),
-                        ];
-
-                        if !container.container_name.is_empty() {
-                            args.push("--name".to_string());
-                            args.push(container.container_name.clone());
-                        } else if !container.service.is_empty() {
-                            args.push("--name".to_string());
-                            args.push(format!("{}-{}", container.name, container.service));
-                        }
+    fn apply_listing(&mut self, listing: Listing, result: anyhow::Result<Vec<RunningContainer>>) {
+        let existing = match result {
+            Ok(existing) => existing,
+            Err(e) => {
+                self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                return;
+            }
+        };
+        match listing {
+            Listing::Service(dir, service, action) => match self.find_service(&dir, &service) {
+                Some(idx) => self.execute_service_action(idx, action, &existing),
+                None => self.log.print_mes(
+                    LogType::Error,
+                    &format!("Service {} is no longer in {}", service, dir),
+                ),
+            },
+            Listing::Project(dir, action) => {
+                match self.projects.iter().position(|p| p.dir == dir) {
+                    Some(p) => self.execute_project_action(p, action, &existing),
+                    None => self
+                        .log
+                        .print_mes(LogType::Error, &format!("Project {} is gone", dir)),
+                }
+            }
+            Listing::PortConflicts => {
+                self.port_conflicts =
+                    ports::conflicts(&self.containers, &self.disabled_services(), &existing);
+                self.port_conflicts_open = true;
+            }
+        }
+    }
 
-                        if !container.hostname.is_empty() {
-                            args.push("--hostname".to_string());
-                            args.push(container.hostname.clone());
-                        }
+    fn execute_service_action(
+        &mut self,
+        idx: usize,
+        action: MenuAction,
+        existing: &[RunningContainer],
+    ) {
+        let container = self.containers[idx].clone();
+        let target_name = container.target_name();
+
+        match action {
+            MenuAction::BuildAndStart => {
+                if self.check_ports(&[idx], existing)
+                    && let Some(mut steps) = self.dependency_steps(idx, existing)
+                {
+                    let opts = CreateOptions::from_compose(&container);
+                    let backend = self.backend.clone();
+                    steps.push(Box::new(move || backend.create(&opts)));
+                    self.start_job(format!("create {}", target_name), Job::chain(steps));
+                }
+            }
+            MenuAction::Start => {
+                if let Some(found) = self.find_container(existing, &target_name)
+                    && self.check_ports(&[idx], existing)
+                    && let Some(mut steps) = self.dependency_steps(idx, existing)
+                {
+                    let backend = self.backend.clone();
+                    steps.push(Box::new(move || backend.start(&found.id)));
+                    self.start_job(format!("start {}", target_name), Job::chain(steps));
+                }
+            }
+            MenuAction::Stop => {
+                if let Some(found) = self.find_container(existing, &target_name) {
+                    self.start_job(
+                        format!("stop {}", target_name),
+                        self.backend.stop(&found.id),
+                    );
+                }
+            }
+            MenuAction::DeleteContainer => {
+                if let Some(found) = self.find_container(existing, &target_name) {
+                    self.start_job(
+                        format!("rm {}", target_name),
+                        self.backend.remove_container(&found.id),
+                    );
+                }
+            }
+            MenuAction::UpAll | MenuAction::DownAll | MenuAction::RebuildAll => {}
+        }
+    }
 
-                        if !container.ports.is_empty() {
-                            args.push("--ports".to_string());
-                            args.push(container.ports.clone());
-                        }
+    /*
+     * Project actions
+     *
+     * Each one becomes a single chained job over the project's services, so
+     * it shows up (and is cancelled) as one entry in the Jobs panel:
+     *   Up All      starts stopped containers and creates missing ones
+     *   Down All    removes every container of the project
+     *   Rebuild All removes existing containers, then builds and creates
+     *               every service again
+     * Up and Rebuild follow depends_on; Down goes the other way round.
+     * */
+    fn execute_project_action(
+        &mut self,
+        p: usize,
+        action: MenuAction,
+        existing: &[RunningContainer],
+    ) {
+        let Some(project) = self.projects.get(p).cloned() else {
+            return;
+        };
+        let order = match project::start_order(&self.containers, &project, &project.services) {
+            Ok(mut order) => {
+                if action == MenuAction::DownAll {
+                    order.reverse();
+                }
+                order
+            }
+            // Removing works in any order, so a cycle does not block it.
+            Err(_) if action == MenuAction::DownAll => project.services.clone(),
+            Err(e) => {
+                self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                return;
+            }
+        };
+        if action != MenuAction::DownAll && !self.check_ports(&order, existing) {
+            return;
+        }
 
-                        if !container.environment.is_empty() {
-                            args.push("--env".to_string());
-                            args.push(container.environment.join(","));
-                        }
+        let steps = match action {
+            MenuAction::DownAll => order
+                .iter()
+                .flat_map(|&s| self.service_steps(action, &self.containers[s], existing))
+                .collect(),
+            _ => self.up_steps(action, &order, &order, existing),
+        };
 
-                        if !container.volumes.is_empty() {
-                            args.push("--volumes".to_string());
-                            args.push(container.volumes.join(","));
-                        }
+        let verb = match action {
+            MenuAction::UpAll => "up",
+            MenuAction::DownAll => "down",
+            _ => "rebuild",
+        };
+        if steps.is_empty() {
+            self.log.print_mes(
+                LogType::Info,
+                &format!("Nothing to {} in project {}", verb, project.name),
+            );
+            return;
+        }
+
+        self.log.print_mes(
+            LogType::Info,
+            &format!(
+                "Project {}: {} ({} step(s))",
+                project.name,
+                verb,
+                steps.len()
+            ),
+        );
+        self.start_job(format!("{} {}", verb, project.name), Job::chain(steps));
+    }
+
+    /// Steps a project action takes for one service, given every container
+    /// that currently exists.
+    fn service_steps(
+        &self,
+        action: MenuAction,
+        service: &DockerCompose,
+        existing: &[RunningContainer],
+    ) -> Vec<Step> {
+        let target = service.target_name();
+        let found = existing.iter().find(|c| c.has_name(&target));
+        let backend = self.backend.clone();
+        let opts = CreateOptions::from_compose(service);
+
+        match (action, found) {
+            (MenuAction::UpAll, Some(c)) if c.state == "running" => Vec::new(),
+            (MenuAction::UpAll, Some(c)) => {
+                let id = c.id.clone();
+                vec![Box::new(move || backend.start(&id))]
+            }
+            (MenuAction::UpAll, None) | (MenuAction::RebuildAll, None) => {
+                vec![Box::new(move || backend.create(&opts))]
+            }
+            (MenuAction::DownAll, Some(c)) => {
+                let id = c.id.clone();
+                vec![Box::new(move || backend.remove_container(&id))]
+            }
+            (MenuAction::RebuildAll, Some(c)) => {
+                let id = c.id.clone();
+                let rm = backend.clone();
+                vec![
+                    Box::new(move || rm.remove_container(&id)),
+                    Box::new(move || backend.create(&opts)),
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Steps bringing up what service `idx` depends on, dependencies first,
+    /// given every container that currently exists. None, with the reason
+    /// logged, when depends_on has a cycle.
+    fn dependency_steps(&mut self, idx: usize, existing: &[RunningContainer]) -> Option<Vec<Step>> {
+        let Some(project) = self.project_of(idx).map(|p| self.projects[p].clone()) else {
+            return Some(Vec::new());
+        };
+        let order = match project::start_order(&self.containers, &project, &[idx]) {
+            Ok(order) => order,
+            Err(e) => {
+                self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                return None;
+            }
+        };
+        let deps: Vec<usize> = order.iter().copied().filter(|&s| s != idx).collect();
+        if deps.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let names: Vec<&str> = deps
+            .iter()
+            .map(|&s| self.containers[s].service.as_str())
+            .collect();
+        let message = format!("Bringing up dependencies first: {}", names.join(", "));
+        self.log.print_mes(LogType::Info, &message);
+
+        Some(self.up_steps(MenuAction::UpAll, &deps, &order, existing))
+    }
+
+    /// Steps for `action` (Up All or Rebuild All) over `services`, in that
+    /// order. After each one comes a wait when a service in `waiters`
+    /// depends on it with service_healthy or service_completed_successfully.
+    fn up_steps(
+        &self,
+        action: MenuAction,
+        services: &[usize],
+        waiters: &[usize],
+        existing: &[RunningContainer],
+    ) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for &s in services {
+            let service = &self.containers[s];
+            steps.extend(self.service_steps(action, service, existing));
+
+            let condition = waiters
+                .iter()
+                .flat_map(|&w| &self.containers[w].depends_on)
+                .find(|dep| {
+                    dep.service == service.service
+                        && matches!(
+                            dep.condition.as_str(),
+                            "service_healthy" | "service_completed_successfully"
+                        )
+                })
+                .map(|dep| dep.condition.clone());
+            if let Some(condition) = condition {
+                let backend = self.backend.clone();
+                let name = service.target_name();
+                steps.push(Box::new(move || Job::wait_for(backend, name, condition)));
+            }
+        }
+        steps
+    }
+
+    /// Warns before stopping or removing service `idx` while services that
+    /// depend on it are still running.
+    fn warn_dependents(&mut self, idx: usize) {
+        let Some(p) = self.project_of(idx) else {
+            return;
+        };
+        let running: Vec<&str> = project::dependents(&self.containers, &self.projects[p], idx)
+            .into_iter()
+            .map(|s| &self.containers[s])
+            .filter(|service| self.is_running(service))
+            .map(|service| service.service.as_str())
+            .collect();
+        if running.is_empty() {
+            return;
+        }
+        let message = format!(
+            "{} depend(s) on {} and {} still running",
+            running.join(", "),
+            self.containers[idx].service,
+            if running.len() == 1 { "is" } else { "are" }
+        );
+        self.log.print_mes(LogType::Warning, &message);
+    }
+
+    /*
+     * Port check before starting
+     *
+     * A host port of `services` that a running container holds, or that
+     * another service in `services` publishes too, would make the start
+     * fail halfway, so it blocks it. A port some stopped service elsewhere
+     * in the workspace also publishes is only a warning: it matters once
+     * both run.
+     * */
+    fn check_ports(&mut self, services: &[usize], existing: &[RunningContainer]) -> bool {
+        let disabled = self.disabled_services();
+        let mut blocked = false;
+        for &s in services {
+            for conflict in ports::conflicts_for(&self.containers, &disabled, existing, s) {
+                let blocks = conflict.claims.iter().any(|claim| {
+                    claim.running
+                        || matches!(claim.owner, Owner::Service(o) if services.contains(&o))
+                });
+                let others: Vec<String> = conflict
+                    .claims
+                    .iter()
+                    .map(|claim| ports::describe(&self.containers, &claim.owner))
+                    .collect();
+                let service = &self.containers[s];
+                if blocks {
+                    blocked = true;
+                    let message = format!(
+                        "Port {} of {} is already used by {}",
+                        conflict.port,
+                        service.service,
+                        others.join(", ")
+                    );
+                    self.log.print_mes(LogType::Error, &message);
+                } else {
+                    let message = format!(
+                        "Port {} of {} is also published by {}",
+                        conflict.port,
+                        service.service,
+                        others.join(", ")
+                    );
+                    self.log.print_mes(LogType::Warning, &message);
+                }
+            }
+        }
+        !blocked
+    }
+
+    /// Lists every host port claimed more than once in the workspace, once
+    /// the running containers are known.
+    pub fn open_port_conflicts(&mut self) {
+        self.spawn_listing(Listing::PortConflicts);
+    }
+
+    pub fn open_workspace(&mut self) {
+        self.workspace_selected = 0;
+        self.workspace_input = None;
+        self.workspace_open = true;
+    }
+
+    pub fn workspace_next(&mut self) {
+        if self.workspace_selected + 1 < self.config.workspace.roots.len() {
+            self.workspace_selected += 1;
+        }
+    }
+
+    pub fn workspace_prev(&mut self) {
+        self.workspace_selected = self.workspace_selected.saturating_sub(1);
+    }
+
+    /// Adds the root typed at the prompt, if it is a directory.
+    pub fn add_workspace_root(&mut self) {
+        let Some(input) = self.workspace_input.take() else {
+            return;
+        };
+        let root = workspace_root(&input);
+        if root.is_empty() {
+            return;
+        }
+        let expanded = config::expand_root(&root);
+        if !std::path::Path::new(&expanded).is_dir() {
+            self.log
+                .print_mes(LogType::Error, &format!("Not a directory: {}", expanded));
+            return;
+        }
+        if self.config.workspace.roots.contains(&root) {
+            return;
+        }
+        self.config.workspace.roots.push(root);
+        self.workspace_selected = self.config.workspace.roots.len() - 1;
+        self.workspace_changed();
+    }
+
+    pub fn remove_workspace_root(&mut self) {
+        let roots = &mut self.config.workspace.roots;
+        if self.workspace_selected >= roots.len() {
+            return;
+        }
+        roots.remove(self.workspace_selected);
+        self.workspace_selected = self.workspace_selected.min(roots.len().saturating_sub(1));
+        self.workspace_changed();
+    }
+
+    /// Keeps the new roots in the config file and searches them again.
+    fn workspace_changed(&mut self) {
+        if let Err(e) = self.config.save_workspace() {
+            self.log.print_mes(LogType::Warning, &format!("{:#}", e));
+        }
+        self.rescan();
+        self.watch_workspace();
+    }
+
+    /// Searches the workspace roots again for compose projects.
+    pub fn rescan(&mut self) {
+        let roots = match self.config.workspace.roots.as_slice() {
+            [] => "home directory".to_string(),
+            roots => roots.join(", "),
+        };
+        self.log
+            .print_mes(LogType::Info, &format!("Searching {}", roots));
+        self.fetch_containers();
+    }
+
+    /*
+     * Compose profiles
+     *
+     * A service with `profiles:` is only part of its project while one of
+     * them is active, as with `docker compose --profile`. The active set is
+     * picked per project and kept in the config file; a project never
+     * picked for follows `COMPOSE_PROFILES` from its .env or the
+     * environment, like compose does. Disabled services get no row and are
+     * left out of project actions, port checks and lint.
+     * */
+    pub fn active_profiles(&self, dir: &str) -> Vec<String> {
+        if let Some(active) = self.config.profiles.get(dir) {
+            return active.clone();
+        }
+        self.containers
+            .iter()
+            .find(|s| project::project_dir(s) == dir)
+            .and_then(|s| s.variables.get("COMPOSE_PROFILES"))
+            .map(|profiles| {
+                profiles
+                    .split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_enabled(&self, service: &DockerCompose) -> bool {
+        if service.profiles.is_empty() {
+            return true;
+        }
+        let active = self.active_profiles(&project::project_dir(service));
+        service
+            .profiles
+            .iter()
+            .any(|p| p == "*" || active.iter().any(|a| a == p || a == "*"))
+    }
+
+    /// Services no active profile enables, across the workspace.
+    fn disabled_services(&self) -> HashSet<usize> {
+        self.projects
+            .iter()
+            .flat_map(|p| p.disabled.iter().copied())
+            .collect()
+    }
+
+    /// Every profile of the project the picker is open for, with whether
+    /// it is active.
+    pub fn profile_choices(&self) -> Vec<(String, bool)> {
+        let Some(project) = self.projects.iter().find(|p| p.dir == self.profiles_dir) else {
+            return Vec::new();
+        };
+        let active = self.active_profiles(&project.dir);
+        project::profiles(&self.containers, project)
+            .into_iter()
+            .map(|p| {
+                let on = active.contains(&p);
+                (p, on)
+            })
+            .collect()
+    }
+
+    /// Opens the profile picker for the selected project.
+    pub fn open_profiles(&mut self) {
+        let Some(project) = self.selected_project().and_then(|p| self.projects.get(p)) else {
+            return;
+        };
+        if project::profiles(&self.containers, project).is_empty() {
+            self.log.print_mes(
+                LogType::Info,
+                &format!("Project {} has no profiles", project.name),
+            );
+            return;
+        }
+        self.profiles_dir = project.dir.clone();
+        self.profiles_selected = 0;
+        self.profiles_open = true;
+    }
 
-                        if !container.restart.is_empty() {
-                            args.push("--restart".to_string());
-                            args.push(container.restart.clone());
-                        }
+    pub fn profiles_next(&mut self) {
+        if self.profiles_selected + 1 < self.profile_choices().len() {
+            self.profiles_selected += 1;
+        }
+    }
 
-                        if !container.build_context.is_empty() {
-                            args.push("--build-context".to_string());
-                            args.push(container.build_context.clone());
-                        }
+    pub fn profiles_prev(&mut self) {
+        self.profiles_selected = self.profiles_selected.saturating_sub(1);
+    }
 
-                        let (tx, rx) = tokio::sync::mpsc::channel::<String>(100);
-                        self.log_rx = Some(rx);
-                        self.loading = true;
+    /// Turns the selected profile on or off, keeps the project's active
+    /// set in the config file and rebuilds the tree around the selection.
+    pub fn toggle_profile(&mut self) {
+        let choices = self.profile_choices();
+        let Some((profile, on)) = choices.get(self.profiles_selected) else {
+            return;
+        };
+        let mut active: Vec<String> = choices
+            .iter()
+            .filter(|(p, a)| *a && p != profile)
+            .map(|(p, _)| p.clone())
+            .collect();
+        if !on {
+            active.push(profile.clone());
+            active.sort();
+        }
+        let dir = self.profiles_dir.clone();
+        self.config.profiles.insert(dir.clone(), active.clone());
+        if let Err(e) = self.config.save_profiles() {
+            self.log.print_mes(LogType::Warning, &format!("{:#}", e));
+        }
 
-                        tokio::spawn(async move {
-                            if let Ok(mut child) = Command::new("easydocker-runner")
-                                .args(&args)
-                                .stdout(Stdio::piped())
-                                .stderr(Stdio::piped())
-                                .spawn()
-                            {
-                                if let Some(stdout) = child.stdout.take() {
-                                    let mut reader = BufReader::new(stdout).lines();
-
-                                    while let Ok(Some(line)) = reader.next_line().await {
-                                        let _ = tx.send(line).await;
-                                    }
-                                }
-                                let _ = child.wait().await;
-                            }
-                            let _ = tx.send("__DONE__".to_string()).await;
-                        });
-                    }
-                    Some(MenuAction::Start) => {
-                        let target_name = if !container.container_name.is_empty() {
-                            container.container_name.clone()
-                        } else if !container.service.is_empty() {
-                            format!("{}-{}", container.name, container.service)
-                        } else {
-                            container.name.clone()
-                        };
+        let selected = self.selection_key();
+        self.rebuild_tree();
+        if self.restore_selection(selected) && self.expanded_index.is_some() {
+            self.expanded_index = self.container_state.selected();
+        } else {
+            self.expanded_index = None;
+            self.menu_selection = 0;
+        }
 
-                        self.log.print_mes(
-                            LogType::Info,
-                            &format!("Starting container: {}", target_name),
-                        );
+        let name = self
+            .projects
+            .iter()
+            .find(|p| p.dir == dir)
+            .map(|p| p.name.clone())
+            .unwrap_or(dir);
+        let active = match active.as_slice() {
+            [] => "none".to_string(),
+            active => active.join(", "),
+        };
+        self.log.print_mes(
+            LogType::Info,
+            &format!("Active profiles of project {}: {}", name, active),
+        );
+    }
 
-                        if let Ok(output) = Command::new("easydocker-runner")
-                            .args(["list"])
-                            .stdout(Stdio::piped())
-                            .stderr(Stdio::piped())
-                            .output()
-                            .await
-                        {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            let mut found_container: Option<RunningContainer> = None;
-
-                            for line in stdout.lines() {
-                                if let Ok(parsed) = serde_json::from_str::<RunningContainer>(line) {
-                                    if parsed
-                                        .names
-                                        .iter()
-                                        .any(|n| n == &target_name || n.contains(&target_name))
-                                    {
-                                        found_container = Some(parsed);
-                                        break;
-                                    }
-                                }
-                            }
+    pub fn open_lint(&mut self) {
+        self.lint_problems = lint::check(&self.containers, &self.disabled_services());
+        self.lint_state
+            .select((!self.lint_problems.is_empty()).then_some(0));
+        self.lint_open = true;
+    }
 
-                            if let Some(parsed) = found_container {
-                                let (tx, rx) = tokio::sync::mpsc::channel::<String>(100);
-                                self.log_rx = Some(rx);
-                                self.loading = true;
-                                let container_id = parsed.id.clone();
-
-                                tokio::spawn(async move {
-                                    if let Ok(mut child) = Command::new("easydocker-runner")
-                                        .args(["start", container_id.as_str()])
-                                        .stdout(Stdio::piped())
-                                        .stderr(Stdio::piped())
-                                        .spawn()
-                                    {
-                                        if let Some(stdout) = child.stdout.take() {
-                                            let mut reader = BufReader::new(stdout).lines();
-
-                                            while let Ok(Some(line)) = reader.next_line().await {
-                                                let _ = tx.send(line).await;
-                                            }
-                                        }
-                                        let _ = child.wait().await;
-                                    }
-                                    let _ = tx.send("__DONE__".to_string()).await;
-                                });
-                            } else {
-                                self.log.print_mes(
-                                    LogType::Error,
-                                    &format!("Container not found: {}", target_name),
-                                );
-                            }
-                        }
-                    }
-                    Some(MenuAction::Stop) => {
-                        let target_name = if !container.container_name.is_empty() {
-                            container.container_name.clone()
-                        } else if !container.service.is_empty() {
-                            format!("{}-{}", container.name, container.service)
-                        } else {
-                            container.name.clone()
-                        };
+    pub fn lint_next(&mut self) {
+        let i = self.lint_state.selected().map_or(0, |i| i + 1);
+        if i < self.lint_problems.len() {
+            self.lint_state.select(Some(i));
+        }
+    }
 
-                        self.log.print_mes(
-                            LogType::Info,
-                            &format!("Stopping container: {}", target_name),
-                        );
+    pub fn lint_prev(&mut self) {
+        let i = self.lint_state.selected().unwrap_or(0);
+        self.lint_state.select(Some(i.saturating_sub(1)));
+    }
 
-                        if let Ok(output) = Command::new("easydocker-runner")
-                            .args(["list"])
-                            .stdout(Stdio::piped())
-                            .stderr(Stdio::piped())
-                            .output()
-                            .await
-                        {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            let mut found_container: Option<RunningContainer> = None;
-
-                            for line in stdout.lines() {
-                                if let Ok(parsed) = serde_json::from_str::<RunningContainer>(line) {
-                                    if parsed
-                                        .names
-                                        .iter()
-                                        .any(|n| n == &target_name || n.contains(&target_name))
-                                    {
-                                        found_container = Some(parsed);
-                                        break;
-                                    }
-                                }
-                            }
+    /// Closes the lint view on the service the selected problem is about.
+    pub fn jump_to_problem(&mut self) {
+        let Some(problem) = self
+            .lint_state
+            .selected()
+            .and_then(|i| self.lint_problems.get(i))
+        else {
+            return;
+        };
+        let service = problem.service;
+        self.lint_open = false;
+        self.select_service(service);
+    }
 
-                            if let Some(parsed) = found_container {
-                                let (tx, rx) = tokio::sync::mpsc::channel::<String>(100);
-                                self.log_rx = Some(rx);
-                                self.loading = true;
-                                let container_id = parsed.id.clone();
-
-                                tokio::spawn(async move {
-                                    if let Ok(mut child) = Command::new("easydocker-runner")
-                                        .args(["stop", container_id.as_str()])
-                                        .stdout(Stdio::piped())
-                                        .stderr(Stdio::piped())
-                                        .spawn()
-                                    {
-                                        if let Some(stdout) = child.stdout.take() {
-                                            let mut reader = BufReader::new(stdout).lines();
-
-                                            while let Ok(Some(line)) = reader.next_line().await {
-                                                let _ = tx.send(line).await;
-                                            }
-                                        }
-                                        let _ = child.wait().await;
-                                    }
-                                    let _ = tx.send("__DONE__".to_string()).await;
-                                });
-                            } else {
-                                self.log.print_mes(
-                                    LogType::Error,
-                                    &format!("Container not started: {}", target_name),
-                                );
-                            }
-                        }
-                    }
-                    Some(MenuAction::DeleteContainer) => {
-                        let target_name = if !container.container_name.is_empty() {
-                            container.container_name.clone()
-                        } else if !container.service.is_empty() {
-                            format!("{}-{}", container.name, container.service)
-                        } else {
-                            container.name.clone()
-                        };
+    /// Outside the Jobs tab the new job gets selected; on it the selection
+    /// stays on its job while spawning forgets older ones.
+    fn start_job(&mut self, label: String, job: Job) -> u64 {
+        let selected = self.job_state.selected();
+        let selected_id = selected
+            .and_then(|idx| self.jobs.jobs().get(idx))
+            .map(|job| job.id);
+        let id = self.jobs.spawn(label, job, &self.events);
+        let follow = if self.current_tab == Tab::Jobs {
+            selected_id
+        } else {
+            Some(id)
+        };
+        // The selected job itself can be forgotten; its neighbour takes over.
+        let idx = follow
+            .and_then(|id| self.jobs.position(id))
+            .or(selected.map(|idx| idx.min(self.jobs.len() - 1)));
+        self.job_state.select(idx);
+        id
+    }
 
-                        self.log.print_mes(
-                            LogType::Info,
-                            &format!("Deleting container: {}", target_name),
-                        );
+    /// Kills the selected job on the Jobs tab, or the most recent running
+    /// one elsewhere. Its output ends with an Error and Done like any other
+    /// failure, so rollbacks still apply.
+    pub fn cancel_job(&mut self) {
+        let target = if self.current_tab == Tab::Jobs {
+            self.job_state
+                .selected()
+                .and_then(|idx| self.jobs.jobs().get(idx))
+                .map(|job| job.id)
+        } else {
+            self.jobs.latest_running()
+        };
 
-                        if let Ok(output) = Command::new("easydocker-runner")
-                            .args(["list"])
-                            .stdout(Stdio::piped())
-                            .stderr(Stdio::piped())
-                            .output()
-                            .await
-                        {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            let mut found_container: Option<RunningContainer> = None;
-
-                            for line in stdout.lines() {
-                                if let Ok(parsed) = serde_json::from_str::<RunningContainer>(line) {
-                                    if parsed
-                                        .names
-                                        .iter()
-                                        .any(|n| n == &target_name || n.contains(&target_name))
-                                    {
-                                        found_container = Some(parsed);
-                                        break;
-                                    }
-                                }
-                            }
+        match target {
+            Some(id) if self.jobs.cancel(id) => self
+                .log
+                .print_mes(LogType::Warning, &format!("Cancelling job #{}...", id)),
+            _ => self.log.print_mes(LogType::Warning, "Nothing to cancel"),
+        }
+    }
 
-                            if let Some(parsed) = found_container {
-                                let (tx, rx) = tokio::sync::mpsc::channel::<String>(100);
-                                self.log_rx = Some(rx);
-                                self.loading = true;
-                                let container_id = parsed.id.clone();
-
-                                tokio::spawn(async move {
-                                    if let Ok(mut child) = Command::new("easydocker-runner")
-                                        .args(["rm", container_id.as_str()])
-                                        .stdout(Stdio::piped())
-                                        .stderr(Stdio::piped())
-                                        .spawn()
-                                    {
-                                        if let Some(stdout) = child.stdout.take() {
-                                            let mut reader = BufReader::new(stdout).lines();
-
-                                            while let Ok(Some(line)) = reader.next_line().await {
-                                                let _ = tx.send(line).await;
-                                            }
-                                        }
-                                        let _ = child.wait().await;
-                                    }
-                                    let _ = tx.send("__DONE__".to_string()).await;
-                                });
-                            } else {
-                                self.log.print_mes(
-                                    LogType::Error,
-                                    &format!("Container not found: {}", target_name),
-                                );
-                            }
-                        }
-                    }
-                    None => {}
+    fn finish_job(&mut self, id: u64, status: JobStatus) {
+        if status == JobStatus::Succeeded {
+            // Keeps the project summaries current.
+            self.fetch_running_containers();
+        }
+        let Some(rollback) = self.rollbacks.remove(&id) else {
+            return;
+        };
+        if status == JobStatus::Succeeded {
+            return;
+        }
+        match rollback {
+            Rollback::RemovedImage { index, image } => {
+                self.log.print_mes(
+                    LogType::Warning,
+                    &format!("Kept image {}:{}", image.repository, image.tag),
+                );
+                // A refresh while rmi ran has listed it again already.
+                if self.images.iter().any(|i| {
+                    i.image_id == image.image_id
+                        && i.repository == image.repository
+                        && i.tag == image.tag
+                }) {
+                    return;
                 }
-                self.expanded_index = None;
-                self.menu_selection = 0;
+                let index = index.min(self.images.len());
+                self.images.insert(index, image);
+                self.image_idx = Some(index);
+                self.image_state.select(Some(index));
             }
         }
     }
 
-    pub fn toggle_details(&mut self) {
-        self.details_state = true;
-        let i = format!("Boolean state: {}", self.details_state);
-        self.log.print_mes(LogType::Info, i.as_str());
-    }
-
-    pub fn poll_logs(&mut self) -> bool {
-        let mut has_updates = false;
-
-        if let Some(ref mut rx) = self.log_rx {
-            while let Ok(line) = rx.try_recv() {
-                has_updates = true;
+    fn handle_message(&mut self, id: u64, message: Message) {
+        let text = match describe(&message) {
+            Some((kind, text)) => {
+                self.log.print_mes(kind, &format!("#{} {}", id, text));
+                Some(text)
+            }
+            None => None,
+        };
 
-                if line == "__DONE__" {
-                    self.loading = false;
-                    self.log_rx = None;
-                    break;
-                }
+        if let Some(status) = self.jobs.record(id, &message, text) {
+            self.finish_job(id, status);
+        }
+    }
 
-                if let Ok(status) = serde_json::from_str::<ImageStatus>(&line) {
-                    match status.status.as_str() {
-                        "pulling" => {
-                            self.log.print_mes(
-                                LogType::Info,
-                                &format!("Pulling image: {}", status.image),
-                            );
-                        }
-                        "downloading" => {
-                            if let Some(progress) = &status.progress {
-                                self.log.print_mes(LogType::Info, progress);
-                            } else {
-                                self.log.print_mes(LogType::Info, "Downloading...");
-                            }
-                        }
-                        "completed" => {
-                            self.log.print_mes(
-                                LogType::Info,
-                                &format!("Pull complete: {}", status.image),
-                            );
-                        }
-                        "exists" => {
-                            self.log.print_mes(
-                                LogType::Info,
-                                &format!("Image exists: {}", status.image),
-                            );
-                        }
-                        "building" => {
-                            if let Some(progress) = &status.progress {
-                                self.log
-                                    .print_mes(LogType::Info, &format!("Building: {}", progress));
-                            } else {
-                                self.log.print_mes(
-                                    LogType::Info,
-                                    &format!("Building image: {}", status.image),
-                                );
-                            }
-                        }
-                        "error" => {
-                            self.log.print_mes(
-                                LogType::Error,
-                                &format!(
-                                    "Pull failed: {}. Please try to run Delete Container action!",
-                                    status.error
-                                ),
-                            );
-                        }
-                        "running" | "created" => {
-                            self.log
-                                .print_mes(LogType::Info, &format!("Container {}", status.status));
-                        }
-                        _ => {
-                            self.log.print_mes(LogType::Info, &line);
-                        }
-                    }
-                } else {
-                    self.log.print_mes(LogType::Info, &line);
-                }
-            }
+    pub fn select_next_job(&mut self) {
+        if self.jobs.is_empty() {
+            return;
         }
+        let i = match self.job_state.selected() {
+            Some(i) if i + 1 < self.jobs.len() => i + 1,
+            Some(i) => i,
+            None => 0,
+        };
+        self.job_state.select(Some(i));
+    }
 
-        has_updates
+    pub fn select_prev_job(&mut self) {
+        if self.jobs.is_empty() {
+            return;
+        }
+        let i = match self.job_state.selected() {
+            Some(i) => i.saturating_sub(1),
+            None => 0,
+        };
+        self.job_state.select(Some(i));
     }
 
     pub fn scroll_log_left(&mut self) {
@@ -1067,7 +2405,7 @@ impl App {
     }
 
     pub fn start_analytics_stream(&mut self, container_id: &str) {
-        if self.analytics_rx.is_some() {
+        if self.analytics_task.is_some() {
             return;
         }
 
@@ -1087,116 +2425,98 @@ impl App {
             });
         }
 
-        let (tx, rx) = tokio::sync::mpsc::channel::<CPUUsage>(100);
-        self.analytics_rx = Some(rx);
-
-        let container_id = container_id.to_string();
-
-        tokio::spawn(async move {
-            if let Ok(mut child) = Command::new("easydocker-runner")
-                .args(["stream", &container_id])
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-            {
-                if let Some(stdout) = child.stdout.take() {
-                    let mut reader = BufReader::new(stdout).lines();
-
-                    while let Ok(Some(line)) = reader.next_line().await {
-                        if let Ok(parsed) = serde_json::from_str::<CPUUsage>(&line) {
-                            if tx.send(parsed).await.is_err() {
-                                break; // Receiver dropped
-                            }
-                        }
-                    }
+        self.analytics_stream += 1;
+        let stream = self.analytics_stream;
+        let mut rx = self.backend.stream(container_id);
+        let events = self.events.clone();
+        self.analytics_task = Some(tokio::spawn(async move {
+            while let Some(usage) = rx.recv().await {
+                if events.send(AppEvent::Analytics(stream, usage)).is_err() {
+                    break;
                 }
-                let _ = child.wait().await;
             }
-        });
+        }));
     }
 
-    pub fn poll_analytics(&mut self) -> bool {
-        let mut updates = Vec::new();
+    /// Whether the analytics graph wants periodic `tick`s.
+    pub fn is_ticking(&self) -> bool {
+        self.analytics_task.is_some() && !self.cpu_data.is_empty()
+    }
 
-        if let Some(ref mut rx) = self.analytics_rx {
-            while let Ok(usage) = rx.try_recv() {
-                updates.push(usage);
-            }
-        }
+    fn push_usage(&mut self, usage: CPUUsage) {
+        let cpu_value = (usage.cpu_percent * 100.0).round() as u64;
+        self.cpu_push_data(cpu_value.max(1));
+        let mem_value = (usage.mem_percent * 100.0).round() as u64;
+        self.mem_push_data(mem_value.max(1));
 
-        let has_updates = !updates.is_empty();
+        let is_first_reading = self.last_net_rx == 0 && self.last_net_tx == 0;
 
-        if has_updates {
-            for usage in updates {
-                let cpu_value = (usage.cpu_percent * 100.0).round() as u64;
-                self.cpu_push_data(cpu_value.max(1));
-                let mem_value = (usage.mem_percent * 100.0).round() as u64;
-                self.mem_push_data(mem_value.max(1));
+        if !is_first_reading {
+            let rx_delta = usage.net_rx.saturating_sub(self.last_net_rx);
+            let tx_delta = usage.net_tx.saturating_sub(self.last_net_tx);
 
-                let is_first_reading = self.last_net_rx == 0 && self.last_net_tx == 0;
+            let rx_scaled = (rx_delta / 100).max(5);
+            let tx_scaled = (tx_delta / 100).max(5);
 
-                if !is_first_reading {
-                    let rx_delta = if usage.net_rx >= self.last_net_rx {
-                        usage.net_rx - self.last_net_rx
-                    } else {
-                        0
-                    };
-                    let tx_delta = if usage.net_tx >= self.last_net_tx {
-                        usage.net_tx - self.last_net_tx
-                    } else {
-                        0
-                    };
+            self.net_push_data(NetData {
+                net_rx: rx_scaled,
+                net_tx: tx_scaled,
+            });
+        }
 
-                    let rx_scaled = ((rx_delta / 100) as u64).max(5);
-                    let tx_scaled = ((tx_delta / 100) as u64).max(5);
+        self.last_net_rx = usage.net_rx;
+        self.last_net_tx = usage.net_tx;
 
-                    self.net_push_data(NetData {
-                        net_rx: rx_scaled,
-                        net_tx: tx_scaled,
-                    });
-                }
+        self.analytics = usage;
+        self.last_heartbeat = Instant::now();
+        self.trim_data();
+    }
 
-                self.last_net_rx = usage.net_rx;
-                self.last_net_tx = usage.net_tx;
+    /// Keeps the graph moving between samples. Returns whether anything
+    /// changed and needs a redraw.
+    pub fn tick(&mut self) -> bool {
+        if self.cpu_data.is_empty() {
+            return false;
+        }
+        let now = Instant::now();
+        if now.duration_since(self.last_heartbeat) < Duration::from_millis(500) {
+            return false;
+        }
 
-                self.analytics = usage;
-            }
-            self.last_heartbeat = Instant::now();
-        } else if !self.cpu_data.is_empty() {
-            let now = Instant::now();
-            if now.duration_since(self.last_heartbeat) >= Duration::from_millis(500) {
-                let last_cpu = *self.cpu_data_as_slice().last().unwrap_or(&1);
-                let last_mem = *self.mem_data_as_slice().last().unwrap_or(&1);
-
-                let variation = (last_cpu as i64 * (rand::random::<i64>() % 21 - 10)) / 100;
-                let new_cpu = (last_cpu as i64 + variation).max(1) as u64;
-
-                let mem_variation = (last_mem as i64 * (rand::random::<i64>() % 11 - 5)) / 100;
-                let new_mem = (last_mem as i64 + mem_variation).max(1) as u64;
-
-                let last_net = self.net_data_as_slice().last().cloned().unwrap_or(NetData {
-                    net_rx: 5,
-                    net_tx: 5,
-                });
-                let rx_base = last_net.net_rx.max(5);
-                let tx_base = last_net.net_tx.max(5);
-
-                let rx_var = (rx_base as i64 * (rand::random::<i64>() % 41 - 20)) / 100; // +/- 20%
-                let tx_var = (tx_base as i64 * (rand::random::<i64>() % 41 - 20)) / 100;
-                let new_rx = (rx_base as i64 + rx_var).max(5) as u64;
-                let new_tx = (tx_base as i64 + tx_var).max(5) as u64;
-
-                self.cpu_push_data(new_cpu);
-                self.mem_push_data(new_mem);
-                self.net_push_data(NetData {
-                    net_rx: new_rx,
-                    net_tx: new_tx,
-                });
+        let last_cpu = *self.cpu_data_as_slice().last().unwrap_or(&1);
+        let last_mem = *self.mem_data_as_slice().last().unwrap_or(&1);
 
-                self.last_heartbeat = now;
-            }
-        }
+        let variation = (last_cpu as i64 * (rand::random::<i64>() % 21 - 10)) / 100;
+        let new_cpu = (last_cpu as i64 + variation).max(1) as u64;
+
+        let mem_variation = (last_mem as i64 * (rand::random::<i64>() % 11 - 5)) / 100;
+        let new_mem = (last_mem as i64 + mem_variation).max(1) as u64;
+
+        let last_net = self.net_data_as_slice().last().cloned().unwrap_or(NetData {
+            net_rx: 5,
+            net_tx: 5,
+        });
+        let rx_base = last_net.net_rx.max(5);
+        let tx_base = last_net.net_tx.max(5);
+
+        let rx_var = (rx_base as i64 * (rand::random::<i64>() % 41 - 20)) / 100; // +/- 20%
+        let tx_var = (tx_base as i64 * (rand::random::<i64>() % 41 - 20)) / 100;
+        let new_rx = (rx_base as i64 + rx_var).max(5) as u64;
+        let new_tx = (tx_base as i64 + tx_var).max(5) as u64;
+
+        self.cpu_push_data(new_cpu);
+        self.mem_push_data(new_mem);
+        self.net_push_data(NetData {
+            net_rx: new_rx,
+            net_tx: new_tx,
+        });
+
+        self.last_heartbeat = now;
+        self.trim_data();
+        true
+    }
 
+    fn trim_data(&mut self) {
         // Limit data size to prevent memory overflow
         const MAX_DATA_POINTS: usize = 500;
         if self.cpu_data.len() > MAX_DATA_POINTS {
@@ -1211,23 +2531,51 @@ impl App {
             self.net_data
                 .drain(0..self.net_data.len() - MAX_DATA_POINTS);
         }
-
-        has_updates
     }
 
     pub fn open_env_editor(&mut self) {
-        if let Some(idx) = self.container_idx {
-            if let Some(ctn) = self.containers.get(idx) {
-                self.env_editor_lines = ctn.environment.clone();
-                if self.env_editor_lines.is_empty() {
-                    self.env_editor_lines.push(String::new());
-                }
-                self.env_editor_selected = 0;
-                self.env_editor_editing = false;
-                self.env_editor_buffer = String::new();
-                self.env_editor_open = true;
-            }
+        if let Some(idx) = self.container_idx
+            && let Some(ctn) = self.containers.get(idx)
+        {
+            let lines = ctn.environment.clone();
+            self.show_env_editor(EnvTarget::Service, lines);
+        }
+    }
+
+    /// Opens the .env file of the selected project in the environment
+    /// editor. A missing file is created on save.
+    pub fn open_dotenv_editor(&mut self) {
+        let Some(project) = self.selected_project().and_then(|p| self.projects.get(p)) else {
+            return;
+        };
+        if project
+            .services
+            .iter()
+            .all(|&s| self.containers[s].files.is_empty())
+        {
+            self.log.print_mes(
+                LogType::Warning,
+                &format!("Project {} has no known directory", project.name),
+            );
+            return;
+        }
+        let path = dotenv::path(&project.dir);
+        let lines = dotenv::entries(&path);
+        self.show_env_editor(EnvTarget::DotEnv(path), lines);
+    }
+
+    fn show_env_editor(&mut self, target: EnvTarget, lines: Vec<String>) {
+        self.env_editor_original = lines.clone();
+        self.env_editor_lines = lines;
+        if self.env_editor_lines.is_empty() {
+            self.env_editor_lines.push(String::new());
         }
+        self.env_editor_target = target;
+        self.env_editor_selected = 0;
+        self.env_editor_editing = false;
+        self.env_editor_buffer = String::new();
+        self.env_editor_revealed.clear();
+        self.env_editor_open = true;
     }
 
     pub fn close_env_editor(&mut self) {
@@ -1236,17 +2584,163 @@ impl App {
         self.env_editor_buffer = String::new();
     }
 
+    /// Works out the file change for the edited environment and opens its
+    /// diff for confirmation. Nothing is written yet.
     pub fn save_env_editor(&mut self) {
-        if let Some(idx) = self.container_idx {
-            if let Some(ctn) = self.containers.get_mut(idx) {
-                ctn.environment = self.env_editor_lines
-                    .iter()
-                    .filter(|s| !s.trim().is_empty())
-                    .cloned()
-                    .collect();
+        let updated: Vec<String> = self
+            .env_editor_lines
+            .iter()
+            .filter(|s| !s.trim().is_empty())
+            .cloned()
+            .collect();
+        if updated == self.env_editor_original {
+            self.log.print_mes(LogType::Info, "No changes to save");
+            self.close_env_editor();
+            return;
+        }
+
+        let planned = match &self.env_editor_target {
+            EnvTarget::DotEnv(path) => {
+                dotenv::plan(path, &self.env_editor_original, &updated).map(|edit| vec![edit])
+            }
+            EnvTarget::Service => {
+                let Some(ctn) = self.container_idx.and_then(|idx| self.containers.get(idx)) else {
+                    self.close_env_editor();
+                    return;
+                };
+                compose::plan_environment(
+                    &ctn.files,
+                    &ctn.service,
+                    &self.env_editor_original,
+                    &updated,
+                )
+            }
+        };
+        match planned {
+            Ok(edits) if edits.is_empty() => {
+                self.log.print_mes(LogType::Info, "No changes to save");
+                self.close_env_editor();
+            }
+            Ok(edits) => {
+                self.save_preview = edits;
+                self.save_preview_scroll = 0;
+            }
+            Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+        }
+    }
+
+    /// Writes the previewed changes, keeping a backup of each file, and
+    /// reloads the projects from disk. Stops at the first file that cannot
+    /// be written; when that is the first one the editor stays open.
+    pub fn confirm_save(&mut self) {
+        let mut written = Vec::new();
+        for edit in std::mem::take(&mut self.save_preview) {
+            match compose::write_with_backup(&edit.path, &edit.contents) {
+                Ok(backup) => {
+                    let message = match backup {
+                        Some(backup) => format!(
+                            "Saved {} (backup at {})",
+                            edit.path.display(),
+                            backup.display()
+                        ),
+                        None => format!("Created {}", edit.path.display()),
+                    };
+                    self.log.print_mes(LogType::Info, &message);
+                    if let Some(dir) = edit.path.parent() {
+                        written.push(dir.display().to_string());
+                    }
+                    if !edit.kept.is_empty() {
+                        self.log.print_mes(
+                            LogType::Warning,
+                            &format!(
+                                "Not set in {}, left as is: {}",
+                                edit.path.display(),
+                                edit.kept.join(", ")
+                            ),
+                        );
+                    }
+                }
+                Err(e) => {
+                    self.log.print_mes(LogType::Error, &format!("{:#}", e));
+                    break;
+                }
             }
         }
+        if written.is_empty() {
+            return;
+        }
         self.close_env_editor();
+        self.form = None;
+        written.dedup();
+        self.reload_projects(written);
+    }
+
+    /// Back to the editor without writing.
+    pub fn cancel_save(&mut self) {
+        self.save_preview.clear();
+    }
+
+    pub fn save_preview_scroll_down(&mut self) {
+        let len = self
+            .save_preview
+            .iter()
+            .map(|e| e.diff.len())
+            .sum::<usize>() as u16;
+        if self.save_preview_scroll + 1 < len {
+            self.save_preview_scroll += 1;
+        }
+    }
+
+    pub fn save_preview_scroll_up(&mut self) {
+        self.save_preview_scroll = self.save_preview_scroll.saturating_sub(1);
+    }
+
+    /// Opens the form editor on the selected service.
+    pub fn open_form(&mut self) {
+        if let Some(idx) = self.container_idx
+            && let Some(ctn) = self.containers.get(idx)
+        {
+            let mut form = Form::new(idx, &ctn.raw, ctn.variables.clone());
+            form.validate();
+            self.form = Some(form);
+        }
+    }
+
+    /// Validates the form and opens the diff of the files it changes.
+    pub fn save_form(&mut self) {
+        let Some(form) = self.form.as_mut() else {
+            return;
+        };
+        if !form.validate() {
+            let count = form.errors.len();
+            self.log.print_mes(
+                LogType::Error,
+                &format!("{} field(s) are invalid; fix them before saving", count),
+            );
+            return;
+        }
+        let changes = form.changes();
+        let Some(ctn) = self.containers.get(form.service) else {
+            self.form = None;
+            return;
+        };
+        if changes.is_empty() {
+            self.log.print_mes(LogType::Info, "No changes to save");
+            self.form = None;
+            return;
+        }
+
+        match compose::plan_service(&ctn.files, &ctn.service, &changes) {
+            Ok(edits) if edits.is_empty() => {
+                self.log.print_mes(LogType::Info, "No changes to save");
+                self.form = None;
+            }
+            Ok(edits) => {
+                self.save_preview = edits;
+                self.save_preview_scroll = 0;
+            }
+            Err(e) => self.log.print_mes(LogType::Error, &format!("{:#}", e)),
+        }
     }
 
     pub fn env_editor_move_up(&mut self) {
@@ -1279,6 +2773,11 @@ impl App {
     pub fn env_editor_add_line(&mut self) {
         let insert_at = self.env_editor_selected + 1;
         self.env_editor_lines.insert(insert_at, String::new());
+        self.env_editor_revealed = self
+            .env_editor_revealed
+            .iter()
+            .map(|&i| if i >= insert_at { i + 1 } else { i })
+            .collect();
         self.env_editor_selected = insert_at;
         self.env_editor_buffer = String::new();
         self.env_editor_editing = true;
@@ -1286,7 +2785,14 @@ impl App {
 
     pub fn env_editor_delete_line(&mut self) {
         if !self.env_editor_lines.is_empty() {
-            self.env_editor_lines.remove(self.env_editor_selected);
+            let removed = self.env_editor_selected;
+            self.env_editor_lines.remove(removed);
+            self.env_editor_revealed = self
+                .env_editor_revealed
+                .iter()
+                .filter(|&&i| i != removed)
+                .map(|&i| if i > removed { i - 1 } else { i })
+                .collect();
             if self.env_editor_lines.is_empty() {
                 self.env_editor_lines.push(String::new());
             }
@@ -1296,6 +2802,14 @@ impl App {
         }
     }
 
+    /// Shows or masks the secret value of the selected line.
+    pub fn env_editor_toggle_reveal(&mut self) {
+        let i = self.env_editor_selected;
+        if !self.env_editor_revealed.remove(&i) {
+            self.env_editor_revealed.insert(i);
+        }
+    }
+
     pub fn env_editor_input_char(&mut self, c: char) {
         self.env_editor_buffer.push(c);
     }
@@ -1304,3 +2818,264 @@ impl App {
         self.env_editor_buffer.pop();
     }
 }
+
+/// A root as typed at the prompt, without its trailing slashes unless it
+/// is `/` itself.
+fn workspace_root(input: &str) -> String {
+    let input = input.trim();
+    match input.trim_end_matches('/') {
+        "" if input.starts_with('/') => "/".to_string(),
+        root => root.to_string(),
+    }
+}
+
+/// Turns a job message into a log line, or None for messages that carry
+/// nothing to show (Done, and failed Results already reported by an Error).
+fn describe(message: &Message) -> Option<(LogType, String)> {
+    match message {
+        Message::Progress {
+            stage,
+            image,
+            detail,
+        } => {
+            let text = match stage {
+                Stage::Pulling => format!("Pulling image: {}", image),
+                Stage::Downloading if detail.is_empty() => "Downloading...".to_string(),
+                Stage::Downloading => detail.clone(),
+                Stage::Exists => format!("Image exists: {}", image),
+                Stage::Building if detail.is_empty() => format!("Building image: {}", image),
+                Stage::Building => format!("Building: {}", detail),
+                Stage::Completed => format!("Image ready: {}", image),
+                Stage::Creating => format!("Creating container: {}", detail),
+                Stage::Starting => format!("Starting container: {}", detail),
+                Stage::Stopping => format!("Stopping container: {}", detail),
+                Stage::Removing if image.is_empty() => format!("Removing: {}", detail),
+                Stage::Removing => format!("Removing: {}", image),
+                Stage::Unknown => format!("Working... {} {}", image, detail),
+            };
+            Some((LogType::Info, text))
+        }
+        Message::Log { line } => Some((LogType::Info, line.clone())),
+        Message::Result {
+            exit_code, status, ..
+        } if *exit_code == 0 => Some((LogType::Info, format!("Finished: {}", status))),
+        Message::Error { message, cause } if cause.is_empty() => {
+            Some((LogType::Error, message.clone()))
+        }
+        Message::Error { message, cause } => {
+            Some((LogType::Error, format!("{}: {}", message, cause)))
+        }
+        Message::Result { .. } | Message::Done => None,
+    }
+}
+
+/// Keeps a list selection in range after the list was replaced.
+fn clamp_selection(state: &mut ListState, idx: &mut Option<usize>, len: usize) {
+    let selected = match state.selected() {
+        _ if len == 0 => None,
+        Some(i) => Some(i.min(len - 1)),
+        None => Some(0),
+    };
+    state.select(selected);
+    *idx = selected;
+}
+
+/// Services of the project in `dir`, as `annotate` leaves them.
+async fn load_project(
+    backend: &dyn DockerBackend,
+    dir: &str,
+) -> anyhow::Result<Vec<DockerCompose>> {
+    let services = backend.read_compose(dir).await?;
+    Ok(annotate(dir, services))
+}
+
+/// Adds what the runner does not report to the services of the project in
+/// `dir`: their fields as written and the variables they interpolate.
+fn annotate(dir: &str, mut services: Vec<DockerCompose>) -> Vec<DockerCompose> {
+    let variables = Arc::new(dotenv::variables(dir));
+    for service in &mut services {
+        service.raw = compose::raw_service(&service.files, &service.service);
+        service.variables = variables.clone();
+    }
+    services
+}
+
+/// What reading the project in `dir` again changed, as a log line; None
+/// when nothing did.
+fn reload_summary(dir: &str, before: &[&DockerCompose], after: &[DockerCompose]) -> Option<String> {
+    if before.is_empty() {
+        return Some(format!(
+            "Found project {} in {} ({} services)",
+            after[0].name,
+            dir,
+            after.len()
+        ));
+    }
+    if after.is_empty() {
+        let mut names: Vec<&str> = before.iter().map(|s| s.name.as_str()).collect();
+        names.dedup();
+        return Some(format!("Project {} in {} is gone", names.join(", "), dir));
+    }
+
+    let added: Vec<&str> = after
+        .iter()
+        .filter(|s| !before.iter().any(|b| b.service == s.service))
+        .map(|s| s.service.as_str())
+        .collect();
+    let removed: Vec<&str> = before
+        .iter()
+        .filter(|b| !after.iter().any(|s| s.service == b.service))
+        .map(|b| b.service.as_str())
+        .collect();
+    let updated: Vec<&str> = after
+        .iter()
+        .filter(|s| before.iter().any(|b| b.service == s.service && *b != *s))
+        .map(|s| s.service.as_str())
+        .collect();
+
+    let changes: Vec<String> = [("added", added), ("removed", removed), ("updated", updated)]
+        .into_iter()
+        .filter(|(_, services)| !services.is_empty())
+        .map(|(what, services)| format!("{} {}", what, services.join(", ")))
+        .collect();
+    if changes.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Project {} changed: {}",
+        after[0].name,
+        changes.join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+    use serde_json::json;
+
+    /// Applies what background tasks report until they go quiet.
+    async fn settle(app: &mut App, events: &mut UnboundedReceiver<AppEvent>) {
+        let quiet = Duration::from_millis(200);
+        while let Ok(Some(event)) = tokio::time::timeout(quiet, events.recv()).await {
+            app.handle_event(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn project_actions_go_through_the_backend_in_dependency_order() {
+        let fake = Arc::new(FakeBackend::default());
+        let mut app = App::with_backend(fake.clone());
+        let mut events = app.take_events().unwrap();
+        let services = vec![
+            DockerCompose::sample(
+                "/srv/app",
+                "web",
+                json!({"depends_on": [{"service": "db", "condition": "service_started"}]}),
+            ),
+            DockerCompose::sample("/srv/app", "db", json!({"container_name": "app-database"})),
+        ];
+        app.handle_event(AppEvent::Fetched(
+            app.generation,
+            Fetched::Indexed(services),
+        ));
+        let project = |action| Listing::Project("/srv/app".to_string(), action);
+
+        app.spawn_listing(project(MenuAction::UpAll));
+        settle(&mut app, &mut events).await;
+        assert_eq!(fake.calls(), ["create app-database", "create app-web"]);
+        assert_eq!(app.running_containers.len(), 2);
+
+        // Both run now, so there is nothing left to bring up.
+        app.spawn_listing(project(MenuAction::UpAll));
+        settle(&mut app, &mut events).await;
+        assert_eq!(fake.calls().len(), 2);
+
+        app.spawn_listing(project(MenuAction::DownAll));
+        settle(&mut app, &mut events).await;
+        assert_eq!(fake.calls()[2..], ["rm 000000000002", "rm 000000000001"]);
+        assert!(fake.containers.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn containers_of_other_projects_are_left_alone() {
+        let fake = Arc::new(FakeBackend::default());
+        for name in ["myapp-db", "app-db-backup"] {
+            fake.create(&CreateOptions {
+                name: name.to_string(),
+                ..Default::default()
+            });
+        }
+        let mut app = App::with_backend(fake.clone());
+        let mut events = app.take_events().unwrap();
+        let services = vec![
+            DockerCompose::sample("/srv/app", "db", json!({})),
+            DockerCompose::sample("/srv/myapp", "db", json!({})),
+        ];
+        app.handle_event(AppEvent::Fetched(
+            app.generation,
+            Fetched::Indexed(services),
+        ));
+        fake.calls.lock().unwrap().clear();
+        let project = |action| Listing::Project("/srv/app".to_string(), action);
+
+        // Neither existing container is app-db, so there is nothing to
+        // remove and it gets created.
+        app.spawn_listing(project(MenuAction::DownAll));
+        settle(&mut app, &mut events).await;
+        assert!(fake.calls().is_empty());
+
+        app.spawn_listing(project(MenuAction::UpAll));
+        settle(&mut app, &mut events).await;
+        assert_eq!(fake.calls(), ["create app-db"]);
+
+        app.spawn_listing(project(MenuAction::DownAll));
+        settle(&mut app, &mut events).await;
+        assert_eq!(fake.calls()[1..], ["rm 000000000003"]);
+        let left: Vec<Vec<String>> = fake
+            .containers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.names.clone())
+            .collect();
+        assert_eq!(left, [["/myapp-db"], ["/app-db-backup"]]);
+    }
+
+    #[test]
+    fn env_editor_reveals_follow_their_lines() {
+        let mut app = App::with_backend(Arc::new(FakeBackend::default()));
+        let lines = ["A=1", "DB_PASSWORD=x", "API_TOKEN=y"].map(String::from);
+        app.show_env_editor(EnvTarget::DotEnv(PathBuf::from(".env")), lines.to_vec());
+        for line in [1, 2] {
+            app.env_editor_selected = line;
+            app.env_editor_toggle_reveal();
+        }
+
+        app.env_editor_selected = 0;
+        app.env_editor_add_line();
+        app.env_editor_confirm_edit();
+        assert_eq!(app.env_editor_lines[2], "DB_PASSWORD=x");
+        assert_eq!(app.env_editor_revealed, HashSet::from([2, 3]));
+
+        app.env_editor_selected = 2;
+        app.env_editor_delete_line();
+        assert_eq!(app.env_editor_lines[2], "API_TOKEN=y");
+        assert_eq!(app.env_editor_revealed, HashSet::from([2]));
+    }
+
+    #[test]
+    fn workspace_roots_lose_trailing_slashes_but_not_the_root() {
+        // (typed, root)
+        let cases = [
+            ("~/src/", "~/src"),
+            (" /srv//  ", "/srv"),
+            ("/", "/"),
+            ("//", "/"),
+            ("  ", ""),
+        ];
+        for (typed, root) in cases {
+            assert_eq!(workspace_root(typed), root, "{:?}", typed);
+        }
+    }
+}
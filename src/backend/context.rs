@@ -0,0 +1,132 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const DEFAULT_CONTEXT: &str = "default";
+
+/// A Docker context as stored by `docker context create`.
+#[derive(Debug, Clone)]
+pub struct DockerContext {
+    pub name: String,
+    pub description: String,
+    /// `DOCKER_HOST`-style address, e.g. `ssh://me@box` or `tcp://1.2.3.4:2376`.
+    /// Empty for the default context, which means "local runtime".
+    pub host: String,
+    /// Directory holding ca.pem/cert.pem/key.pem when the context uses TLS.
+    pub tls_dir: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CliConfig {
+    #[serde(rename = "currentContext")]
+    current_context: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ContextMeta {
+    name: String,
+    metadata: ContextMetadata,
+    endpoints: HashMap<String, ContextEndpoint>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ContextMetadata {
+    description: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ContextEndpoint {
+    host: String,
+    #[serde(rename = "SkipTLSVerify")]
+    skip_tls_verify: bool,
+}
+
+/*
+ * Reading the docker CLI's contexts
+ *
+ * Contexts live in `$DOCKER_CONFIG` (default `~/.docker`): one directory per
+ * context under contexts/meta/ holding meta.json, with TLS material under
+ * contexts/tls/ in a directory of the same name. The directory names are
+ * hashes of the context name, so they are simply all read.
+ * */
+pub fn list() -> Vec<DockerContext> {
+    let mut contexts = vec![DockerContext {
+        name: DEFAULT_CONTEXT.to_string(),
+        description: "Local runtime".to_string(),
+        host: String::new(),
+        tls_dir: None,
+    }];
+
+    let Some(dir) = docker_config_dir() else {
+        return contexts;
+    };
+    let Ok(entries) = std::fs::read_dir(dir.join("contexts").join("meta")) else {
+        return contexts;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(raw) = std::fs::read_to_string(entry.path().join("meta.json")) else {
+            continue;
+        };
+        let Ok(meta) = serde_json::from_str::<ContextMeta>(&raw) else {
+            continue;
+        };
+        let Some(endpoint) = meta.endpoints.get("docker") else {
+            continue;
+        };
+
+        let tls_dir = dir
+            .join("contexts")
+            .join("tls")
+            .join(entry.file_name())
+            .join("docker");
+        contexts.push(DockerContext {
+            name: meta.name,
+            description: meta.metadata.description,
+            host: endpoint.host.clone(),
+            tls_dir: (!endpoint.skip_tls_verify && tls_dir.is_dir()).then_some(tls_dir),
+        });
+    }
+
+    contexts[1..].sort_by(|a, b| a.name.cmp(&b.name));
+    contexts
+}
+
+/// The context the docker CLI would use: `DOCKER_CONTEXT`, then
+/// `currentContext` from config.json, then "default".
+pub fn current() -> String {
+    if let Ok(name) = std::env::var("DOCKER_CONTEXT")
+        && !name.is_empty()
+    {
+        return name;
+    }
+    docker_config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join("config.json")).ok())
+        .and_then(|raw| serde_json::from_str::<CliConfig>(&raw).ok())
+        .map(|config| config.current_context)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_CONTEXT.to_string())
+}
+
+pub fn find(name: &str) -> Option<DockerContext> {
+    list().into_iter().find(|c| c.name == name)
+}
+
+fn docker_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("DOCKER_CONFIG")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir));
+    }
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()?;
+    Some(PathBuf::from(home).join(".docker"))
+}
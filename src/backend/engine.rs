@@ -0,0 +1,1113 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::task::{self, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, ReadBuf};
+use tokio::net::TcpStream;
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::mpsc::{self, Receiver};
+
+use crate::app::{CPUUsage, ContainerPort, DockerCompose, DockerImage, Health, RunningContainer};
+use crate::backend::runner::RunnerBackend;
+use crate::backend::runtime::Target;
+use crate::backend::{CreateOptions, DockerBackend, Job};
+use crate::config::Workspace;
+
+/// How long the engine gets to answer a request, and to send the whole body
+/// of a plain (not streamed) response.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the Docker Engine API is listening.
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Unix(PathBuf),
+    Tcp(String),
+    /// `ssh://[user@]host[:port]`, reached through `docker system dial-stdio`
+    /// on the remote side.
+    Ssh(String),
+}
+
+impl Endpoint {
+    pub fn parse(host: &str) -> Self {
+        if let Some(path) = host.strip_prefix("unix://") {
+            Endpoint::Unix(PathBuf::from(path))
+        } else if host.starts_with("ssh://") {
+            Endpoint::Ssh(host.to_string())
+        } else if let Some(addr) = host.strip_prefix("tcp://") {
+            Endpoint::Tcp(addr.trim_end_matches('/').to_string())
+        } else if host.starts_with('/') {
+            Endpoint::Unix(PathBuf::from(host))
+        } else {
+            Endpoint::Tcp(host.to_string())
+        }
+    }
+
+    /// The `DOCKER_HOST` value pointing at this endpoint.
+    pub fn host(&self) -> String {
+        match self {
+            Endpoint::Unix(path) => format!("unix://{}", path.display()),
+            Endpoint::Tcp(addr) => format!("tcp://{}", addr),
+            Endpoint::Ssh(host) => host.clone(),
+        }
+    }
+}
+
+/*
+ * Backend that talks to the Docker Engine HTTP API directly
+ *
+ * Container list, image list and stats are served over the socket without
+ * spawning anything. Compose discovery and lifecycle actions still go
+ * through the runner.
+ * */
+pub struct EngineBackend {
+    target: Target,
+    endpoint: Endpoint,
+    runner: RunnerBackend,
+}
+
+impl EngineBackend {
+    pub fn new(target: Target, runner: RunnerBackend) -> Self {
+        Self {
+            endpoint: target.endpoint.clone(),
+            target,
+            runner,
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        if self.target.tls_dir.is_some() {
+            bail!(
+                "context {:?} uses TLS, which the engine backend cannot speak; use backend \"runner\"",
+                self.target.context
+            );
+        }
+
+        let response = request(&self.endpoint, "GET", path)
+            .await
+            .with_context(|| format!("cannot reach Docker at {:?}", self.endpoint))?;
+        let status = response.status;
+        let body = response.body().await?;
+        if status / 100 != 2 {
+            bail!(
+                "GET {} returned HTTP {}: {}",
+                path,
+                status,
+                api_message(&body)
+            );
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Health and last probe of container `id`, from inspecting it. None
+    /// when it cannot be inspected (it may be gone already).
+    async fn health(&self, id: &str) -> Option<Health> {
+        let inspect: ContainerInspect = self
+            .get_json(&format!("/containers/{}/json", id))
+            .await
+            .ok()?;
+        let health = inspect.state.health?;
+        let last = health.log.last();
+        Some(Health {
+            status: health.status,
+            failing_streak: health.failing_streak,
+            exit_code: last.map_or(0, |l| l.exit_code),
+            output: last
+                .map(|l| l.output.trim().to_string())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl DockerBackend for EngineBackend {
+    async fn search(&self, scope: &Workspace, names: &[&str]) -> Result<Vec<String>> {
+        self.runner.search(scope, names).await
+    }
+
+    async fn read_compose(&self, path: &str) -> Result<Vec<DockerCompose>> {
+        self.runner.read_compose(path).await
+    }
+
+    async fn images(&self) -> Result<Vec<DockerImage>> {
+        let summaries: Vec<ImageSummary> = self.get_json("/images/json").await?;
+
+        let now = unix_now();
+        let mut images = Vec::new();
+        for summary in summaries {
+            let image_id = short_id(&summary.id);
+            let created = format!("{} ago", human_duration(now - summary.created));
+            let size = human_size(summary.size);
+
+            let tags = summary.repo_tags.unwrap_or_default();
+            if tags.is_empty() {
+                images.push(DockerImage {
+                    repository: "<none>".to_string(),
+                    tag: "<none>".to_string(),
+                    image_id,
+                    created,
+                    size,
+                });
+                continue;
+            }
+
+            for repo_tag in tags {
+                let (repository, tag) = match repo_tag.rsplit_once(':') {
+                    Some((repo, tag)) if !tag.contains('/') => (repo.to_string(), tag.to_string()),
+                    _ => (repo_tag.clone(), "<none>".to_string()),
+                };
+                images.push(DockerImage {
+                    repository,
+                    tag,
+                    image_id: image_id.clone(),
+                    created: created.clone(),
+                    size: size.clone(),
+                });
+            }
+        }
+        Ok(images)
+    }
+
+    async fn list(&self) -> Result<Vec<RunningContainer>> {
+        let summaries: Vec<ContainerSummary> = self.get_json("/containers/json?all=true").await?;
+
+        let mut containers = Vec::new();
+        for c in summaries {
+            containers.push(RunningContainer {
+                id: short_id(&c.id),
+                image: c.image,
+                command: c.command,
+                created: c.created,
+                status: c.status,
+                state: c.state,
+                ports: Some(
+                    c.ports
+                        .into_iter()
+                        .map(|p| ContainerPort {
+                            ip: p.ip,
+                            private_port: p.private_port,
+                            public_port: p.public_port,
+                            port_type: p.port_type,
+                        })
+                        .collect(),
+                ),
+                names: c.names,
+                health: None,
+            });
+        }
+        Ok(self.target.visible(containers))
+    }
+
+    async fn list_with_health(&self) -> Result<Vec<RunningContainer>> {
+        let mut containers = self.list().await?;
+        // The list only has health in the status text; containers with a
+        // healthcheck are inspected for the probe results, all at once.
+        let healths = futures::future::join_all(containers.iter().map(|c| async move {
+            if c.status.contains("health") {
+                self.health(&c.id).await
+            } else {
+                None
+            }
+        }))
+        .await;
+        for (container, health) in containers.iter_mut().zip(healths) {
+            container.health = health;
+        }
+        Ok(containers)
+    }
+
+    fn start(&self, container_id: &str) -> Job {
+        self.runner.start(container_id)
+    }
+
+    fn stop(&self, container_id: &str) -> Job {
+        self.runner.stop(container_id)
+    }
+
+    fn remove_container(&self, container_id: &str) -> Job {
+        self.runner.remove_container(container_id)
+    }
+
+    fn remove_image(&self, image_id: &str) -> Job {
+        self.runner.remove_image(image_id)
+    }
+
+    fn create(&self, opts: &CreateOptions) -> Job {
+        self.runner.create(opts)
+    }
+
+    fn stream(&self, container_id: &str) -> Receiver<Result<CPUUsage>> {
+        let (tx, rx) = mpsc::channel::<Result<CPUUsage>>(100);
+        let endpoint = self.endpoint.clone();
+        let container_id = container_id.to_string();
+
+        tokio::spawn(async move {
+            let path = format!("/containers/{}/stats?stream=true", container_id);
+            let mut response = match request(&endpoint, "GET", &path).await {
+                Ok(response) if response.status / 100 == 2 => response,
+                Ok(response) => {
+                    let status = response.status;
+                    let body = response.body().await.unwrap_or_default();
+                    let _ = tx
+                        .send(Err(anyhow!(
+                            "GET {} returned HTTP {}: {}",
+                            path,
+                            status,
+                            api_message(&body)
+                        )))
+                        .await;
+                    return;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(Err(anyhow!("cannot reach Docker at {:?}: {}", endpoint, e)))
+                        .await;
+                    return;
+                }
+            };
+
+            let mut disk = disk_usage(&endpoint).await;
+            let mut tick_count = 0;
+            let mut pending: Vec<u8> = Vec::new();
+
+            while let Ok(Some(chunk)) = response.next_chunk().await {
+                pending.extend_from_slice(&chunk);
+
+                while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=pos).collect();
+                    let Ok(stats) = serde_json::from_slice::<ContainerStats>(&line) else {
+                        continue;
+                    };
+
+                    tick_count += 1;
+                    if tick_count >= 10 {
+                        disk = disk_usage(&endpoint).await;
+                        tick_count = 0;
+                    }
+
+                    if tx
+                        .send(Ok(stats.to_usage(&container_id, &disk)))
+                        .await
+                        .is_err()
+                    {
+                        return; // Receiver dropped
+                    }
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerSummary {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    command: String,
+    #[serde(default)]
+    created: i64,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    ports: Vec<PortSummary>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerInspect {
+    #[serde(default)]
+    state: InspectState,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+struct InspectState {
+    #[serde(default)]
+    health: Option<InspectHealth>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InspectHealth {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    failing_streak: u32,
+    #[serde(default)]
+    log: Vec<HealthLog>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HealthLog {
+    #[serde(default)]
+    exit_code: i64,
+    #[serde(default)]
+    output: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PortSummary {
+    #[serde(rename = "IP", default)]
+    ip: String,
+    #[serde(default)]
+    private_port: u16,
+    #[serde(default)]
+    public_port: u16,
+    #[serde(rename = "Type", default)]
+    port_type: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ImageSummary {
+    id: String,
+    #[serde(default)]
+    repo_tags: Option<Vec<String>>,
+    #[serde(default)]
+    created: i64,
+    #[serde(default)]
+    size: i64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CpuUsageStats {
+    total_usage: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CpuStats {
+    cpu_usage: CpuUsageStats,
+    system_cpu_usage: u64,
+    online_cpus: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MemoryStats {
+    usage: u64,
+    limit: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NetworkStats {
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ContainerStats {
+    cpu_stats: CpuStats,
+    precpu_stats: CpuStats,
+    memory_stats: MemoryStats,
+    // Podman sends null for containers without a network.
+    networks: Option<HashMap<String, NetworkStats>>,
+}
+
+impl ContainerStats {
+    // Same math as the runner's `stream` command so both backends agree.
+    fn to_usage(&self, container_id: &str, disk: &DiskUsage) -> CPUUsage {
+        let cpu_delta = self
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(self.precpu_stats.cpu_usage.total_usage) as f64;
+        let system_delta = self
+            .cpu_stats
+            .system_cpu_usage
+            .saturating_sub(self.precpu_stats.system_cpu_usage) as f64;
+
+        let mut cpu_percent = 0.0;
+        if system_delta > 0.0 && self.cpu_stats.online_cpus > 0 {
+            cpu_percent = (cpu_delta / system_delta) * self.cpu_stats.online_cpus as f64 * 100.0;
+        } else if system_delta > 0.0 {
+            cpu_percent = (cpu_delta / system_delta) * 100.0;
+        }
+
+        let mut mem_percent = 0.0;
+        if self.memory_stats.limit > 0 {
+            mem_percent = (self.memory_stats.usage as f64 / self.memory_stats.limit as f64) * 100.0;
+        }
+
+        let networks = self.networks.iter().flat_map(|n| n.values());
+        let net_rx = networks.clone().map(|n| n.rx_bytes).sum();
+        let net_tx = networks.map(|n| n.tx_bytes).sum();
+
+        CPUUsage {
+            container_id: container_id.to_string(),
+            cpu_percent,
+            mem_usage: self.memory_stats.usage as f64,
+            mem_limit: self.memory_stats.limit,
+            mem_percent,
+            net_rx,
+            net_tx,
+            disk_images: disk.images,
+            disk_containers: disk.containers,
+            disk_volumes: disk.volumes,
+            disk_build_cache: disk.build_cache,
+            disk_total: disk.images + disk.containers + disk.volumes + disk.build_cache,
+        }
+    }
+}
+
+#[derive(Default)]
+struct DiskUsage {
+    images: u64,
+    containers: u64,
+    volumes: u64,
+    build_cache: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct SystemDf {
+    images: Option<Vec<SizeEntry>>,
+    containers: Option<Vec<SizeEntry>>,
+    volumes: Option<Vec<VolumeEntry>>,
+    build_cache: Option<Vec<SizeEntry>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct SizeEntry {
+    size: i64,
+    size_rw: i64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct VolumeEntry {
+    usage_data: Option<SizeEntry>,
+}
+
+async fn disk_usage(endpoint: &Endpoint) -> DiskUsage {
+    let Ok(response) = request(endpoint, "GET", "/system/df").await else {
+        return DiskUsage::default();
+    };
+    let Ok(body) = response.body().await else {
+        return DiskUsage::default();
+    };
+    let Ok(df) = serde_json::from_slice::<SystemDf>(&body) else {
+        return DiskUsage::default();
+    };
+
+    let positive = |n: i64| if n > 0 { n as u64 } else { 0 };
+
+    DiskUsage {
+        images: df
+            .images
+            .unwrap_or_default()
+            .iter()
+            .map(|i| positive(i.size))
+            .sum(),
+        containers: df
+            .containers
+            .unwrap_or_default()
+            .iter()
+            .map(|c| positive(c.size_rw))
+            .sum(),
+        volumes: df
+            .volumes
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|v| v.usage_data.as_ref())
+            .map(|u| positive(u.size))
+            .sum(),
+        build_cache: df
+            .build_cache
+            .unwrap_or_default()
+            .iter()
+            .map(|b| positive(b.size))
+            .sum(),
+    }
+}
+
+type Connection = Pin<Box<dyn AsyncRead + Send>>;
+
+async fn connect(endpoint: &Endpoint, head: &[u8]) -> io::Result<Connection> {
+    match endpoint {
+        #[cfg(unix)]
+        Endpoint::Unix(path) => {
+            let mut stream = tokio::net::UnixStream::connect(path).await?;
+            stream.write_all(head).await?;
+            Ok(Box::pin(stream))
+        }
+        #[cfg(not(unix))]
+        Endpoint::Unix(path) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("unix sockets are not supported here: {}", path.display()),
+        )),
+        Endpoint::Tcp(addr) => {
+            let mut stream = TcpStream::connect(addr).await?;
+            stream.write_all(head).await?;
+            Ok(Box::pin(stream))
+        }
+        Endpoint::Ssh(host) => {
+            // BatchMode: a password prompt would land in the middle of the TUI.
+            let mut child = Command::new("ssh")
+                .args(["-T", "-o", "BatchMode=yes", "--", host])
+                .args(["docker", "system", "dial-stdio"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn()?;
+            let mut stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| io::Error::other("ssh: no stdin"))?;
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| io::Error::other("ssh: no stdout"))?;
+            stdin.write_all(head).await?;
+            Ok(Box::pin(SshStream {
+                _child: child,
+                _stdin: stdin,
+                stdout,
+            }))
+        }
+    }
+}
+
+// Keeps the ssh process (and its stdin) alive for as long as the response
+// is being read.
+struct SshStream {
+    _child: Child,
+    _stdin: ChildStdin,
+    stdout: ChildStdout,
+}
+
+impl AsyncRead for SshStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stdout).poll_read(cx, buf)
+    }
+}
+
+/*
+ * Minimal HTTP/1.1 client
+ *
+ * One request per connection (`Connection: close`), which is all the Engine
+ * API needs. Handles both `Content-Length` and chunked bodies so the same
+ * reader works for plain JSON responses and the endless stats stream.
+ *
+ * Connecting and reading the headers is limited to `REQUEST_TIMEOUT`, so a
+ * socket that accepts but never answers (a hung daemon, an ssh host waiting
+ * on something) fails the request instead of stalling it forever.
+ * */
+async fn request(endpoint: &Endpoint, method: &str, path: &str) -> io::Result<Response> {
+    tokio::time::timeout(REQUEST_TIMEOUT, send(endpoint, method, path))
+        .await
+        .map_err(|_| timed_out(method, path))?
+}
+
+fn timed_out(method: &str, path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!(
+            "{} {} got no answer within {}s",
+            method,
+            path,
+            REQUEST_TIMEOUT.as_secs()
+        ),
+    )
+}
+
+async fn send(endpoint: &Endpoint, method: &str, path: &str) -> io::Result<Response> {
+    let head = format!(
+        "{} {} HTTP/1.1\r\nHost: docker\r\nUser-Agent: easydocker\r\nConnection: close\r\n\r\n",
+        method, path
+    );
+    let conn = connect(endpoint, head.as_bytes()).await?;
+    let mut reader = BufReader::new(conn);
+
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line).await?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| io::Error::other(format!("bad status line: {:?}", status_line)))?;
+
+    let mut chunked = false;
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            } else if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse::<usize>().ok();
+            }
+        }
+    }
+
+    Ok(Response {
+        status,
+        reader,
+        chunked,
+        remaining: content_length,
+        finished: false,
+    })
+}
+
+struct Response {
+    status: u16,
+    reader: BufReader<Connection>,
+    chunked: bool,
+    remaining: Option<usize>,
+    finished: bool,
+}
+
+impl Response {
+    /// Reads the next piece of the body, or `None` once it is exhausted.
+    async fn next_chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        if self.chunked {
+            let mut size_line = String::new();
+            if self.reader.read_line(&mut size_line).await? == 0 {
+                self.finished = true;
+                return Ok(None);
+            }
+            let size_str = size_line.trim().split(';').next().unwrap_or("");
+            let size = usize::from_str_radix(size_str, 16)
+                .map_err(|_| io::Error::other(format!("bad chunk size: {:?}", size_line)))?;
+            if size == 0 {
+                self.finished = true;
+                return Ok(None);
+            }
+            let mut buf = vec![0u8; size];
+            self.reader.read_exact(&mut buf).await?;
+            let mut crlf = [0u8; 2];
+            self.reader.read_exact(&mut crlf).await?;
+            return Ok(Some(buf));
+        }
+
+        let mut buf = vec![0u8; 8192];
+        let limit = match self.remaining {
+            Some(0) => {
+                self.finished = true;
+                return Ok(None);
+            }
+            Some(remaining) => remaining.min(buf.len()),
+            None => buf.len(),
+        };
+        let n = self.reader.read(&mut buf[..limit]).await?;
+        if n == 0 {
+            self.finished = true;
+            return Ok(None);
+        }
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= n;
+        }
+        buf.truncate(n);
+        Ok(Some(buf))
+    }
+
+    /// The rest of the body, which has to arrive within `REQUEST_TIMEOUT`.
+    /// Streams are read with `next_chunk` instead.
+    async fn body(mut self) -> io::Result<Vec<u8>> {
+        let read = async {
+            let mut body = Vec::new();
+            while let Some(chunk) = self.next_chunk().await? {
+                body.extend_from_slice(&chunk);
+            }
+            Ok(body)
+        };
+        tokio::time::timeout(REQUEST_TIMEOUT, read)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "response body timed out"))?
+    }
+}
+
+// Engine API errors look like {"message": "..."}.
+fn api_message(body: &[u8]) -> String {
+    #[derive(Deserialize)]
+    struct ApiError {
+        message: String,
+    }
+    serde_json::from_slice::<ApiError>(body)
+        .map(|e| e.message)
+        .unwrap_or_else(|_| String::from_utf8_lossy(body).trim().to_string())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn short_id(id: &str) -> String {
+    let id = id.strip_prefix("sha256:").unwrap_or(id);
+    id.chars().take(12).collect()
+}
+
+// Mirrors docker's units.HumanDuration so "Created" reads like `docker images`.
+fn human_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let minutes = seconds / 60;
+    let hours = (seconds as f64 / 3600.0 + 0.5) as i64;
+
+    if seconds < 1 {
+        "Less than a second".to_string()
+    } else if seconds == 1 {
+        "1 second".to_string()
+    } else if seconds < 60 {
+        format!("{} seconds", seconds)
+    } else if minutes == 1 {
+        "About a minute".to_string()
+    } else if minutes < 60 {
+        format!("{} minutes", minutes)
+    } else if hours == 1 {
+        "About an hour".to_string()
+    } else if hours < 48 {
+        format!("{} hours", hours)
+    } else if hours < 24 * 7 * 2 {
+        format!("{} days", hours / 24)
+    } else if hours < 24 * 30 * 2 {
+        format!("{} weeks", hours / 24 / 7)
+    } else if hours < 24 * 365 * 2 {
+        format!("{} months", hours / 24 / 30)
+    } else {
+        format!("{} years", hours / 24 / 365)
+    }
+}
+
+// Mirrors docker's units.HumanSizeWithPrecision(size, 3).
+fn human_size(bytes: i64) -> String {
+    const UNITS: [&str; 9] = ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
+
+    let mut value = bytes.max(0) as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+
+    let digits = if value >= 100.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+    let mut number = format!("{:.*}", digits, value);
+    if number.contains('.') {
+        number = number
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string();
+    }
+    format!("{}{}", number, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::runtime::Runtime;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::UnixListener;
+
+    /// A Docker socket in a fresh temp directory, removed on drop.
+    struct MockDocker {
+        dir: PathBuf,
+        socket: PathBuf,
+    }
+
+    impl MockDocker {
+        /// Answers every connection with the canned response of the first
+        /// route whose prefix matches the request path, or a 404.
+        fn serve(routes: Vec<(&'static str, String)>) -> Self {
+            let mock = Self::bind();
+            let listener = UnixListener::bind(&mock.socket).unwrap();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let routes = routes.clone();
+                    tokio::spawn(async move {
+                        let (read, mut write) = stream.into_split();
+                        let mut reader = BufReader::new(read);
+                        let mut request_line = String::new();
+                        reader.read_line(&mut request_line).await.unwrap();
+                        let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                        let response = routes
+                            .iter()
+                            .find(|(prefix, _)| path.starts_with(prefix))
+                            .map(|(_, response)| response.clone())
+                            .unwrap_or_else(|| json(404, r#"{"message":"page not found"}"#));
+                        write.write_all(response.as_bytes()).await.unwrap();
+                    });
+                }
+            });
+            mock
+        }
+
+        /// Accepts connections and never answers.
+        fn hang() -> Self {
+            let mock = Self::bind();
+            let listener = UnixListener::bind(&mock.socket).unwrap();
+            tokio::spawn(async move {
+                let mut open = Vec::new();
+                while let Ok((stream, _)) = listener.accept().await {
+                    open.push(stream);
+                }
+            });
+            mock
+        }
+
+        fn bind() -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "easydocker-engine-{}-{}",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self {
+                socket: dir.join("docker.sock"),
+                dir,
+            }
+        }
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::Unix(self.socket.clone())
+        }
+
+        fn backend(&self) -> EngineBackend {
+            let target = Target {
+                runtime: Runtime::Docker,
+                endpoint: self.endpoint(),
+                context: "default".to_string(),
+                tls_dir: None,
+            };
+            EngineBackend::new(target, RunnerBackend::default())
+        }
+    }
+
+    impl Drop for MockDocker {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn json(status: u16, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    fn chunked(status: u16, chunks: &[&str]) -> String {
+        let mut response = format!(
+            "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n",
+            status
+        );
+        for chunk in chunks {
+            response.push_str(&format!("{:x};ext=1\r\n{}\r\n", chunk.len(), chunk));
+        }
+        response.push_str("0\r\n\r\n");
+        response
+    }
+
+    #[tokio::test]
+    async fn request_reads_content_length_body() {
+        let mock = MockDocker::serve(vec![("/_ping", json(200, "OK"))]);
+        let response = request(&mock.endpoint(), "GET", "/_ping").await.unwrap();
+        assert_eq!(response.status, 200);
+        assert!(!response.chunked);
+        assert_eq!(response.body().await.unwrap(), b"OK");
+    }
+
+    #[tokio::test]
+    async fn request_joins_chunked_body() {
+        let mock = MockDocker::serve(vec![(
+            "/version",
+            chunked(200, &[r#"{"Version":"#, r#""27.1.0"}"#]),
+        )]);
+        let response = request(&mock.endpoint(), "GET", "/version").await.unwrap();
+        assert!(response.chunked);
+        assert_eq!(response.body().await.unwrap(), br#"{"Version":"27.1.0"}"#);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn request_times_out_when_the_engine_never_answers() {
+        let mock = MockDocker::hang();
+        let err = request(&mock.endpoint(), "GET", "/_ping")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn get_json_reports_api_errors() {
+        let mock = MockDocker::serve(vec![(
+            "/images/json",
+            json(500, r#"{"message":"daemon is shutting down"}"#),
+        )]);
+        let err = mock.backend().images().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GET /images/json returned HTTP 500: daemon is shutting down"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_parses_containers() {
+        let containers = r#"[
+            {"Id":"0123456789abcdef0123","Names":["/web"],"Image":"nginx","Command":"nginx -g","Created":1700000000,
+             "Status":"Up 2 minutes (unhealthy)","State":"running",
+             "Ports":[{"IP":"0.0.0.0","PrivatePort":80,"PublicPort":8080,"Type":"tcp"}]},
+            {"Id":"fedcba9876543210fedc","Names":["/db"],"Image":"postgres","Status":"Exited (0) 1 hour ago","State":"exited"}
+        ]"#;
+        let inspect = r#"{"State":{"Health":{"Status":"unhealthy","FailingStreak":3,
+            "Log":[{"ExitCode":0,"Output":"ok"},{"ExitCode":1,"Output":"connection refused\n"}]}}}"#;
+        let mock = MockDocker::serve(vec![
+            ("/containers/json", chunked(200, &[containers])),
+            // Inspected by the short id the list hands out.
+            ("/containers/0123456789ab/json", json(200, inspect)),
+        ]);
+        let backend = mock.backend();
+
+        let list = backend.list().await.unwrap();
+        assert_eq!(list.len(), 2);
+
+        let web = &list[0];
+        assert_eq!(web.id, "0123456789ab");
+        assert_eq!(web.names, vec!["/web"]);
+        assert_eq!(web.state, "running");
+        let ports = web.ports.as_ref().unwrap();
+        assert_eq!(ports[0].public_port, 8080);
+        assert_eq!(ports[0].private_port, 80);
+        assert!(web.health.is_none());
+
+        let db = &list[1];
+        assert_eq!(db.id, "fedcba987654");
+        assert!(db.ports.as_ref().unwrap().is_empty());
+
+        let list = backend.list_with_health().await.unwrap();
+        let health = list[0].health.as_ref().unwrap();
+        assert_eq!(health.status, "unhealthy");
+        assert_eq!(health.failing_streak, 3);
+        assert_eq!(health.exit_code, 1);
+        assert_eq!(health.output, "connection refused");
+        assert!(list[1].health.is_none());
+    }
+
+    #[tokio::test]
+    async fn images_splits_tags() {
+        let images = r#"[
+            {"Id":"sha256:aaaaaaaaaaaabbbbbbbb","RepoTags":["nginx:1.27","localhost:5000/app"],"Created":0,"Size":187654321},
+            {"Id":"sha256:ccccccccccccdddddddd","RepoTags":null,"Created":0,"Size":1500}
+        ]"#;
+        let mock = MockDocker::serve(vec![("/images/json", json(200, images))]);
+
+        let images = mock.backend().images().await.unwrap();
+        let rows: Vec<(&str, &str, &str, &str)> = images
+            .iter()
+            .map(|i| {
+                (
+                    i.repository.as_str(),
+                    i.tag.as_str(),
+                    i.image_id.as_str(),
+                    i.size.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("nginx", "1.27", "aaaaaaaaaaaa", "188MB"),
+                ("localhost:5000/app", "<none>", "aaaaaaaaaaaa", "188MB"),
+                ("<none>", "<none>", "cccccccccccc", "1.5kB"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_parses_stats_lines() {
+        // One stats object split across chunks, the next one whole.
+        let first = r#"{"cpu_stats":{"cpu_usage":{"total_usage":300},"system_cpu_usage":2000,"online_cpus":2},"precpu_stats":{"cpu_usage":{"total_usage":100},"system_cpu_usage":1000,"online_cpus":2},"memory_stats":{"usage":256,"limit":1024},"networks":{"eth0":{"rx_bytes":10,"tx_bytes":20},"eth1":{"rx_bytes":1,"tx_bytes":2}}}"#;
+        let second = r#"{"cpu_stats":{},"precpu_stats":{},"memory_stats":{},"networks":null}"#;
+        let (head, tail) = first.split_at(40);
+        let df = r#"{"Images":[{"Size":100}],"Containers":[{"SizeRw":20}],"Volumes":[{"UsageData":{"Size":3}}],"BuildCache":null}"#;
+        let mock = MockDocker::serve(vec![
+            (
+                "/containers/web/stats",
+                chunked(
+                    200,
+                    &[head, &format!("{}\n", tail), &format!("{}\n", second)],
+                ),
+            ),
+            ("/system/df", json(200, df)),
+        ]);
+
+        let mut rx = mock.backend().stream("web");
+        let usage = rx.recv().await.unwrap().unwrap();
+        assert_eq!(usage.container_id, "web");
+        assert_eq!(usage.cpu_percent, 40.0);
+        assert_eq!(usage.mem_percent, 25.0);
+        assert_eq!((usage.net_rx, usage.net_tx), (11, 22));
+        assert_eq!(usage.disk_total, 123);
+
+        let usage = rx.recv().await.unwrap().unwrap();
+        assert_eq!(usage.cpu_percent, 0.0);
+        assert_eq!(usage.net_rx, 0);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_reports_api_errors() {
+        let mock = MockDocker::serve(vec![(
+            "/containers/gone/stats",
+            json(404, r#"{"message":"No such container: gone"}"#),
+        )]);
+
+        let mut rx = mock.backend().stream("gone");
+        let err = rx.recv().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GET /containers/gone/stats?stream=true returned HTTP 404: No such container: gone"
+        );
+        assert!(rx.recv().await.is_none());
+    }
+}
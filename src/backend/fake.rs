@@ -0,0 +1,137 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::oneshot;
+
+use crate::app::{CPUUsage, DockerCompose, DockerImage, RunningContainer};
+use crate::backend::{CreateOptions, DockerBackend, Job};
+use crate::config::Workspace;
+use crate::protocol::Message;
+
+/*
+ * In-memory backend for tests
+ *
+ * Queries are answered from `projects`, `images` and `containers`.
+ * Lifecycle calls are recorded in `calls` ("create app-web", "start
+ * 0123456789ab", ...), applied to `containers` the way the runtime would,
+ * and finish straight away with a successful Result and Done.
+ * */
+#[derive(Default)]
+pub struct FakeBackend {
+    /// Services by project directory.
+    pub projects: Mutex<BTreeMap<String, Vec<DockerCompose>>>,
+    pub images: Mutex<Vec<DockerImage>>,
+    pub containers: Mutex<Vec<RunningContainer>>,
+    pub calls: Mutex<Vec<String>>,
+}
+
+impl FakeBackend {
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn finish(&self, call: String, status: &str, id: &str) -> Job {
+        self.calls.lock().unwrap().push(call);
+        let (tx, rx) = mpsc::channel(2);
+        let (cancel, _) = oneshot::channel();
+        let _ = tx.try_send(Message::Result {
+            exit_code: 0,
+            status: status.to_string(),
+            id: id.to_string(),
+        });
+        let _ = tx.try_send(Message::Done);
+        Job::new(rx, cancel)
+    }
+
+    fn set_state(&self, id: &str, state: &str, status: &str) {
+        let mut containers = self.containers.lock().unwrap();
+        if let Some(container) = containers.iter_mut().find(|c| c.id == id) {
+            container.state = state.to_string();
+            container.status = status.to_string();
+        }
+    }
+}
+
+#[async_trait]
+impl DockerBackend for FakeBackend {
+    async fn search(&self, _: &Workspace, _: &[&str]) -> Result<Vec<String>> {
+        Ok(self.projects.lock().unwrap().keys().cloned().collect())
+    }
+
+    async fn read_compose(&self, path: &str) -> Result<Vec<DockerCompose>> {
+        self.projects
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow!("no compose file in {}", path))
+    }
+
+    async fn images(&self) -> Result<Vec<DockerImage>> {
+        Ok(self.images.lock().unwrap().clone())
+    }
+
+    async fn list(&self) -> Result<Vec<RunningContainer>> {
+        Ok(self.containers.lock().unwrap().clone())
+    }
+
+    async fn list_with_health(&self) -> Result<Vec<RunningContainer>> {
+        self.list().await
+    }
+
+    fn start(&self, container_id: &str) -> Job {
+        self.set_state(container_id, "running", "Up Less than a second");
+        self.finish(format!("start {}", container_id), "running", container_id)
+    }
+
+    fn stop(&self, container_id: &str) -> Job {
+        self.set_state(container_id, "exited", "Exited (0) Less than a second ago");
+        self.finish(format!("stop {}", container_id), "exited", container_id)
+    }
+
+    fn remove_container(&self, container_id: &str) -> Job {
+        self.containers
+            .lock()
+            .unwrap()
+            .retain(|c| c.id != container_id);
+        self.finish(format!("rm {}", container_id), "removed", container_id)
+    }
+
+    fn remove_image(&self, image_id: &str) -> Job {
+        self.images
+            .lock()
+            .unwrap()
+            .retain(|i| i.image_id != image_id);
+        self.finish(format!("rmi {}", image_id), "removed", image_id)
+    }
+
+    fn create(&self, opts: &CreateOptions) -> Job {
+        let id = {
+            let mut containers = self.containers.lock().unwrap();
+            let id = format!("{:012x}", containers.len() + 1);
+            containers.push(RunningContainer {
+                id: id.clone(),
+                image: opts.image.clone(),
+                command: String::new(),
+                created: 0,
+                status: "Up Less than a second".to_string(),
+                state: "running".to_string(),
+                ports: None,
+                names: vec![format!("/{}", opts.name)],
+                health: None,
+            });
+            id
+        };
+        self.finish(format!("create {}", opts.name), "running", &id)
+    }
+
+    /// No samples; the stream ends straight away.
+    fn stream(&self, _: &str) -> Receiver<Result<CPUUsage>> {
+        mpsc::channel(1).1
+    }
+}
@@ -0,0 +1,536 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+pub mod context;
+pub mod engine;
+#[cfg(test)]
+pub mod fake;
+pub mod runner;
+pub mod runtime;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::oneshot;
+
+use crate::app::{CPUUsage, DockerCompose, DockerImage, RunningContainer};
+use crate::backend::runtime::Target;
+use crate::config::{Config, Workspace};
+use crate::protocol::Message;
+
+/// How long a dependency gets to become healthy or to complete.
+const WAIT_LIMIT: Duration = Duration::from_secs(120);
+const WAIT_POLL: Duration = Duration::from_secs(1);
+
+/*
+ * Everything the TUI needs from Docker goes through this trait, so the UI
+ * never knows whether it is talking to the Go runner or something else.
+ *
+ * Query calls resolve to parsed values, or an error carrying whatever the
+ * backend reported (runner stderr, HTTP status, ...). Lifecycle calls (start, stop, rm,
+ * rmi, create) return a `Job` whose receiver yields protocol messages and
+ * always ends with `Message::Done`, even when the job is cancelled or times
+ * out. stream returns a receiver of stats samples until the container
+ * stops or the receiver is dropped; a failure to read them comes as an
+ * error, which is the last item.
+ * */
+#[async_trait]
+pub trait DockerBackend: Send + Sync {
+    /// Directories within `scope` holding a file named exactly one of
+    /// `names`.
+    async fn search(&self, scope: &Workspace, names: &[&str]) -> Result<Vec<String>>;
+    /// Services of a compose file, or of a project directory with its
+    /// override file merged in.
+    async fn read_compose(&self, path: &str) -> Result<Vec<DockerCompose>>;
+    async fn images(&self) -> Result<Vec<DockerImage>>;
+    /// Every container, running or not, in a single request.
+    async fn list(&self) -> Result<Vec<RunningContainer>>;
+    /// `list` with the health of the containers that have a healthcheck,
+    /// which takes an inspect each. Only the Running list needs it.
+    async fn list_with_health(&self) -> Result<Vec<RunningContainer>>;
+
+    fn start(&self, container_id: &str) -> Job;
+    fn stop(&self, container_id: &str) -> Job;
+    fn remove_container(&self, container_id: &str) -> Job;
+    fn remove_image(&self, image_id: &str) -> Job;
+    fn create(&self, opts: &CreateOptions) -> Job;
+
+    fn stream(&self, container_id: &str) -> Receiver<Result<CPUUsage>>;
+}
+
+/// Builds the backend selected by `config.backend` ("runner" or "engine"),
+/// defaulting to the Go runner at `runner_path`. Either one talks to the
+/// runtime described by `target`.
+pub fn build(config: &Config, runner_path: PathBuf, target: Target) -> Arc<dyn DockerBackend> {
+    let runner = runner::RunnerBackend::new(runner_path, config.timeouts.clone(), target.clone());
+    match config.backend.as_deref() {
+        Some("engine") => Arc::new(engine::EngineBackend::new(target, runner)),
+        _ => Arc::new(runner),
+    }
+}
+
+/// A lifecycle operation in flight: its message stream and a way to stop it.
+pub struct Job {
+    pub rx: Receiver<Message>,
+    pub cancel: Canceller,
+}
+
+impl Job {
+    pub fn new(rx: Receiver<Message>, cancel: oneshot::Sender<()>) -> Self {
+        Self {
+            rx,
+            cancel: Canceller(Some(cancel)),
+        }
+    }
+
+    /*
+     * Several lifecycle calls as one job
+     *
+     * Steps run one after another, each started only once the previous one
+     * finished without an error. Their messages are passed through except
+     * for `Done`, which is sent once at the end. Cancelling stops the step
+     * in flight and skips the rest.
+     * */
+    pub fn chain(steps: Vec<Step>) -> Job {
+        let (tx, rx) = mpsc::channel(64);
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let mut cancelled = false;
+            let mut steps = steps.into_iter();
+
+            for step in steps.by_ref() {
+                let Job {
+                    rx: mut step_rx,
+                    cancel: mut step_cancel,
+                } = step();
+                let mut failed = false;
+
+                loop {
+                    tokio::select! {
+                        message = step_rx.recv() => {
+                            let Some(message) = message else { break };
+                            match &message {
+                                Message::Done => break,
+                                Message::Error { .. } => failed = true,
+                                Message::Result { exit_code, .. } if *exit_code != 0 => failed = true,
+                                _ => {}
+                            }
+                            let _ = tx.send(message).await;
+                        }
+                        _ = &mut cancel_rx, if !cancelled => {
+                            cancelled = true;
+                            step_cancel.cancel();
+                        }
+                    }
+                }
+
+                // A cancelled step usually fails too; the skipped ones are
+                // still reported.
+                if cancelled {
+                    let skipped = steps.len();
+                    if skipped > 0 {
+                        let _ = tx
+                            .send(Message::Error {
+                                message: format!("cancelled, {} step(s) skipped", skipped),
+                                cause: String::new(),
+                            })
+                            .await;
+                    }
+                    break;
+                }
+                if failed {
+                    break;
+                }
+            }
+            let _ = tx.send(Message::Done).await;
+        });
+
+        Job::new(rx, cancel_tx)
+    }
+
+    /*
+     * Waiting on a depends_on condition
+     *
+     * Polls the container list until the container named exactly `name`
+     * is healthy (service_healthy) or has exited with status 0
+     * (service_completed_successfully), going by the runtime's status
+     * text; a container whose name merely contains it does not count. Fails when it turns unhealthy, has no healthcheck, exits
+     * otherwise, disappears, or is not there after WAIT_LIMIT.
+     * */
+    pub fn wait_for(backend: Arc<dyn DockerBackend>, name: String, condition: String) -> Job {
+        let (tx, rx) = mpsc::channel(8);
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let goal = match condition.as_str() {
+                "service_healthy" => "healthy",
+                _ => "completed",
+            };
+            let _ = tx
+                .send(Message::Log {
+                    line: format!("Waiting for {} to be {}", name, goal),
+                })
+                .await;
+
+            let deadline = tokio::time::sleep(WAIT_LIMIT);
+            tokio::pin!(deadline);
+            let outcome = loop {
+                let found = match backend.list().await {
+                    Ok(containers) => containers.into_iter().find(|c| c.has_name(&name)),
+                    Err(e) => break Err(format!("{:#}", e)),
+                };
+                if let Some(outcome) = condition_met(found.as_ref(), &condition) {
+                    break outcome;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(WAIT_POLL) => {}
+                    _ = &mut deadline => {
+                        break Err(format!("not {} after {}s", goal, WAIT_LIMIT.as_secs()));
+                    }
+                    _ = &mut cancel_rx => break Err("cancelled".to_string()),
+                }
+            };
+
+            let _ = match outcome {
+                Ok(()) => {
+                    tx.send(Message::Result {
+                        exit_code: 0,
+                        status: format!("{} is {}", name, goal),
+                        id: String::new(),
+                    })
+                    .await
+                }
+                Err(reason) => {
+                    tx.send(Message::Error {
+                        message: format!("waiting for {}", name),
+                        cause: reason,
+                    })
+                    .await
+                }
+            };
+            let _ = tx.send(Message::Done).await;
+        });
+
+        Job::new(rx, cancel_tx)
+    }
+}
+
+/// None while still waiting, otherwise whether `container` met `condition`.
+fn condition_met(
+    container: Option<&RunningContainer>,
+    condition: &str,
+) -> Option<std::result::Result<(), String>> {
+    let Some(container) = container else {
+        return Some(Err("container not found".to_string()));
+    };
+    let status = container.status.as_str();
+
+    match condition {
+        "service_healthy" => {
+            if status.contains("(healthy)") {
+                Some(Ok(()))
+            } else if status.contains("(unhealthy)") {
+                Some(Err(format!("unhealthy: {}", status)))
+            } else if !matches!(
+                container.state.as_str(),
+                "running" | "created" | "restarting"
+            ) {
+                Some(Err(format!("not running: {}", status)))
+            } else if container.state == "running" && !status.contains("health") {
+                Some(Err("no healthcheck configured".to_string()))
+            } else {
+                None
+            }
+        }
+        "service_completed_successfully" => match container.state.as_str() {
+            "exited" if status.starts_with("Exited (0)") => Some(Ok(())),
+            "exited" | "dead" => Some(Err(status.to_string())),
+            _ => None,
+        },
+        _ => Some(Ok(())),
+    }
+}
+
+/// Starts one step of a `Job::chain`.
+pub type Step = Box<dyn FnOnce() -> Job + Send>;
+
+/// Stops a job. Kept apart from the receiver so the stream can be handed
+/// to another task.
+pub struct Canceller(Option<oneshot::Sender<()>>);
+
+impl Canceller {
+    /// Asks the job to stop. It still finishes its stream with an Error and
+    /// `Done`, so the consumer keeps reading as usual.
+    pub fn cancel(&mut self) -> bool {
+        match self.0.take() {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateOptions {
+    pub image: String,
+    pub name: String,
+    pub hostname: String,
+    pub ports: String,
+    pub environment: Vec<String>,
+    pub volumes: Vec<String>,
+    pub restart: String,
+    pub build_context: String,
+}
+
+impl CreateOptions {
+    pub fn from_compose(container: &DockerCompose) -> Self {
+        Self {
+            image: container.image.clone(),
+            name: container.target_name(),
+            hostname: container.hostname.clone(),
+            ports: container.ports.clone(),
+            environment: container.environment.clone(),
+            volumes: container.volumes.clone(),
+            restart: container.restart.clone(),
+            build_context: container.build_context.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn container(name: &str, state: &str, status: &str) -> RunningContainer {
+        RunningContainer {
+            id: "0123456789ab".to_string(),
+            image: "postgres:16".to_string(),
+            command: String::new(),
+            created: 0,
+            status: status.to_string(),
+            state: state.to_string(),
+            ports: None,
+            names: vec![format!("/{}", name)],
+            health: None,
+        }
+    }
+
+    fn log(line: &str) -> Message {
+        Message::Log {
+            line: line.to_string(),
+        }
+    }
+
+    /// A job sending `messages`, then, when `hang`, waiting to be
+    /// cancelled, then `Done`.
+    fn scripted(messages: Vec<Message>, hang: bool) -> Job {
+        let (tx, rx) = mpsc::channel(8);
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            for message in messages {
+                let _ = tx.send(message).await;
+            }
+            if hang {
+                let _ = cancel_rx.await;
+                let _ = tx
+                    .send(Message::Error {
+                        message: "cancelled".to_string(),
+                        cause: String::new(),
+                    })
+                    .await;
+            }
+            let _ = tx.send(Message::Done).await;
+        });
+        Job::new(rx, cancel_tx)
+    }
+
+    /// A step that only records that it was started.
+    fn never(started: &Arc<AtomicBool>) -> Step {
+        let started = started.clone();
+        Box::new(move || {
+            started.store(true, Ordering::SeqCst);
+            scripted(Vec::new(), false)
+        })
+    }
+
+    async fn collect(job: &mut Job) -> Vec<Message> {
+        let mut messages = Vec::new();
+        while let Some(message) = job.rx.recv().await {
+            let done = message == Message::Done;
+            messages.push(message);
+            if done {
+                break;
+            }
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn chain_stops_after_a_failed_step() {
+        let started = Arc::new(AtomicBool::new(false));
+        let failed = Message::Result {
+            exit_code: 1,
+            status: "exited".to_string(),
+            id: String::new(),
+        };
+        let steps: Vec<Step> = vec![
+            Box::new(|| scripted(vec![log("one")], false)),
+            Box::new({
+                let failed = failed.clone();
+                move || scripted(vec![failed], false)
+            }),
+            never(&started),
+        ];
+        let messages = collect(&mut Job::chain(steps)).await;
+        assert_eq!(messages, [log("one"), failed, Message::Done]);
+        assert!(!started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn chain_cancel_stops_the_step_and_skips_the_rest() {
+        let started = Arc::new(AtomicBool::new(false));
+        let steps: Vec<Step> = vec![
+            Box::new(|| scripted(vec![log("waiting")], true)),
+            never(&started),
+            never(&started),
+        ];
+        let mut job = Job::chain(steps);
+        assert_eq!(job.rx.recv().await, Some(log("waiting")));
+        assert!(job.cancel.cancel());
+
+        let messages = collect(&mut job).await;
+        let error = |message: &str| Message::Error {
+            message: message.to_string(),
+            cause: String::new(),
+        };
+        assert_eq!(
+            messages,
+            [
+                error("cancelled"),
+                error("cancelled, 2 step(s) skipped"),
+                Message::Done
+            ]
+        );
+        assert!(!started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_polls_until_healthy() {
+        let backend = Arc::new(FakeBackend {
+            containers: Mutex::new(vec![container(
+                "app-db-1",
+                "running",
+                "Up 2 seconds (health: starting)",
+            )]),
+            ..Default::default()
+        });
+        let mut job = Job::wait_for(
+            backend.clone(),
+            "app-db-1".to_string(),
+            "service_healthy".to_string(),
+        );
+        assert_eq!(
+            job.rx.recv().await,
+            Some(log("Waiting for app-db-1 to be healthy"))
+        );
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        *backend.containers.lock().unwrap() =
+            vec![container("app-db-1", "running", "Up 7 seconds (healthy)")];
+
+        let messages = collect(&mut job).await;
+        assert_eq!(
+            messages,
+            [
+                Message::Result {
+                    exit_code: 0,
+                    status: "app-db-1 is healthy".to_string(),
+                    id: String::new(),
+                },
+                Message::Done
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_gives_up_after_the_limit() {
+        let backend = Arc::new(FakeBackend {
+            containers: Mutex::new(vec![container(
+                "app-db-1",
+                "running",
+                "Up 2 seconds (health: starting)",
+            )]),
+            ..Default::default()
+        });
+        let mut job = Job::wait_for(
+            backend,
+            "app-db-1".to_string(),
+            "service_healthy".to_string(),
+        );
+        let messages = collect(&mut job).await;
+        assert_eq!(
+            messages[1..],
+            [
+                Message::Error {
+                    message: "waiting for app-db-1".to_string(),
+                    cause: "not healthy after 120s".to_string(),
+                },
+                Message::Done
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_ignores_containers_of_other_projects() {
+        let backend = Arc::new(FakeBackend {
+            containers: Mutex::new(vec![
+                container("myapp-db", "running", "Up 1 minute (healthy)"),
+                container("app-db-backup", "running", "Up 1 minute (healthy)"),
+            ]),
+            ..Default::default()
+        });
+        let mut job = Job::wait_for(backend, "app-db".to_string(), "service_healthy".to_string());
+        let messages = collect(&mut job).await;
+        assert_eq!(
+            messages[1..],
+            [
+                Message::Error {
+                    message: "waiting for app-db".to_string(),
+                    cause: "container not found".to_string(),
+                },
+                Message::Done
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_can_be_cancelled() {
+        let backend = Arc::new(FakeBackend {
+            containers: Mutex::new(vec![container("app-migrate-1", "running", "Up 1 second")]),
+            ..Default::default()
+        });
+        let mut job = Job::wait_for(
+            backend,
+            "app-migrate-1".to_string(),
+            "service_completed_successfully".to_string(),
+        );
+        assert!(matches!(job.rx.recv().await, Some(Message::Log { .. })));
+        assert!(job.cancel.cancel());
+        let messages = collect(&mut job).await;
+        assert_eq!(
+            messages,
+            [
+                Message::Error {
+                    message: "waiting for app-migrate-1".to_string(),
+                    cause: "cancelled".to_string(),
+                },
+                Message::Done
+            ]
+        );
+    }
+}
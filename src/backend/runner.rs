@@ -0,0 +1,604 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::oneshot;
+
+use crate::app::{CPUUsage, DockerCompose, DockerImage, RunningContainer};
+use crate::backend::runtime::{self, Target};
+use crate::backend::{CreateOptions, DockerBackend, Job};
+use crate::config::{Config, Timeouts, Workspace};
+use crate::protocol::{self, Message};
+
+pub const RUNNER_NAME: &str = "easydocker-runner";
+
+/// Major version of the runner this build speaks to. Bump together with
+/// `Version` in runner/cmd/version.go when the CLI contract breaks.
+pub const SUPPORTED_RUNNER_MAJOR: u64 = 5;
+
+/// How long `<runner> version` may take before the runner counts as broken.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+struct FilePath {
+    filepath: String,
+}
+
+#[derive(Deserialize)]
+struct ImageJson {
+    repository: String,
+    tag: String,
+    image_id: String,
+    created: String,
+    size: String,
+}
+
+/// Backend that shells out to the `easydocker-runner` Go binary.
+pub struct RunnerBackend {
+    program: PathBuf,
+    timeouts: Timeouts,
+    target: Target,
+}
+
+impl Default for RunnerBackend {
+    fn default() -> Self {
+        Self::new(
+            PathBuf::from(RUNNER_NAME),
+            Timeouts::default(),
+            runtime::resolve(&Config::default()),
+        )
+    }
+}
+
+impl RunnerBackend {
+    pub fn new(program: PathBuf, timeouts: Timeouts, target: Target) -> Self {
+        Self {
+            program,
+            timeouts,
+            target,
+        }
+    }
+
+    // The runner reaches the runtime through DOCKER_HOST, like the docker CLI.
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.env("DOCKER_HOST", self.target.endpoint.host());
+        if let Some(dir) = &self.target.tls_dir {
+            command
+                .env("DOCKER_CERT_PATH", dir)
+                .env("DOCKER_TLS_VERIFY", "1");
+        }
+        command
+    }
+
+    /// Runs a query and decodes the items of its reply. A failure the
+    /// runner reported itself wins over its exit status and stderr.
+    async fn output_json<T: DeserializeOwned>(&self, args: &[&str]) -> Result<Vec<T>> {
+        let output = self
+            .command()
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .with_context(|| format!("failed to run {}", self.program.display()))?;
+
+        let command = format!("`{} {}`", RUNNER_NAME, args.join(" "));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let items: Result<Vec<T>> = stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| protocol::decode_data(line).transpose())
+            .collect();
+        if !output.status.success() {
+            let reason = match items {
+                Err(e) => format!("{:#}", e),
+                Ok(_) => exit_reason(output.status, &String::from_utf8_lossy(&output.stderr)),
+            };
+            bail!("{} {}", command, reason);
+        }
+        items.with_context(|| command)
+    }
+
+    /*
+     * Runs a lifecycle command and forwards its protocol messages
+     *
+     * stderr is collected on the side. When the process exits non-zero
+     * without having reported an error itself, a synthetic Error carrying
+     * the exit status and stderr is sent, so a failure never looks like a
+     * success. Cancelling the job or running past `timeout` kills the
+     * process and reports that instead. The runner's own Done is held back
+     * and re-sent last.
+     * */
+    fn spawn_messages(&self, args: Vec<String>, timeout: u64) -> Job {
+        let (tx, rx) = mpsc::channel::<Message>(100);
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        let program = self.program.clone();
+        let mut process = self.command();
+        let limit = Timeouts::limit(timeout);
+
+        tokio::spawn(async move {
+            let command = format!("`{} {}`", RUNNER_NAME, args.join(" "));
+            let mut child = match process
+                .args(&args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+            {
+                Ok(child) => child,
+                Err(e) => {
+                    let _ = tx
+                        .send(Message::Error {
+                            message: format!("failed to run {}", program.display()),
+                            cause: e.to_string(),
+                        })
+                        .await;
+                    let _ = tx.send(Message::Done).await;
+                    return;
+                }
+            };
+
+            let stderr_task = child.stderr.take().map(|mut stderr| {
+                tokio::spawn(async move {
+                    let mut buf = String::new();
+                    let _ = stderr.read_to_string(&mut buf).await;
+                    buf
+                })
+            });
+
+            // Returns whether the runner already reported a failure itself.
+            let stdout_task = child.stdout.take().map(|stdout| {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let mut reported_failure = false;
+                    let mut reader = BufReader::new(stdout).lines();
+
+                    while let Ok(Some(line)) = reader.next_line().await {
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        let message = Message::decode(&line);
+                        match &message {
+                            Message::Done => continue,
+                            Message::Error { .. } => reported_failure = true,
+                            Message::Result { exit_code, .. } if *exit_code != 0 => {
+                                reported_failure = true
+                            }
+                            _ => {}
+                        }
+                        let _ = tx.send(message).await;
+                    }
+                    reported_failure
+                })
+            });
+
+            let deadline = async {
+                match limit {
+                    Some(limit) => tokio::time::sleep(limit).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            let outcome = tokio::select! {
+                status = child.wait() => Outcome::Exited(status),
+                Ok(()) = &mut cancel_rx => Outcome::Interrupted(format!("{} cancelled", command)),
+                _ = deadline => Outcome::Interrupted(format!(
+                    "{} timed out after {}s",
+                    command, timeout
+                )),
+            };
+            if let Outcome::Interrupted(_) = outcome {
+                let _ = child.kill().await;
+                // Anything the runner spawned may still hold the pipes open.
+                if let Some(task) = &stdout_task {
+                    task.abort();
+                }
+                if let Some(task) = &stderr_task {
+                    task.abort();
+                }
+            }
+
+            let reported_failure = match stdout_task {
+                Some(task) => task.await.unwrap_or_default(),
+                None => false,
+            };
+            let stderr = match stderr_task {
+                Some(task) => task.await.unwrap_or_default(),
+                None => String::new(),
+            };
+
+            let failure = match outcome {
+                Outcome::Exited(Ok(status)) if status.success() || reported_failure => None,
+                Outcome::Exited(Ok(status)) => Some(Message::Error {
+                    message: format!("{} failed", command),
+                    cause: exit_reason(status, &stderr),
+                }),
+                Outcome::Exited(Err(e)) => Some(Message::Error {
+                    message: format!("lost track of {}", RUNNER_NAME),
+                    cause: e.to_string(),
+                }),
+                Outcome::Interrupted(message) => Some(Message::Error {
+                    message,
+                    cause: String::new(),
+                }),
+            };
+            if let Some(failure) = failure {
+                let _ = tx.send(failure).await;
+            }
+            let _ = tx.send(Message::Done).await;
+        });
+
+        Job::new(rx, cancel_tx)
+    }
+}
+
+enum Outcome {
+    Exited(std::io::Result<ExitStatus>),
+    Interrupted(String),
+}
+
+fn exit_reason(status: ExitStatus, stderr: &str) -> String {
+    let stderr = stderr.trim();
+    if stderr.is_empty() {
+        format!("exited with {}", status)
+    } else {
+        format!("exited with {}: {}", status, stderr)
+    }
+}
+
+#[async_trait]
+impl DockerBackend for RunnerBackend {
+    async fn search(&self, scope: &Workspace, names: &[&str]) -> Result<Vec<String>> {
+        let mut args = vec![
+            "search".to_string(),
+            "--max-depth".to_string(),
+            scope.max_depth.to_string(),
+        ];
+        for root in scope.expanded_roots() {
+            args.extend(["--root".to_string(), root]);
+        }
+        for glob in &scope.ignore {
+            args.extend(["--ignore".to_string(), glob.clone()]);
+        }
+        args.push("--".to_string());
+        args.extend(names.iter().map(|n| n.to_string()));
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Ok(self
+            .output_json::<FilePath>(&args)
+            .await?
+            .into_iter()
+            .map(|f| f.filepath)
+            .collect())
+    }
+
+    async fn read_compose(&self, path: &str) -> Result<Vec<DockerCompose>> {
+        self.output_json(&["read", "compose", path]).await
+    }
+
+    async fn images(&self) -> Result<Vec<DockerImage>> {
+        Ok(self
+            .output_json::<ImageJson>(&["images"])
+            .await?
+            .into_iter()
+            .map(|parsed| DockerImage {
+                repository: parsed.repository,
+                tag: parsed.tag,
+                image_id: parsed.image_id,
+                created: parsed.created,
+                size: parsed.size,
+            })
+            .collect())
+    }
+
+    async fn list(&self) -> Result<Vec<RunningContainer>> {
+        Ok(self.target.visible(self.output_json(&["list"]).await?))
+    }
+
+    async fn list_with_health(&self) -> Result<Vec<RunningContainer>> {
+        Ok(self
+            .target
+            .visible(self.output_json(&["list", "--health"]).await?))
+    }
+
+    fn start(&self, container_id: &str) -> Job {
+        self.spawn_messages(
+            vec!["start".to_string(), container_id.to_string()],
+            self.timeouts.start,
+        )
+    }
+
+    fn stop(&self, container_id: &str) -> Job {
+        self.spawn_messages(
+            vec!["stop".to_string(), container_id.to_string()],
+            self.timeouts.stop,
+        )
+    }
+
+    fn remove_container(&self, container_id: &str) -> Job {
+        self.spawn_messages(
+            vec!["rm".to_string(), container_id.to_string()],
+            self.timeouts.remove,
+        )
+    }
+
+    fn remove_image(&self, image_id: &str) -> Job {
+        self.spawn_messages(
+            vec!["rmi".to_string(), image_id.to_string()],
+            self.timeouts.remove,
+        )
+    }
+
+    fn create(&self, opts: &CreateOptions) -> Job {
+        let mut args = vec![
+            "create".to_string(),
+            "--image".to_string(),
+            opts.image.clone(),
+            "--start".to_string(),
+        ];
+
+        if !opts.name.is_empty() {
+            args.push("--name".to_string());
+            args.push(opts.name.clone());
+        }
+
+        if !opts.hostname.is_empty() {
+            args.push("--hostname".to_string());
+            args.push(opts.hostname.clone());
+        }
+
+        if !opts.ports.is_empty() {
+            args.push("--ports".to_string());
+            args.push(opts.ports.clone());
+        }
+
+        if !opts.environment.is_empty() {
+            args.push("--env".to_string());
+            args.push(opts.environment.join(","));
+        }
+
+        if !opts.volumes.is_empty() {
+            args.push("--volumes".to_string());
+            args.push(opts.volumes.join(","));
+        }
+
+        if !opts.restart.is_empty() {
+            args.push("--restart".to_string());
+            args.push(opts.restart.clone());
+        }
+
+        if !opts.build_context.is_empty() {
+            args.push("--build-context".to_string());
+            args.push(opts.build_context.clone());
+        }
+
+        self.spawn_messages(args, self.timeouts.create)
+    }
+
+    /*
+     * Forwards the stats samples of a container
+     *
+     * Samples come until the runner sends done or the receiver is dropped.
+     * An error the runner reports, a line it cannot have meant or an exit
+     * it gave no reason for ends the stream as its last item.
+     * */
+    fn stream(&self, container_id: &str) -> Receiver<Result<CPUUsage>> {
+        let (tx, rx) = mpsc::channel::<Result<CPUUsage>>(100);
+        let program = self.program.clone();
+        let mut process = self.command();
+        let container_id = container_id.to_string();
+
+        tokio::spawn(async move {
+            let command = format!("`{} stream {}`", RUNNER_NAME, container_id);
+            let mut child = match process
+                .args(["stream", &container_id])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+            {
+                Ok(child) => child,
+                Err(e) => {
+                    let _ = tx
+                        .send(Err(anyhow!("failed to run {}: {}", program.display(), e)))
+                        .await;
+                    return;
+                }
+            };
+
+            let stderr_task = child.stderr.take().map(|mut stderr| {
+                tokio::spawn(async move {
+                    let mut buf = String::new();
+                    let _ = stderr.read_to_string(&mut buf).await;
+                    buf
+                })
+            });
+
+            if let Some(stdout) = child.stdout.take() {
+                let mut reader = BufReader::new(stdout).lines();
+
+                while let Ok(Some(line)) = reader.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match protocol::decode_data::<CPUUsage>(&line) {
+                        Ok(Some(usage)) => {
+                            if tx.send(Ok(usage)).await.is_err() {
+                                return; // Receiver dropped
+                            }
+                        }
+                        Ok(None) => return,
+                        Err(e) => {
+                            let _ = tx.send(Err(e.context(command))).await;
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let status = child.wait().await;
+            let stderr = match stderr_task {
+                Some(task) => task.await.unwrap_or_default(),
+                None => String::new(),
+            };
+            let failure = match status {
+                Ok(status) if status.success() => return,
+                Ok(status) => anyhow!("{} {}", command, exit_reason(status, &stderr)),
+                Err(e) => anyhow!("lost track of {}: {}", RUNNER_NAME, e),
+            };
+            let _ = tx.send(Err(failure)).await;
+        });
+
+        rx
+    }
+}
+
+#[derive(Debug)]
+pub enum RunnerError {
+    NotFound { searched: Vec<PathBuf> },
+    Failed { path: PathBuf, reason: String },
+    Incompatible { path: PathBuf, version: String },
+}
+
+impl fmt::Display for RunnerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunnerError::NotFound { searched } => {
+                writeln!(f, "{} was not found. Looked in:", RUNNER_NAME)?;
+                for path in searched {
+                    writeln!(f, "  {}", path.display())?;
+                }
+                write!(
+                    f,
+                    "Install it next to easydocker, put it on PATH, or set EASYDOCKER_RUNNER."
+                )
+            }
+            RunnerError::Failed { path, reason } => {
+                write!(f, "Could not run {}: {}", path.display(), reason)
+            }
+            RunnerError::Incompatible { path, version } => write!(
+                f,
+                "{} reports version {:?}, but this easydocker needs runner v{}.x.\n\
+                 Rebuild both with `make all` so they stay in sync.",
+                path.display(),
+                version,
+                SUPPORTED_RUNNER_MAJOR
+            ),
+        }
+    }
+}
+
+/*
+ * Locating the runner
+ *
+ * Order: EASYDOCKER_RUNNER / config `runner`, then the directory holding the
+ * easydocker executable, then every entry of PATH.
+ * */
+pub fn locate(config: &Config) -> Result<PathBuf, RunnerError> {
+    let file_name = format!("{}{}", RUNNER_NAME, std::env::consts::EXE_SUFFIX);
+    let mut searched = Vec::new();
+
+    if let Some(configured) = &config.runner {
+        let path = PathBuf::from(configured);
+        if path.is_file() {
+            return Ok(path);
+        }
+        searched.push(path);
+    }
+
+    if let Ok(exe) = std::env::current_exe()
+        && let Some(dir) = exe.parent()
+    {
+        let path = dir.join(&file_name);
+        if path.is_file() {
+            return Ok(path);
+        }
+        searched.push(path);
+    }
+
+    if let Some(paths) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&paths) {
+            let path = dir.join(&file_name);
+            if path.is_file() {
+                return Ok(path);
+            }
+            searched.push(path);
+        }
+    }
+
+    Err(RunnerError::NotFound { searched })
+}
+
+/// Runs `<runner> version` and checks that its major version is supported.
+/// Returns the reported version string on success. A runner that does not
+/// answer within `HANDSHAKE_TIMEOUT` is killed and reported as failed.
+pub async fn handshake(path: &Path) -> Result<String, RunnerError> {
+    let mut command = Command::new(path);
+    command
+        .arg("version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(HANDSHAKE_TIMEOUT, command.output())
+        .await
+        .map_err(|_| RunnerError::Failed {
+            path: path.to_path_buf(),
+            reason: format!(
+                "`version` gave no answer within {}s",
+                HANDSHAKE_TIMEOUT.as_secs()
+            ),
+        })?
+        .map_err(|e| RunnerError::Failed {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(RunnerError::Failed {
+            path: path.to_path_buf(),
+            reason: format!(
+                "`version` exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = parse_version(&stdout).ok_or_else(|| RunnerError::Incompatible {
+        path: path.to_path_buf(),
+        version: stdout.trim().to_string(),
+    })?;
+
+    match version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u64>().ok())
+    {
+        Some(major) if major == SUPPORTED_RUNNER_MAJOR => Ok(version),
+        _ => Err(RunnerError::Incompatible {
+            path: path.to_path_buf(),
+            version,
+        }),
+    }
+}
+
+// "foreci runner v1.0 (abc123)" -> "1.0"
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix('v'))
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|v| v.to_string())
+}
@@ -0,0 +1,215 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::app::RunningContainer;
+use crate::backend::context::{self, DEFAULT_CONTEXT};
+use crate::backend::engine::Endpoint;
+use crate::config::Config;
+
+const DOCKER_SOCKET: &str = "/var/run/docker.sock";
+const PODMAN_ROOTFUL_SOCKET: &str = "/run/podman/podman.sock";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Runtime {
+    Docker,
+    Podman,
+}
+
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Runtime::Docker => write!(f, "docker"),
+            Runtime::Podman => write!(f, "podman"),
+        }
+    }
+}
+
+/// The container runtime in use and the API endpoint that reaches it.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub runtime: Runtime,
+    pub endpoint: Endpoint,
+    /// Docker context this came from, "default" for the local runtime.
+    pub context: String,
+    /// Client certificates for a TLS context (DOCKER_CERT_PATH).
+    pub tls_dir: Option<PathBuf>,
+}
+
+impl Target {
+    fn for_host(host: &str, requested: Option<Runtime>, context: &str) -> Self {
+        let runtime = requested.unwrap_or(if host.contains("podman") {
+            Runtime::Podman
+        } else {
+            Runtime::Docker
+        });
+        Target {
+            runtime,
+            endpoint: Endpoint::parse(host),
+            context: context.to_string(),
+            tls_dir: None,
+        }
+    }
+
+    /// Hides what the runtime adds on its own and the tabs should not show,
+    /// i.e. the infra container Podman starts for every pod.
+    pub fn visible(&self, containers: Vec<RunningContainer>) -> Vec<RunningContainer> {
+        if self.runtime != Runtime::Podman {
+            return containers;
+        }
+        containers
+            .into_iter()
+            .filter(|c| !is_pod_infra(c))
+            .collect()
+    }
+}
+
+/*
+ * Picking the runtime
+ *
+ * `runtime` in the config (or EASYDOCKER_RUNTIME) is "docker", "podman" or
+ * "auto" (the default). DOCKER_HOST wins for the endpoint, as it does for
+ * the docker and podman CLIs, then the current docker context. Without
+ * either, the runtime's usual sockets are tried in order: auto prefers a
+ * live Docker socket, then Podman's rootless socket, then the rootful one.
+ * */
+pub fn resolve(config: &Config) -> Target {
+    if let Ok(host) = std::env::var("DOCKER_HOST")
+        && !host.is_empty()
+    {
+        return Target::for_host(&host, requested(config), DEFAULT_CONTEXT);
+    }
+    resolve_context(config, &context::current())
+}
+
+/// Target for the docker context `name`. The default context, and any
+/// context that no longer exists, means the local runtime.
+pub fn resolve_context(config: &Config, name: &str) -> Target {
+    let requested = requested(config);
+
+    if name != DEFAULT_CONTEXT
+        && let Some(ctx) = context::find(name)
+        && !ctx.host.is_empty()
+    {
+        let mut target = Target::for_host(&ctx.host, requested, &ctx.name);
+        target.tls_dir = ctx.tls_dir;
+        return target;
+    }
+
+    let docker = docker_sockets();
+    let podman = podman_sockets();
+    let existing = |candidates: &[PathBuf]| candidates.iter().find(|p| p.exists()).cloned();
+
+    let (runtime, socket) = match requested {
+        Some(Runtime::Docker) => (
+            Runtime::Docker,
+            existing(&docker).unwrap_or(docker[0].clone()),
+        ),
+        Some(Runtime::Podman) => (
+            Runtime::Podman,
+            existing(&podman).unwrap_or(podman[0].clone()),
+        ),
+        None => match (existing(&docker), existing(&podman)) {
+            (Some(socket), _) => (Runtime::Docker, socket),
+            (None, Some(socket)) => (Runtime::Podman, socket),
+            (None, None) => (Runtime::Docker, docker[0].clone()),
+        },
+    };
+
+    Target {
+        runtime,
+        endpoint: Endpoint::Unix(socket),
+        context: DEFAULT_CONTEXT.to_string(),
+        tls_dir: None,
+    }
+}
+
+fn requested(config: &Config) -> Option<Runtime> {
+    match config.runtime.as_deref() {
+        Some("docker") => Some(Runtime::Docker),
+        Some("podman") => Some(Runtime::Podman),
+        _ => None,
+    }
+}
+
+fn docker_sockets() -> Vec<PathBuf> {
+    let mut sockets = vec![PathBuf::from(DOCKER_SOCKET)];
+    // Rootless dockerd
+    if let Some(dir) = runtime_dir() {
+        sockets.push(dir.join("docker.sock"));
+    }
+    sockets
+}
+
+fn podman_sockets() -> Vec<PathBuf> {
+    let mut sockets = Vec::new();
+    if let Some(dir) = runtime_dir() {
+        sockets.push(dir.join("podman").join("podman.sock"));
+    }
+    sockets.push(PathBuf::from(PODMAN_ROOTFUL_SOCKET));
+    sockets
+}
+
+fn runtime_dir() -> Option<PathBuf> {
+    std::env::var("XDG_RUNTIME_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+// Podman names a pod's infra container "<12 hex digits of the pod id>-infra"
+// and runs it from the pause image (localhost/podman-pause, or the
+// Kubernetes pause image on older versions). A service that merely ends in
+// "-infra" is not one.
+fn is_pod_infra(container: &RunningContainer) -> bool {
+    let image = container.image.rsplit('/').next().unwrap_or_default();
+    let image = image.split(['@', ':']).next().unwrap_or_default();
+    matches!(image, "podman-pause" | "pause")
+        || container.names.iter().any(|n| {
+            n.trim_start_matches('/')
+                .strip_suffix("-infra")
+                .is_some_and(|id| id.len() == 12 && id.bytes().all(|b| b.is_ascii_hexdigit()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(image: &str, name: &str) -> RunningContainer {
+        RunningContainer {
+            id: "0123456789ab".to_string(),
+            image: image.to_string(),
+            command: String::new(),
+            created: 0,
+            status: "Up 1 minute".to_string(),
+            state: "running".to_string(),
+            ports: None,
+            names: vec![format!("/{}", name)],
+            health: None,
+        }
+    }
+
+    #[test]
+    fn pod_infra_cases() {
+        let cases = [
+            ("localhost/podman-pause:4.9.3-0", "3f1c2a9b7d10-infra", true),
+            ("k8s.gcr.io/pause:3.5", "mypod-infra", true),
+            ("docker.io/library/alpine:3", "3f1c2a9b7d10-infra", true),
+            ("docker.io/library/postgres:16", "shop-infra", false),
+            ("docker.io/library/postgres:16", "3f1c2a9b7d1-infra", false),
+            ("registry.example.com:5000/pause:3.9", "web", true),
+            ("registry.example.com:5000/team/paused:1", "web", false),
+            ("ghcr.io/acme/podman-pause-exporter:1", "exporter", false),
+        ];
+        for (image, name, infra) in cases {
+            assert_eq!(
+                is_pod_infra(&container(image, name)),
+                infra,
+                "{image} {name}"
+            );
+        }
+    }
+}
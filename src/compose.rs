@@ -0,0 +1,1228 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result, bail};
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+/// A compose file change waiting for confirmation.
+pub struct FileEdit {
+    pub path: PathBuf,
+    pub contents: String,
+    /// Unified diff of the change, one entry per line.
+    pub diff: Vec<String>,
+    /// Removed variables no compose file sets (they come from env_file),
+    /// so removing them here changes nothing.
+    pub kept: Vec<String>,
+}
+
+/*
+ * Writing a service's environment back
+ *
+ * The YAML is edited line by line instead of being parsed and dumped
+ * again, so comments and layout outside the environment block survive,
+ * and so do the lines of variables that did not change. `original` is
+ * what the editor was opened with (after interpolation), `updated` what it
+ * holds now; only the difference between the two is written.
+ * */
+pub fn plan_environment(
+    files: &[String],
+    service: &str,
+    original: &[String],
+    updated: &[String],
+) -> Result<Vec<FileEdit>> {
+    let change = Change {
+        key: "environment",
+        list: true,
+        original: original.to_vec(),
+        updated: updated.to_vec(),
+    };
+    plan_service(files, service, &[change])
+}
+
+/// One field of a service as the form editor changed it.
+#[derive(Clone)]
+pub struct Change {
+    pub key: &'static str,
+    /// Whether the field is a list (ports, volumes, ...) or a scalar, which
+    /// has at most one value.
+    pub list: bool,
+    pub original: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+/*
+ * Writing service fields back
+ *
+ * A changed scalar goes to the file that sets it, or else to the last file
+ * defining the service. Entries of the environment and of lists go to the
+ * file that lists them, so changing a variable the base file sets edits
+ * the base file even when the override has an environment of its own; new
+ * entries follow the scalar rule. A save can touch several files and there
+ * is one edit per file. Lists are edited like the environment: entries
+ * that stay keep their lines (long-syntax ones included), removed ones go,
+ * new ones are appended.
+ * */
+pub fn plan_service(files: &[String], service: &str, changes: &[Change]) -> Result<Vec<FileEdit>> {
+    let mut parts: Vec<(PathBuf, Change)> = Vec::new();
+    for change in changes {
+        let path = pick_file(files, service, change.key)?;
+        if change.list {
+            parts.extend(split_by_file(files, service, change, &path));
+        } else {
+            parts.push((path, change.clone()));
+        }
+    }
+
+    let mut sources: Vec<(PathBuf, String, String, Vec<String>)> = Vec::new();
+    for (path, change) in &parts {
+        let path = path.clone();
+        let pos = match sources.iter().position(|(p, ..)| *p == path) {
+            Some(pos) => pos,
+            None => {
+                let source = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Cannot read {}", path.display()))?;
+                sources.push((path.clone(), source.clone(), source, Vec::new()));
+                sources.len() - 1
+            }
+        };
+
+        let (_, _, contents, kept) = &mut sources[pos];
+        let (updated, mut missing) = if change.key == "environment" {
+            set_environment(contents, service, &change.original, &change.updated)
+        } else if change.list {
+            set_list(
+                contents,
+                service,
+                change.key,
+                &change.original,
+                &change.updated,
+            )
+        } else {
+            let value = change.updated.first().map(String::as_str);
+            set_scalar(contents, service, change.key, value).map(|c| (c, Vec::new()))
+        }
+        .with_context(|| format!("Cannot update {} in {}", change.key, path.display()))?;
+        *contents = updated;
+        kept.append(&mut missing);
+    }
+
+    Ok(sources
+        .into_iter()
+        .filter(|(_, source, contents, _)| source != contents)
+        .map(|(path, source, contents, kept)| file_edit(path, &source, contents, kept))
+        .collect())
+}
+
+/// The edit turning `source` into `contents`, with its unified diff.
+pub fn file_edit(path: PathBuf, source: &str, contents: String, kept: Vec<String>) -> FileEdit {
+    let name = path.display().to_string();
+    let diff = TextDiff::from_lines(source, &contents)
+        .unified_diff()
+        .context_radius(3)
+        .header(&name, &name)
+        .to_string()
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    FileEdit {
+        path,
+        contents,
+        diff,
+        kept,
+    }
+}
+
+/// Writes `contents` to `path`, copying the current file to `<path>.bak`
+/// first unless there is a backup already, so the file as it was before the
+/// first save is never overwritten. Returns the backup's path, or None when
+/// there was no file yet.
+pub fn write_with_backup(path: &Path, contents: &str) -> Result<Option<PathBuf>> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let backup = if path.exists() {
+        let backup = path.with_file_name(format!("{}.bak", name));
+        if !backup.exists() {
+            std::fs::copy(path, &backup).with_context(|| {
+                format!("Cannot back up {} to {}", path.display(), backup.display())
+            })?;
+        }
+        Some(backup)
+    } else {
+        None
+    };
+
+    // Written next to the file and renamed over it, so a failed write
+    // never leaves half a compose file behind.
+    let tmp = path.with_file_name(format!(".{}.easydocker", name));
+    std::fs::write(&tmp, contents).with_context(|| format!("Cannot write {}", tmp.display()))?;
+    if let Ok(meta) = std::fs::metadata(path) {
+        let _ = std::fs::set_permissions(&tmp, meta.permissions());
+    }
+    std::fs::rename(&tmp, path).with_context(|| format!("Cannot replace {}", path.display()))?;
+    Ok(backup)
+}
+
+/// A service's fields as written in the compose files, before any `${VAR}`
+/// is substituted and before paths are resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RawService {
+    pub image: String,
+    pub container_name: String,
+    pub hostname: String,
+    pub restart: String,
+    pub ports: Vec<String>,
+    pub volumes: Vec<String>,
+    pub networks: Vec<String>,
+    pub environment: Vec<String>,
+}
+
+impl RawService {
+    /// The values of the field `key`; a scalar has at most one.
+    pub fn field(&self, key: &str) -> Vec<String> {
+        let scalar = |s: &String| {
+            if s.is_empty() {
+                Vec::new()
+            } else {
+                vec![s.clone()]
+            }
+        };
+        match key {
+            "image" => scalar(&self.image),
+            "container_name" => scalar(&self.container_name),
+            "hostname" => scalar(&self.hostname),
+            "restart" => scalar(&self.restart),
+            "ports" => self.ports.clone(),
+            "volumes" => self.volumes.clone(),
+            "networks" => self.networks.clone(),
+            "environment" => self.environment.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/*
+ * Raw service values
+ *
+ * The runner only reports values after interpolation. To show what they
+ * were made from, the files are read again here and merged the way
+ * compose merges overrides: the last scalar wins, ports, volumes and
+ * networks add up and environment entries are replaced by key. Long-syntax
+ * entries are shown in their short form (see `item_text`).
+ * */
+pub fn raw_service(files: &[String], service: &str) -> RawService {
+    let mut raw = RawService::default();
+    for file in files {
+        let Ok(source) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let Ok(doc) = serde_yaml::from_str::<serde_yaml::Value>(&source) else {
+            continue;
+        };
+        let Some(svc) = doc.get("services").and_then(|s| s.get(service)) else {
+            continue;
+        };
+
+        for (key, value) in [
+            ("image", &mut raw.image),
+            ("container_name", &mut raw.container_name),
+            ("hostname", &mut raw.hostname),
+            ("restart", &mut raw.restart),
+        ] {
+            if let Some(set) = svc.get(key).and_then(yaml_scalar) {
+                *value = set;
+            }
+        }
+        for (key, values) in [
+            ("ports", &mut raw.ports),
+            ("volumes", &mut raw.volumes),
+            ("networks", &mut raw.networks),
+        ] {
+            for item in svc.get(key).map(|v| list_items(key, v)).unwrap_or_default() {
+                if key == "ports" || !values.contains(&item) {
+                    values.push(item);
+                }
+            }
+        }
+
+        let entries: Vec<String> = match svc.get("environment") {
+            Some(serde_yaml::Value::Sequence(list)) => {
+                list.iter().filter_map(yaml_scalar).collect()
+            }
+            Some(serde_yaml::Value::Mapping(map)) => map
+                .iter()
+                .filter_map(|(key, value)| {
+                    let key = yaml_scalar(key)?;
+                    Some(match yaml_scalar(value) {
+                        Some(value) => format!("{}={}", key, value),
+                        None => key,
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for entry in entries {
+            let key = split_env(&entry).0.to_string();
+            match raw.environment.iter_mut().find(|e| split_env(e).0 == key) {
+                Some(existing) => *existing = entry,
+                None => raw.environment.push(entry),
+            }
+        }
+    }
+    raw
+}
+
+/// Entries of a list field: a sequence, or the keys of a mapping
+/// (`networks: {front: {}}`).
+fn list_items(key: &str, value: &serde_yaml::Value) -> Vec<String> {
+    match value {
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .filter_map(|item| item_text(key, item))
+            .collect(),
+        serde_yaml::Value::Mapping(map) => map.keys().filter_map(yaml_scalar).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How a list entry shows up in the form: scalars as they are, long-syntax
+/// ports as `published:target[/protocol]` and long-syntax volumes as
+/// `source:target[:ro]`.
+fn item_text(key: &str, item: &serde_yaml::Value) -> Option<String> {
+    if let Some(text) = yaml_scalar(item) {
+        return Some(text);
+    }
+    let field = |name: &str| item.get(name).and_then(yaml_scalar);
+    let target = field("target")?;
+    let text = match key {
+        "ports" => {
+            let mut text = match field("published") {
+                Some(published) => format!("{}:{}", published, target),
+                None => target,
+            };
+            if let Some(protocol) = field("protocol").filter(|p| p != "tcp") {
+                text = format!("{}/{}", text, protocol);
+            }
+            text
+        }
+        "volumes" => {
+            let mut text = match field("source") {
+                Some(source) => format!("{}:{}", source, target),
+                None => target,
+            };
+            if field("read_only").as_deref() == Some("true") {
+                text.push_str(":ro");
+            }
+            text
+        }
+        _ => target,
+    };
+    Some(text)
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// The file `service`'s `key` lives in: the last of `files` (the order
+/// they are merged in) that sets it, else the last that defines the
+/// service at all.
+fn pick_file(files: &[String], service: &str, key: &str) -> Result<PathBuf> {
+    if files.is_empty() {
+        bail!(
+            "The runner did not report which file defines {}; refresh with a newer runner",
+            service
+        );
+    }
+
+    let mut defines = None;
+    for file in files.iter().rev() {
+        let Ok(source) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let lines: Vec<&str> = source.lines().collect();
+        if let Some((start, end)) = service_block(&lines, service) {
+            if find_key(&lines, start + 1, end, key).is_some() {
+                return Ok(PathBuf::from(file));
+            }
+            defines.get_or_insert_with(|| PathBuf::from(file));
+        }
+    }
+    defines.with_context(|| format!("No compose file defines service {}", service))
+}
+
+/*
+ * Entries of a list field by file
+ *
+ * Splits `change` of an environment or list field into one change per
+ * file: every entry goes to the last of `files` listing it (by key for the
+ * environment, by its text for lists), entries no file lists to `default`.
+ * Entries that did not change land in the same file on both sides and
+ * cancel out there; files left without a difference are dropped.
+ * */
+fn split_by_file(
+    files: &[String],
+    service: &str,
+    change: &Change,
+    default: &Path,
+) -> Vec<(PathBuf, Change)> {
+    let environment = change.key == "environment";
+    let identity = |entry: &str| {
+        if environment {
+            split_env(entry).0.to_string()
+        } else {
+            entry.to_string()
+        }
+    };
+
+    let listed: Vec<(PathBuf, Vec<String>)> = files
+        .iter()
+        .map(|file| {
+            let entries = std::fs::read_to_string(file)
+                .ok()
+                .and_then(|source| serde_yaml::from_str::<serde_yaml::Value>(&source).ok())
+                .and_then(|doc| {
+                    let value = doc.get("services")?.get(service)?.get(change.key)?.clone();
+                    Some(if environment {
+                        env_keys(&value)
+                    } else {
+                        list_items(change.key, &value)
+                    })
+                })
+                .unwrap_or_default();
+            (PathBuf::from(file), entries)
+        })
+        .collect();
+    let owner = |entry: &String| {
+        let id = identity(entry);
+        listed
+            .iter()
+            .rev()
+            .find(|(_, entries)| entries.contains(&id))
+            .map_or_else(|| default.to_path_buf(), |(path, _)| path.clone())
+    };
+
+    let mut parts: Vec<(PathBuf, Change)> = Vec::new();
+    for (entry, updated) in change
+        .original
+        .iter()
+        .map(|e| (e, false))
+        .chain(change.updated.iter().map(|e| (e, true)))
+    {
+        let path = owner(entry);
+        let pos = match parts.iter().position(|(p, _)| *p == path) {
+            Some(pos) => pos,
+            None => {
+                let empty = Change {
+                    original: Vec::new(),
+                    updated: Vec::new(),
+                    ..change.clone()
+                };
+                parts.push((path, empty));
+                parts.len() - 1
+            }
+        };
+        let part = &mut parts[pos].1;
+        if updated {
+            part.updated.push(entry.clone());
+        } else {
+            part.original.push(entry.clone());
+        }
+    }
+    parts.retain(|(_, part)| {
+        let mut original = part.original.clone();
+        let mut updated = part.updated.clone();
+        original.sort();
+        updated.sort();
+        original != updated
+    });
+    parts
+}
+
+/// Keys of an `environment` value, list or map style.
+fn env_keys(value: &serde_yaml::Value) -> Vec<String> {
+    match value {
+        serde_yaml::Value::Sequence(list) => list
+            .iter()
+            .filter_map(yaml_scalar)
+            .map(|entry| split_env(&entry).0.to_string())
+            .collect(),
+        serde_yaml::Value::Mapping(map) => map.keys().filter_map(yaml_scalar).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    List,
+    Map,
+}
+
+fn set_environment(
+    source: &str,
+    service: &str,
+    original: &[String],
+    updated: &[String],
+) -> Result<(String, Vec<String>)> {
+    let original: Vec<(&str, Option<&str>)> = original.iter().map(|e| split_env(e)).collect();
+    let updated: Vec<(&str, Option<&str>)> = updated.iter().map(|e| split_env(e)).collect();
+    let changed: Vec<(&str, Option<&str>)> = updated
+        .iter()
+        .copied()
+        .filter(|entry| !original.contains(entry))
+        .collect();
+    let mut removed: Vec<&str> = original
+        .iter()
+        .map(|(key, _)| *key)
+        .filter(|key| !updated.iter().any(|(k, _)| k == key))
+        .collect();
+
+    let lines: Vec<&str> = source.lines().collect();
+    let (svc_line, svc_end) = editable_service(&lines, service)?;
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len() + changed.len() + 1);
+    let mut written: Vec<&str> = Vec::new();
+
+    match find_key(&lines, svc_line + 1, svc_end, "environment") {
+        Some(env_line) => {
+            let indent = indent_of(lines[env_line]);
+            let (_, rest) = mapping_key(lines[env_line].trim_start()).unwrap_or_default();
+            out.extend(lines[..env_line].iter().map(|l| l.to_string()));
+
+            if rest.is_empty() {
+                let end = block_end(&lines, env_line);
+                let first = (env_line + 1..end).find(|&i| !is_blank_or_comment(lines[i]));
+                let (style, entry_indent) = match first {
+                    Some(i) if lines[i].trim_start().starts_with('-') => {
+                        (Style::List, indent_of(lines[i]))
+                    }
+                    Some(i) => (Style::Map, indent_of(lines[i])),
+                    None => (Style::List, indent + 2),
+                };
+
+                let mut block = Vec::new();
+                let mut has_entries = false;
+                // Continuation lines (block scalars) follow their entry.
+                let mut skipping = false;
+                for &line in &lines[env_line + 1..end] {
+                    if is_blank_or_comment(line) {
+                        block.push(line.to_string());
+                        continue;
+                    }
+                    if indent_of(line) > entry_indent {
+                        if !skipping {
+                            block.push(line.to_string());
+                        }
+                        continue;
+                    }
+                    skipping = false;
+                    let key = entry_key(line.trim_start(), style).unwrap_or_default();
+                    if removed.contains(&key.as_str()) {
+                        removed.retain(|k| *k != key);
+                        skipping = true;
+                    } else if let Some(&(k, value)) = changed.iter().find(|(k, _)| *k == key) {
+                        block.push(render(style, entry_indent, k, value));
+                        written.push(k);
+                        skipping = true;
+                    } else {
+                        block.push(line.to_string());
+                        has_entries = true;
+                    }
+                }
+                for &(key, value) in changed.iter().filter(|(k, _)| !written.contains(k)) {
+                    block.push(render(style, entry_indent, key, value));
+                }
+                has_entries |= !changed.is_empty();
+
+                if has_entries {
+                    out.push(lines[env_line].to_string());
+                    out.extend(block);
+                }
+                out.extend(lines[end..].iter().map(|l| l.to_string()));
+            } else {
+                // Flow style ([A=1, B=2] or {A: 1}) is rewritten as a block
+                // list with the keys it had plus the changed ones.
+                let listed = flow_keys(rest);
+                removed.retain(|k| !listed.contains(&k.to_string()));
+                let entries: Vec<(&str, Option<&str>)> = updated
+                    .iter()
+                    .copied()
+                    .filter(|(k, _)| {
+                        listed.contains(&k.to_string()) || changed.iter().any(|(c, _)| c == k)
+                    })
+                    .collect();
+                if !entries.is_empty() {
+                    out.push(format!("{}environment:", " ".repeat(indent)));
+                    for (key, value) in entries {
+                        out.push(render(Style::List, indent + 2, key, value));
+                    }
+                }
+                out.extend(lines[env_line + 1..].iter().map(|l| l.to_string()));
+            }
+        }
+        None => {
+            let body_indent = body_indent(&lines, svc_line, svc_end);
+            out.extend(lines[..svc_end].iter().map(|l| l.to_string()));
+            if !changed.is_empty() {
+                out.push(format!("{}environment:", " ".repeat(body_indent)));
+                for &(key, value) in &changed {
+                    out.push(render(Style::List, body_indent + 2, key, value));
+                }
+            }
+            out.extend(lines[svc_end..].iter().map(|l| l.to_string()));
+        }
+    }
+
+    let contents = join_lines(out, source);
+    Ok((contents, removed.into_iter().map(str::to_string).collect()))
+}
+
+/// Sets, or with None removes, a scalar key of the service.
+fn set_scalar(source: &str, service: &str, key: &str, value: Option<&str>) -> Result<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let (svc_line, svc_end) = editable_service(&lines, service)?;
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len() + 1);
+    match find_key(&lines, svc_line + 1, svc_end, key) {
+        Some(key_line) => {
+            let indent = indent_of(lines[key_line]);
+            out.extend(lines[..key_line].iter().map(|l| l.to_string()));
+            if let Some(value) = value {
+                out.push(format!("{}{}: {}", " ".repeat(indent), key, scalar(value)));
+            }
+            out.extend(
+                lines[block_end(&lines, key_line)..]
+                    .iter()
+                    .map(|l| l.to_string()),
+            );
+        }
+        None => {
+            let indent = body_indent(&lines, svc_line, svc_end);
+            out.extend(lines[..svc_end].iter().map(|l| l.to_string()));
+            if let Some(value) = value {
+                out.push(format!("{}{}: {}", " ".repeat(indent), key, scalar(value)));
+            }
+            out.extend(lines[svc_end..].iter().map(|l| l.to_string()));
+        }
+    }
+    Ok(join_lines(out, source))
+}
+
+/*
+ * Editing a list field
+ *
+ * Entries are told apart by their text as the form shows it (`item_text`),
+ * so a long-syntax entry that stays keeps all its lines. `original` and
+ * `updated` are compared as multisets; what is left of `original` once
+ * `updated` is taken out gets removed, the rest of `updated` is appended.
+ * Removed entries not found in this file are returned, like the
+ * environment's.
+ * */
+fn set_list(
+    source: &str,
+    service: &str,
+    key: &str,
+    original: &[String],
+    updated: &[String],
+) -> Result<(String, Vec<String>)> {
+    let mut removed: Vec<String> = original.to_vec();
+    let mut added: Vec<String> = Vec::new();
+    for item in updated {
+        match removed.iter().position(|r| r == item) {
+            Some(pos) => {
+                removed.remove(pos);
+            }
+            None => added.push(item.clone()),
+        }
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let (svc_line, svc_end) = editable_service(&lines, service)?;
+    let mut out: Vec<String> = Vec::with_capacity(lines.len() + added.len() + 1);
+
+    let Some(key_line) = find_key(&lines, svc_line + 1, svc_end, key) else {
+        let indent = body_indent(&lines, svc_line, svc_end);
+        out.extend(lines[..svc_end].iter().map(|l| l.to_string()));
+        if !added.is_empty() {
+            out.push(format!("{}{}:", " ".repeat(indent), key));
+            for item in &added {
+                out.push(render_item(Style::List, indent + 2, key, item));
+            }
+        }
+        out.extend(lines[svc_end..].iter().map(|l| l.to_string()));
+        return Ok((join_lines(out, source), removed));
+    };
+
+    let indent = indent_of(lines[key_line]);
+    let (_, rest) = mapping_key(lines[key_line].trim_start()).unwrap_or_default();
+    out.extend(lines[..key_line].iter().map(|l| l.to_string()));
+
+    if !rest.is_empty() {
+        // Flow style is rewritten as a block with what stays plus what was
+        // added.
+        let flow: serde_yaml::Value = serde_yaml::from_str(rest)
+            .with_context(|| format!("cannot parse {}: {}", key, rest))?;
+        let style = if flow.is_mapping() {
+            Style::Map
+        } else {
+            Style::List
+        };
+        let mut items = list_items(key, &flow);
+        for item in &removed.clone() {
+            if let Some(pos) = items.iter().position(|i| i == item) {
+                items.remove(pos);
+                removed.retain(|r| r != item);
+            }
+        }
+        items.extend(added);
+        if !items.is_empty() {
+            out.push(format!("{}{}:", " ".repeat(indent), key));
+            for item in &items {
+                out.push(render_item(style, indent + 2, key, item));
+            }
+        }
+        out.extend(lines[key_line + 1..].iter().map(|l| l.to_string()));
+        return Ok((join_lines(out, source), removed));
+    }
+
+    let end = block_end(&lines, key_line);
+    let first = (key_line + 1..end).find(|&i| !is_blank_or_comment(lines[i]));
+    let (style, entry_indent) = match first {
+        Some(i) if lines[i].trim_start().starts_with('-') => (Style::List, indent_of(lines[i])),
+        Some(i) => (Style::Map, indent_of(lines[i])),
+        None => (Style::List, indent + 2),
+    };
+
+    let mut block: Vec<String> = Vec::new();
+    let mut has_entries = false;
+    let mut i = key_line + 1;
+    while i < end {
+        if is_blank_or_comment(lines[i]) {
+            block.push(lines[i].to_string());
+            i += 1;
+            continue;
+        }
+        // An entry and the deeper lines of its long syntax.
+        let start = i;
+        i += 1;
+        while i < end && (is_blank_or_comment(lines[i]) || indent_of(lines[i]) > entry_indent) {
+            i += 1;
+        }
+        while i > start + 1 && is_blank_or_comment(lines[i - 1]) {
+            i -= 1;
+        }
+
+        let text = match style {
+            Style::List => {
+                let entry: Vec<&str> = lines[start..i]
+                    .iter()
+                    .map(|l| l.get(entry_indent..).unwrap_or(l.trim_start()))
+                    .collect();
+                serde_yaml::from_str::<serde_yaml::Value>(&entry.join("\n"))
+                    .ok()
+                    .and_then(|v| v.as_sequence().and_then(|s| s.first()).cloned())
+                    .and_then(|v| item_text(key, &v))
+            }
+            Style::Map => mapping_key(lines[start].trim_start()).map(|(k, _)| k),
+        };
+        match text.and_then(|t| removed.iter().position(|r| *r == t)) {
+            Some(pos) => {
+                removed.remove(pos);
+            }
+            None => {
+                block.extend(lines[start..i].iter().map(|l| l.to_string()));
+                has_entries = true;
+            }
+        }
+    }
+    for item in &added {
+        block.push(render_item(style, entry_indent, key, item));
+    }
+    has_entries |= !added.is_empty();
+
+    if has_entries {
+        out.push(lines[key_line].to_string());
+        out.extend(block);
+    }
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    Ok((join_lines(out, source), removed))
+}
+
+/// A new list entry. Ports are always quoted: YAML 1.1 parsers read an
+/// unquoted `80:80` as a base 60 number.
+fn render_item(style: Style, indent: usize, key: &str, item: &str) -> String {
+    let pad = " ".repeat(indent);
+    match style {
+        Style::List if key == "ports" => format!(
+            "{}- \"{}\"",
+            pad,
+            item.replace('\\', "\\\\").replace('"', "\\\"")
+        ),
+        Style::List => format!("{}- {}", pad, scalar(item)),
+        Style::Map => format!("{}{}:", pad, scalar(item)),
+    }
+}
+
+/// The service's block, refusing services written inline.
+fn editable_service(lines: &[&str], service: &str) -> Result<(usize, usize)> {
+    let Some((svc_line, svc_end)) = service_block(lines, service) else {
+        bail!("service {} not found", service);
+    };
+    if let Some((_, rest)) = mapping_key(lines[svc_line].trim_start())
+        && !rest.is_empty()
+    {
+        bail!("service {} is written inline; edit it by hand", service);
+    }
+    Ok((svc_line, svc_end))
+}
+
+/// Indent of the keys inside the service's mapping.
+fn body_indent(lines: &[&str], svc_line: usize, svc_end: usize) -> usize {
+    (svc_line + 1..svc_end)
+        .find(|&i| !is_blank_or_comment(lines[i]))
+        .map(|i| indent_of(lines[i]))
+        .unwrap_or(indent_of(lines[svc_line]) + 2)
+}
+
+fn join_lines(lines: Vec<String>, source: &str) -> String {
+    let mut contents = lines.join("\n");
+    if source.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents
+}
+
+/// Lines of the service's own mapping: its key line and the end of its
+/// block.
+fn service_block(lines: &[&str], service: &str) -> Option<(usize, usize)> {
+    let services = find_key(lines, 0, lines.len(), "services")?;
+    let end = block_end(lines, services);
+    let line = find_key(lines, services + 1, end, service)?;
+    Some((line, block_end(lines, line)))
+}
+
+/// Line of `key:` among the entries of the block [start, end), which sit
+/// at the indent of its first content line.
+fn find_key(lines: &[&str], start: usize, end: usize, key: &str) -> Option<usize> {
+    let indent = (start..end)
+        .find(|&i| !is_blank_or_comment(lines[i]))
+        .map(|i| indent_of(lines[i]))?;
+    (start..end).find(|&i| {
+        let line = lines[i];
+        !is_blank_or_comment(line)
+            && indent_of(line) == indent
+            && mapping_key(line.trim_start()).is_some_and(|(k, _)| k == key)
+    })
+}
+
+/// One past the last line belonging to the value of the key at
+/// `key_line`: deeper lines, or sequence items at the key's own indent.
+/// Trailing blank and comment lines are left to what follows.
+fn block_end(lines: &[&str], key_line: usize) -> usize {
+    let indent = indent_of(lines[key_line]);
+    let mut end = key_line + 1;
+    for (i, line) in lines.iter().enumerate().skip(key_line + 1) {
+        if is_blank_or_comment(line) {
+            continue;
+        }
+        let deeper = indent_of(line) > indent;
+        let item = indent_of(line) == indent && line.trim_start().starts_with('-');
+        if !deeper && !item {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+/// Splits `key: rest` off a mapping line, with any comment after the key
+/// dropped from `rest`.
+fn mapping_key(content: &str) -> Option<(String, &str)> {
+    let (key, rest) = match content.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = content[1..].find(quote)? + 1;
+            (
+                content[1..close].to_string(),
+                content[close + 1..].trim_start(),
+            )
+        }
+        '-' | '#' => return None,
+        _ => {
+            let colon = content.char_indices().find(|&(i, c)| {
+                c == ':' && content[i + 1..].chars().next().is_none_or(|n| n == ' ')
+            })?;
+            (
+                content[..colon.0].trim_end().to_string(),
+                &content[colon.0..],
+            )
+        }
+    };
+    let rest = rest.strip_prefix(':')?.trim();
+    let rest = if rest.starts_with('#') { "" } else { rest };
+    Some((key, rest))
+}
+
+fn entry_key(content: &str, style: Style) -> Option<String> {
+    match style {
+        Style::List => {
+            let item = content.strip_prefix('-')?.trim();
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            Some(split_env(unquote(item)).0.to_string())
+        }
+        Style::Map => mapping_key(content).map(|(key, _)| key),
+    }
+}
+
+fn flow_keys(flow: &str) -> Vec<String> {
+    flow.trim_matches(|c| matches!(c, '[' | ']' | '{' | '}' | ' '))
+        .split(',')
+        .map(|item| {
+            let item = unquote(item.trim());
+            let end = item.find(['=', ':']).unwrap_or(item.len());
+            unquote(item[..end].trim()).to_string()
+        })
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+fn render(style: Style, indent: usize, key: &str, value: Option<&str>) -> String {
+    let pad = " ".repeat(indent);
+    match (style, value) {
+        (Style::List, Some(value)) => format!("{}- {}", pad, scalar(&format!("{}={}", key, value))),
+        (Style::List, None) => format!("{}- {}", pad, scalar(key)),
+        (Style::Map, Some(value)) => format!("{}{}: {}", pad, key, scalar(value)),
+        (Style::Map, None) => format!("{}{}:", pad, key),
+    }
+}
+
+/// `s` as a YAML scalar, double-quoted when a plain one would be read as
+/// something else.
+fn scalar(s: &str) -> String {
+    let special = s.is_empty()
+        || s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@` ".contains(c))
+        || s.ends_with([' ', ':'])
+        || s.contains(": ")
+        || s.contains(" #")
+        || matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "~"
+        );
+    if special {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn split_env(entry: &str) -> (&str, Option<&str>) {
+    match entry.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (entry, None),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    for quote in ['"', '\''] {
+        if s.len() >= 2 && s.starts_with(quote) && s.ends_with(quote) {
+            return &s[1..s.len() - 1];
+        }
+    }
+    s
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// A fresh temp directory holding `files`, removed on drop.
+    struct Dir(PathBuf);
+
+    impl Dir {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "easydocker-compose-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file, contents) in files {
+                std::fs::write(dir.join(file), contents).unwrap();
+            }
+            Dir(dir)
+        }
+
+        fn path(&self, file: &str) -> String {
+            self.0.join(file).display().to_string()
+        }
+    }
+
+    impl Drop for Dir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn set_environment_cases() {
+        // (name, source, original, updated, expected contents, kept)
+        type Case<'a> = (
+            &'a str,
+            &'a str,
+            &'a [&'a str],
+            &'a [&'a str],
+            &'a str,
+            &'a [&'a str],
+        );
+        let cases: &[Case] = &[
+            (
+                "list style",
+                "services:\n  web:\n    image: nginx\n    environment:\n      - A=1\n      - B=2  # two\n    ports:\n      - \"80:80\"\n",
+                &["A=1", "B=2"],
+                &["A=1", "B=3", "C=4"],
+                "services:\n  web:\n    image: nginx\n    environment:\n      - A=1\n      - B=3\n      - C=4\n    ports:\n      - \"80:80\"\n",
+                &[],
+            ),
+            (
+                "list style, items at the key's indent",
+                "services:\n  web:\n    environment:\n    - A=1\n    - B\n",
+                &["A=1", "B"],
+                &["A=2", "B"],
+                "services:\n  web:\n    environment:\n    - A=2\n    - B\n",
+                &[],
+            ),
+            (
+                "map style",
+                "services:\n  web:\n    environment:\n      A: \"1\"\n      B: two\n",
+                &["A=1", "B=two"],
+                &["B=three", "C=yes"],
+                "services:\n  web:\n    environment:\n      B: three\n      C: \"yes\"\n",
+                &[],
+            ),
+            (
+                "flow list",
+                "services:\n  web:\n    environment: [A=1, B=2]\n    image: nginx\n",
+                &["A=1", "B=2"],
+                &["A=1", "B=3"],
+                "services:\n  web:\n    environment:\n      - A=1\n      - B=3\n    image: nginx\n",
+                &[],
+            ),
+            (
+                "flow map",
+                "services:\n  web:\n    environment: {A: 1}\n",
+                &["A=1"],
+                &["A=1", "C=3"],
+                "services:\n  web:\n    environment:\n      - A=1\n      - C=3\n",
+                &[],
+            ),
+            (
+                "block scalar",
+                "services:\n  web:\n    environment:\n      CERT: |\n        line one\n        line two\n      B: x\n",
+                &["CERT=line one\nline two\n", "B=x"],
+                &["CERT=short", "B=x"],
+                "services:\n  web:\n    environment:\n      CERT: short\n      B: x\n",
+                &[],
+            ),
+            (
+                "quoted keys",
+                "services:\n  \"web\":\n    environment:\n      \"A.B\": 1\n      'C': 2\n",
+                &["A.B=1", "C=2"],
+                &["C=2"],
+                "services:\n  \"web\":\n    environment:\n      'C': 2\n",
+                &[],
+            ),
+            (
+                "comments",
+                "services:\n  web:\n    environment: # settings\n      # the first\n      - A=1\n\n      - B=2\n# trailing\n",
+                &["A=1", "B=2"],
+                &["A=1", "B=5"],
+                "services:\n  web:\n    environment: # settings\n      # the first\n      - A=1\n\n      - B=5\n# trailing\n",
+                &[],
+            ),
+            (
+                "everything removed",
+                "services:\n  web:\n    environment:\n      - A=1\n    image: nginx\n",
+                &["A=1"],
+                &[],
+                "services:\n  web:\n    image: nginx\n",
+                &[],
+            ),
+            (
+                "missing environment",
+                "services:\n  web:\n    image: nginx\n  db:\n    image: postgres\n",
+                &["FROM_ENV_FILE=1"],
+                &["NEW=1"],
+                "services:\n  web:\n    image: nginx\n    environment:\n      - NEW=1\n  db:\n    image: postgres\n",
+                &["FROM_ENV_FILE"],
+            ),
+        ];
+
+        for (name, source, original, updated, expected, kept) in cases {
+            let (contents, missing) =
+                set_environment(source, "web", &strings(original), &strings(updated))
+                    .unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+            assert_eq!(contents, *expected, "{}", name);
+            assert_eq!(missing, strings(kept), "{}", name);
+        }
+    }
+
+    #[test]
+    fn set_environment_refuses_inline_and_missing_services() {
+        let inline = "services:\n  web: {image: nginx}\n";
+        let err = set_environment(inline, "web", &[], &strings(&["A=1"])).unwrap_err();
+        assert!(err.to_string().contains("inline"), "{}", err);
+
+        let err = set_environment(inline, "db", &[], &strings(&["A=1"])).unwrap_err();
+        assert!(err.to_string().contains("not found"), "{}", err);
+    }
+
+    #[test]
+    fn mapping_key_cases() {
+        let cases: &[(&str, Option<(&str, &str)>)] = &[
+            ("image: nginx", Some(("image", "nginx"))),
+            ("environment:", Some(("environment", ""))),
+            ("environment: # note", Some(("environment", ""))),
+            ("url: http://host:80", Some(("url", "http://host:80"))),
+            ("\"a: b\": 1", Some(("a: b", "1"))),
+            ("'key':", Some(("key", ""))),
+            ("- A=1", None),
+            ("# image: nginx", None),
+            ("no colon here", None),
+            ("http://host", None),
+        ];
+        for (line, expected) in cases {
+            let got = mapping_key(line);
+            let got = got.as_ref().map(|(k, rest)| (k.as_str(), *rest));
+            assert_eq!(got, *expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn block_end_cases() {
+        // (lines, key line, end)
+        let cases: &[(&[&str], usize, usize)] = &[
+            (&["a:", "  b: 1", "  c: 2", "d: 3"], 0, 3),
+            (&["a:", "- 1", "- 2", "d: 3"], 0, 3),
+            (&["a: 1", "b: 2"], 0, 1),
+            // Trailing comments and blank lines belong to what follows.
+            (&["a:", "  b: 1", "", "# about d", "d: 3"], 0, 2),
+            (&["a:", "  b: 1", "", "  c: 2"], 0, 4),
+        ];
+        for (lines, key_line, end) in cases {
+            assert_eq!(block_end(lines, *key_line), *end, "{:?}", lines);
+        }
+    }
+
+    #[test]
+    fn plan_environment_edits_the_file_listing_each_key() {
+        let dir = Dir::new(
+            "split",
+            &[
+                (
+                    "compose.yaml",
+                    "services:\n  web:\n    image: nginx\n    environment:\n      - A=1\n      - B=2\n",
+                ),
+                (
+                    "compose.override.yaml",
+                    "services:\n  web:\n    environment:\n      B: override\n",
+                ),
+            ],
+        );
+        let files = vec![dir.path("compose.yaml"), dir.path("compose.override.yaml")];
+        let original = strings(&["A=1", "B=override"]);
+
+        // A is only in the base file, even though the override has an
+        // environment of its own.
+        let edits =
+            plan_environment(&files, "web", &original, &strings(&["A=9", "B=override"])).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].path, PathBuf::from(&files[0]));
+        assert!(edits[0].contents.contains("- A=9\n"));
+
+        // B is overridden, new keys go where the environment is set last.
+        let edits =
+            plan_environment(&files, "web", &original, &strings(&["A=1", "B=x", "C=3"])).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].path, PathBuf::from(&files[1]));
+        assert_eq!(
+            edits[0].contents,
+            "services:\n  web:\n    environment:\n      B: x\n      C: 3\n"
+        );
+
+        // Removing A and changing B touches both.
+        let edits = plan_environment(&files, "web", &original, &strings(&["B=y"])).unwrap();
+        let paths: Vec<&PathBuf> = edits.iter().map(|e| &e.path).collect();
+        assert_eq!(
+            paths,
+            vec![&PathBuf::from(&files[0]), &PathBuf::from(&files[1])]
+        );
+        assert!(!edits[0].contents.contains("A=1"));
+    }
+
+    #[test]
+    fn plan_service_removes_list_entries_where_they_are() {
+        let dir = Dir::new(
+            "ports",
+            &[
+                (
+                    "compose.yaml",
+                    "services:\n  web:\n    image: nginx\n    ports:\n      - \"80:80\"\n",
+                ),
+                (
+                    "compose.override.yaml",
+                    "services:\n  web:\n    ports:\n      - \"443:443\"\n",
+                ),
+            ],
+        );
+        let files = vec![dir.path("compose.yaml"), dir.path("compose.override.yaml")];
+        let change = Change {
+            key: "ports",
+            list: true,
+            original: strings(&["80:80", "443:443"]),
+            updated: strings(&["443:443", "8080:8080"]),
+        };
+        let edits = plan_service(&files, "web", &[change]).unwrap();
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0].contents, "services:\n  web:\n    image: nginx\n");
+        assert_eq!(
+            edits[1].contents,
+            "services:\n  web:\n    ports:\n      - \"443:443\"\n      - \"8080:8080\"\n"
+        );
+    }
+
+    #[test]
+    fn write_with_backup_keeps_the_first_backup() {
+        let dir = Dir::new("backup", &[("compose.yaml", "first\n")]);
+        let path = PathBuf::from(dir.path("compose.yaml"));
+
+        let backup = write_with_backup(&path, "second\n").unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "first\n");
+        write_with_backup(&path, "third\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "first\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+
+        let new = PathBuf::from(dir.path("new.yaml"));
+        assert!(write_with_backup(&new, "x\n").unwrap().is_none());
+    }
+}
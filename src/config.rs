@@ -0,0 +1,218 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/*
+ * User configuration
+ *
+ * Read from `$XDG_CONFIG_HOME/easydocker/config.json` (or
+ * `~/.config/easydocker/config.json`, `%APPDATA%\easydocker\config.json`
+ * on Windows). A missing or unreadable file falls back to the defaults.
+ * Environment variables win over the file:
+ *
+ *   EASYDOCKER_RUNNER   path to the easydocker-runner binary
+ *   EASYDOCKER_BACKEND  "runner" or "engine"
+ *   EASYDOCKER_RUNTIME  "docker", "podman" or "auto"
+ * */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub runner: Option<String>,
+    pub backend: Option<String>,
+    pub runtime: Option<String>,
+    pub timeouts: Timeouts,
+    pub workspace: Workspace,
+    /// Active compose profiles by project directory. Projects not listed
+    /// use `COMPOSE_PROFILES` from their .env or the environment.
+    pub profiles: BTreeMap<String, Vec<String>>,
+    pub secrets: Secrets,
+}
+
+/// Where compose projects are searched for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    /// Directories to search; `~` stands for the home directory. Empty
+    /// searches the home directory.
+    pub roots: Vec<String>,
+    /// Directories to descend below a root. 0 disables the limit.
+    pub max_depth: usize,
+    /// Globs of directory names (or full paths) never entered, in the
+    /// syntax of Go's filepath.Match: `*`, `?`, `[a-z]`, `[^a-z]`.
+    pub ignore: Vec<String>,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            max_depth: 6,
+            ignore: ["node_modules", ".git", "target", "vendor"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl Workspace {
+    /// The roots with `~` expanded.
+    pub fn expanded_roots(&self) -> Vec<String> {
+        self.roots.iter().map(|root| expand_root(root)).collect()
+    }
+}
+
+/// `root` with a leading `~` replaced by the home directory.
+pub fn expand_root(root: &str) -> String {
+    let home = std::env::var("HOME").unwrap_or_default();
+    match root.strip_prefix('~') {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("{}{}", home, rest)
+        }
+        _ => root.to_string(),
+    }
+}
+
+/// Patterns of environment keys whose values are masked on screen; a key
+/// containing one of them, ignoring case, is secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secrets {
+    pub patterns: Vec<String>,
+}
+
+impl Default for Secrets {
+    fn default() -> Self {
+        Self {
+            patterns: ["PASSWORD", "SECRET", "TOKEN", "KEY"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// Time limits for lifecycle operations, in seconds. 0 disables the limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timeouts {
+    pub create: u64,
+    pub start: u64,
+    pub stop: u64,
+    pub remove: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            // Covers pulling or building the image as well.
+            create: 900,
+            start: 60,
+            stop: 60,
+            remove: 120,
+        }
+    }
+}
+
+impl Timeouts {
+    pub fn limit(seconds: u64) -> Option<Duration> {
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let mut config = config_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str::<Config>(&raw).ok())
+            .unwrap_or_default();
+
+        if let Ok(runner) = std::env::var("EASYDOCKER_RUNNER")
+            && !runner.is_empty()
+        {
+            config.runner = Some(runner);
+        }
+        if let Ok(backend) = std::env::var("EASYDOCKER_BACKEND")
+            && !backend.is_empty()
+        {
+            config.backend = Some(backend);
+        }
+        if let Ok(runtime) = std::env::var("EASYDOCKER_RUNTIME")
+            && !runtime.is_empty()
+        {
+            config.runtime = Some(runtime);
+        }
+
+        config
+    }
+
+    /// Writes `workspace` to the config file, leaving the rest of the file
+    /// as it is (values coming from environment variables stay out of it).
+    pub fn save_workspace(&self) -> Result<()> {
+        save_key("workspace", serde_json::to_value(&self.workspace)?)
+    }
+
+    /// Writes `profiles` to the config file the same way.
+    pub fn save_profiles(&self) -> Result<()> {
+        save_key("profiles", serde_json::to_value(&self.profiles)?)
+    }
+}
+
+/// Sets `key` in the config file to `value`.
+fn save_key(key: &str, value: serde_json::Value) -> Result<()> {
+    let path = config_path().context("No config directory (HOME is not set)")?;
+    let mut file = match std::fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<serde_json::Value>(&raw)
+            .ok()
+            .filter(|value| value.is_object())
+            .with_context(|| format!("{} is not a JSON object; not touching it", path.display()))?,
+        Err(_) => serde_json::json!({}),
+    };
+    file[key] = value;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&file)?;
+    std::fs::write(&path, json).with_context(|| format!("Cannot write {}", path.display()))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir).join("easydocker"));
+    }
+    if cfg!(windows)
+        && let Ok(dir) = std::env::var("APPDATA")
+    {
+        return Some(PathBuf::from(dir).join("easydocker"));
+    }
+    std::env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join(".config").join("easydocker"))
+}
+
+/// Where easydocker keeps data it can rebuild, such as the workspace index.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("XDG_CACHE_HOME")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir).join("easydocker"));
+    }
+    if cfg!(windows)
+        && let Ok(dir) = std::env::var("LOCALAPPDATA")
+    {
+        return Some(PathBuf::from(dir).join("easydocker"));
+    }
+    std::env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join(".cache").join("easydocker"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.json"))
+}
@@ -0,0 +1,350 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::compose::{self, FileEdit};
+
+pub const DOTENV_FILE: &str = ".env";
+
+/// Path of the .env file of the project in `dir`.
+pub fn path(dir: &str) -> PathBuf {
+    Path::new(dir).join(DOTENV_FILE)
+}
+
+/// Assignments of a .env file as `KEY=value`, the value exactly as written
+/// (quotes included). Empty when the file does not exist.
+pub fn entries(path: &Path) -> Vec<String> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    source
+        .lines()
+        .filter_map(assignment)
+        .map(|(_, key, value)| format!("{}={}", key, value))
+        .collect()
+}
+
+/*
+ * Interpolation variables
+ *
+ * What `docker compose` substitutes into the project in `dir`: the .env
+ * file, where unquoted and double-quoted values may use the variables
+ * before them, overridden by the process environment.
+ * */
+pub fn variables(dir: &str) -> HashMap<String, String> {
+    let process: HashMap<String, String> = std::env::vars().collect();
+    let mut vars = HashMap::new();
+
+    if let Ok(source) = std::fs::read_to_string(path(dir)) {
+        for (_, key, raw) in source.lines().filter_map(assignment) {
+            let lookup = |name: &str| process.get(name).or_else(|| vars.get(name)).cloned();
+            let value = parse_value(raw, &lookup);
+            vars.insert(key.to_string(), value);
+        }
+    }
+    vars.extend(process);
+    vars
+}
+
+/*
+ * Compose interpolation
+ *
+ * $VAR and ${VAR} with the modifiers compose supports: ${VAR:-default},
+ * ${VAR-default}, ${VAR:+alt}, ${VAR+alt}, ${VAR:?error}, ${VAR?error}.
+ * `$$` is a literal `$`. A required variable that is missing shows up as
+ * `<VAR: error>` rather than failing, since this is only a preview.
+ * */
+pub fn interpolate(s: &str, vars: &HashMap<String, String>) -> String {
+    expand(s, &|name: &str| vars.get(name).cloned())
+}
+
+fn expand(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let Some(end) = closing_brace(body) else {
+                out.push_str(&rest[pos..]);
+                return out;
+            };
+            out.push_str(&substitute(&body[..end], lookup));
+            rest = &body[end + 1..];
+        } else {
+            let len = name_len(after);
+            if len == 0 {
+                out.push('$');
+            } else {
+                out.push_str(&lookup(&after[..len]).unwrap_or_default());
+            }
+            rest = &after[len..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn substitute(expr: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let (name, op) = expr.split_at(name_len(expr));
+    let value = lookup(name);
+    let set = value.is_some();
+    let non_empty = value.as_deref().is_some_and(|v| !v.is_empty());
+
+    let (word, use_word, required) = if let Some(word) = op.strip_prefix(":-") {
+        (word, !non_empty, false)
+    } else if let Some(word) = op.strip_prefix('-') {
+        (word, !set, false)
+    } else if let Some(word) = op.strip_prefix(":+") {
+        return if non_empty {
+            expand(word, lookup)
+        } else {
+            String::new()
+        };
+    } else if let Some(word) = op.strip_prefix('+') {
+        return if set {
+            expand(word, lookup)
+        } else {
+            String::new()
+        };
+    } else if let Some(word) = op.strip_prefix(":?") {
+        (word, !non_empty, true)
+    } else if let Some(word) = op.strip_prefix('?') {
+        (word, !set, true)
+    } else if op.is_empty() {
+        return value.unwrap_or_default();
+    } else {
+        return format!("${{{}}}", expr);
+    };
+
+    match (use_word, required) {
+        (false, _) => value.unwrap_or_default(),
+        (true, false) => expand(word, lookup),
+        (true, true) => format!("<{}: {}>", name, expand(word, lookup)),
+    }
+}
+
+fn closing_brace(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn name_len(s: &str) -> usize {
+    s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len())
+}
+
+/// Splits a .env line into (`export ` prefix, key, raw value), or None for
+/// blank lines and comments.
+fn assignment(line: &str) -> Option<(&str, &str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let (prefix, rest) = match trimmed.strip_prefix("export ") {
+        Some(rest) => ("export ", rest.trim_start()),
+        None => ("", trimmed),
+    };
+    let (key, value) = rest.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((prefix, key, value.trim()))
+}
+
+/// The value a raw .env value stands for: single quotes are literal,
+/// double quotes take escapes, and both unquoted and double-quoted values
+/// are interpolated. An unquoted value ends at ` #`.
+fn parse_value(raw: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    if let Some(inner) = raw.strip_prefix('\'')
+        && let Some(end) = inner.rfind('\'')
+    {
+        return inner[..end].to_string();
+    }
+    if let Some(inner) = raw.strip_prefix('"')
+        && let Some(end) = inner.rfind('"')
+    {
+        return expand(&unescape(&inner[..end]), lookup);
+    }
+    let value = raw.split(" #").next().unwrap_or(raw).trim();
+    expand(value, lookup)
+}
+
+/// Resolves the escapes of a double-quoted value (`\n`, `\"`, `\\`) in one
+/// pass, so an escaped backslash is never read as the start of another
+/// escape. Any other backslash stays.
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(c @ ('"' | '\\')) => out.push(c),
+            Some(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// The change to `path` that turns the `original` entries (as returned by
+/// `entries`) into `updated`. Comments, blank lines and untouched
+/// assignments stay as they are; new entries go at the end.
+pub fn plan(path: &Path, original: &[String], updated: &[String]) -> Result<FileEdit> {
+    let source = if path.exists() {
+        std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let split = |entry: &String| -> (String, String) {
+        match entry.split_once('=') {
+            Some((key, value)) => (key.trim().to_string(), value.to_string()),
+            None => (entry.trim().to_string(), String::new()),
+        }
+    };
+    let original: Vec<(String, String)> = original.iter().map(split).collect();
+    let updated: Vec<(String, String)> = updated.iter().map(split).collect();
+    let changed: Vec<&(String, String)> =
+        updated.iter().filter(|e| !original.contains(e)).collect();
+
+    let mut out: Vec<String> = Vec::new();
+    let mut written: Vec<&str> = Vec::new();
+    for line in source.lines() {
+        let Some((prefix, key, _)) = assignment(line) else {
+            out.push(line.to_string());
+            continue;
+        };
+        if let Some((k, value)) = changed.iter().find(|(k, _)| k == key).copied() {
+            out.push(format!("{}{}={}", prefix, k, value));
+            written.push(k);
+        } else if updated.iter().any(|(k, _)| k == key) {
+            out.push(line.to_string());
+        }
+    }
+    for (key, value) in changed
+        .iter()
+        .filter(|(k, _)| !written.contains(&k.as_str()))
+    {
+        out.push(format!("{}={}", key, value));
+    }
+
+    let mut contents = out.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    Ok(compose::file_edit(
+        path.to_path_buf(),
+        &source,
+        contents,
+        Vec::new(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<String, String> {
+        [("SET", "x"), ("EMPTY", ""), ("B", "b")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn interpolate_cases() {
+        let vars = vars();
+        // (input, expected)
+        let cases = [
+            ("$SET/${B}", "x/b"),
+            ("${UNSET}", ""),
+            ("${SET:-d}", "x"),
+            ("${EMPTY:-d}", "d"),
+            ("${UNSET:-d}", "d"),
+            ("${EMPTY-d}", ""),
+            ("${UNSET-d}", "d"),
+            ("${SET:+a}", "a"),
+            ("${EMPTY:+a}", ""),
+            ("${EMPTY+a}", "a"),
+            ("${UNSET+a}", ""),
+            ("${SET?e}", "x"),
+            ("${EMPTY?e}", ""),
+            ("${UNSET?e}", "<UNSET: e>"),
+            ("${EMPTY:?e}", "<EMPTY: e>"),
+            ("$$SET costs $$5", "$SET costs $5"),
+            ("a $ b", "a $ b"),
+            ("${UNSET:-${B}}", "b"),
+            ("${UNSET:-${UNSET2:-deep}}", "deep"),
+            ("${SET:+${B}-$SET}", "b-x"),
+            ("pre ${SET", "pre ${SET"),
+            ("${SET%x}", "${SET%x}"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(interpolate(input, &vars), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_value_cases() {
+        let vars = vars();
+        let lookup = |name: &str| vars.get(name).cloned();
+        // (raw value, expected)
+        let cases = [
+            ("plain", "plain"),
+            ("plain $B # note", "plain b"),
+            ("a#b", "a#b"),
+            ("'$B #not a comment' # note", "$B #not a comment"),
+            (r#""$B #not a comment" # note"#, "b #not a comment"),
+            (r#""line\nbreak""#, "line\nbreak"),
+            (r#""literal\\n""#, "literal\\n"),
+            (r#""say \"hi\"""#, "say \"hi\""),
+            (r#""C:\\dir\temp""#, "C:\\dir\\temp"),
+            (r#""unterminated"#, "\"unterminated"),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_value(raw, &lookup), expected, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn plan_keeps_comments_and_order() {
+        let path =
+            std::env::temp_dir().join(format!("easydocker-dotenv-{}.env", std::process::id()));
+        std::fs::write(&path, "# database\nexport A=1\n\nB=2 # keep\nC=3\n# tail\n").unwrap();
+
+        let original = entries(&path);
+        assert_eq!(original, ["A=1", "B=2 # keep", "C=3"]);
+        let updated = ["A=10", "B=2 # keep", "D=4"].map(String::from);
+        let edit = plan(&path, &original, &updated).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            edit.contents,
+            "# database\nexport A=10\n\nB=2 # keep\n# tail\nD=4\n"
+        );
+    }
+}
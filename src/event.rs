@@ -0,0 +1,86 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use crossterm::event::{Event as TermEvent, EventStream};
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::app::{CPUUsage, DockerCompose, Fetched, Listing, RunningContainer};
+use crate::protocol::Message;
+
+/// How often the analytics graph is nudged while no samples arrive.
+const TICK: Duration = Duration::from_millis(500);
+
+/// Things background tasks report back to the UI.
+pub enum AppEvent {
+    Job(u64, Message),
+    /// A fetch result, tagged with the generation it was started in.
+    Fetched(u64, Fetched),
+    /// Every container, listed for an action waiting on it, tagged with the
+    /// generation it was started in.
+    Listed(u64, Listing, anyhow::Result<Vec<RunningContainer>>),
+    /// A stats sample, or the error that ended the stream, tagged with the
+    /// stream it came from so samples from a stream that was already
+    /// replaced can be dropped.
+    Analytics(u64, anyhow::Result<CPUUsage>),
+    /// Project directories whose files changed on disk.
+    ProjectsChanged(Vec<String>),
+    /// A project directory read again: its services, none when it has no
+    /// compose file any more. Tagged with the generation it was started in.
+    Reloaded(u64, String, anyhow::Result<Vec<DockerCompose>>),
+    /// Something the workspace watcher could not do.
+    WatchError(String),
+}
+
+pub enum Event {
+    Terminal(TermEvent),
+    App(AppEvent),
+    Tick,
+}
+
+pub type EventSender = UnboundedSender<AppEvent>;
+
+pub fn channel() -> (EventSender, UnboundedReceiver<AppEvent>) {
+    mpsc::unbounded_channel()
+}
+
+/*
+ * The main loop's single source of wake-ups
+ *
+ * Terminal input, job output, fetch results, analytics samples and
+ * workspace changes all arrive here, so main.rs only redraws after
+ * something actually happened instead of on a fixed poll interval.
+ * */
+pub struct EventLoop {
+    terminal: EventStream,
+    app: UnboundedReceiver<AppEvent>,
+    tick: Interval,
+}
+
+impl EventLoop {
+    pub fn new(app: UnboundedReceiver<AppEvent>) -> Self {
+        let mut tick = tokio::time::interval(TICK);
+        tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        Self {
+            terminal: EventStream::new(),
+            app,
+            tick,
+        }
+    }
+
+    /// Waits for the next event. `Tick` is only produced while `ticking`,
+    /// so an idle screen sleeps until input arrives.
+    pub async fn next(&mut self, ticking: bool) -> Option<Event> {
+        tokio::select! {
+            event = self.terminal.next() => match event {
+                Some(Ok(event)) => Some(Event::Terminal(event)),
+                // Input is gone; nothing left to drive the UI.
+                Some(Err(_)) | None => None,
+            },
+            Some(event) = self.app.recv() => Some(Event::App(event)),
+            _ = self.tick.tick(), if ticking => Some(Event::Tick),
+        }
+    }
+}
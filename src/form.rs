@@ -0,0 +1,625 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use crate::compose::{Change, RawService};
+use crate::dotenv;
+use crate::lint;
+
+/// A service field the form edits.
+pub struct Field {
+    /// Key in the compose file.
+    pub key: &'static str,
+    pub label: &'static str,
+    /// List fields take any number of entries, scalars at most one.
+    pub list: bool,
+    /// Checks one entry, after interpolation.
+    pub validate: fn(&str) -> Result<(), String>,
+}
+
+/*
+ * Form fields
+ *
+ * Everything the form covers is listed here; a new field needs an entry
+ * and a way to read it in `RawService::field`. Values are edited as
+ * written in the files, so `${VAR}` and relative paths stay as they are,
+ * and are checked as compose will see them.
+ * */
+pub const FIELDS: &[Field] = &[
+    Field {
+        key: "image",
+        label: "Image",
+        list: false,
+        validate: validate_image,
+    },
+    Field {
+        key: "container_name",
+        label: "Container",
+        list: false,
+        validate: validate_container_name,
+    },
+    Field {
+        key: "hostname",
+        label: "Hostname",
+        list: false,
+        validate: validate_hostname,
+    },
+    Field {
+        key: "restart",
+        label: "Restart",
+        list: false,
+        validate: validate_restart,
+    },
+    Field {
+        key: "ports",
+        label: "Ports",
+        list: true,
+        validate: validate_port,
+    },
+    Field {
+        key: "volumes",
+        label: "Volumes",
+        list: true,
+        validate: validate_volume,
+    },
+    Field {
+        key: "networks",
+        label: "Networks",
+        list: true,
+        validate: validate_network,
+    },
+    Field {
+        key: "environment",
+        label: "Env",
+        list: true,
+        validate: validate_env,
+    },
+];
+
+/// A problem with one entry of the form.
+pub struct FieldError {
+    pub field: usize,
+    pub item: usize,
+    pub message: String,
+}
+
+/// The form editor's state for one service.
+pub struct Form {
+    /// Index into the service list.
+    pub service: usize,
+    /// Entries per field of `FIELDS`, in the same order.
+    pub values: Vec<Vec<String>>,
+    original: Vec<Vec<String>>,
+    pub selected: usize,
+    pub editing: bool,
+    pub buffer: String,
+    pub errors: Vec<FieldError>,
+    // Entries, as (field, entry), whose secret value is shown.
+    revealed: HashSet<(usize, usize)>,
+    variables: Arc<HashMap<String, String>>,
+}
+
+impl Form {
+    pub fn new(service: usize, raw: &RawService, variables: Arc<HashMap<String, String>>) -> Self {
+        let values: Vec<Vec<String>> = FIELDS.iter().map(|f| raw.field(f.key)).collect();
+        Self {
+            service,
+            original: values.clone(),
+            values,
+            selected: 0,
+            editing: false,
+            buffer: String::new(),
+            errors: Vec::new(),
+            revealed: HashSet::new(),
+            variables,
+        }
+    }
+
+    /// One row per entry, and one for each field without any, as
+    /// (field, entry).
+    pub fn rows(&self) -> Vec<(usize, Option<usize>)> {
+        let mut rows = Vec::new();
+        for (field, values) in self.values.iter().enumerate() {
+            if values.is_empty() {
+                rows.push((field, None));
+            } else {
+                rows.extend((0..values.len()).map(|i| (field, Some(i))));
+            }
+        }
+        rows
+    }
+
+    fn current(&self) -> (usize, Option<usize>) {
+        let rows = self.rows();
+        rows[self.selected.min(rows.len() - 1)]
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.rows().len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn start_edit(&mut self) {
+        let (field, item) = self.current();
+        self.buffer = item
+            .map(|i| self.values[field][i].clone())
+            .unwrap_or_default();
+        self.editing = true;
+    }
+
+    /// Stores the edited entry; an emptied one is removed.
+    pub fn confirm_edit(&mut self) {
+        let (field, item) = self.current();
+        let value = self.buffer.trim().to_string();
+        match (item, value.is_empty()) {
+            (Some(i), true) => self.remove(field, i),
+            (Some(i), false) => self.values[field][i] = value,
+            (None, false) => self.values[field].push(value),
+            (None, true) => {}
+        }
+        self.editing = false;
+        self.buffer.clear();
+        self.clamp();
+        self.validate();
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.editing = false;
+        self.buffer.clear();
+        // A blank entry added with `a` goes again.
+        let (field, item) = self.current();
+        if let Some(i) = item
+            && self.values[field][i].is_empty()
+        {
+            self.remove(field, i);
+            self.clamp();
+        }
+    }
+
+    /// Adds an entry after the selected one of a list field and starts
+    /// editing it; on a scalar this just edits it.
+    pub fn add_item(&mut self) {
+        let (field, item) = self.current();
+        if !FIELDS[field].list {
+            self.start_edit();
+            return;
+        }
+        let at = item.map_or(0, |i| i + 1);
+        self.values[field].insert(at, String::new());
+        self.revealed = self
+            .revealed
+            .iter()
+            .map(|&(f, i)| (f, if f == field && i >= at { i + 1 } else { i }))
+            .collect();
+        self.selected = self
+            .rows()
+            .iter()
+            .position(|r| *r == (field, Some(at)))
+            .unwrap_or(self.selected);
+        self.buffer.clear();
+        self.editing = true;
+    }
+
+    pub fn delete_item(&mut self) {
+        let (field, item) = self.current();
+        if let Some(i) = item {
+            self.remove(field, i);
+            self.clamp();
+            self.validate();
+        }
+    }
+
+    /// Removes an entry, keeping the reveal state with the ones after it.
+    fn remove(&mut self, field: usize, item: usize) {
+        self.values[field].remove(item);
+        self.revealed = self
+            .revealed
+            .iter()
+            .filter(|&&r| r != (field, item))
+            .map(|&(f, i)| (f, if f == field && i > item { i - 1 } else { i }))
+            .collect();
+    }
+
+    /// Shows or masks the secret value of the selected entry.
+    pub fn toggle_reveal(&mut self) {
+        if let (field, Some(item)) = self.current()
+            && !self.revealed.remove(&(field, item))
+        {
+            self.revealed.insert((field, item));
+        }
+    }
+
+    pub fn is_revealed(&self, field: usize, item: usize) -> bool {
+        self.revealed.contains(&(field, item))
+    }
+
+    fn clamp(&mut self) {
+        self.selected = self.selected.min(self.rows().len() - 1);
+    }
+
+    /// Checks every entry, keeping the problems in `errors`. True when
+    /// there are none.
+    pub fn validate(&mut self) -> bool {
+        self.errors.clear();
+        for (field, values) in self.values.iter().enumerate() {
+            for (item, value) in values.iter().enumerate() {
+                let resolved = dotenv::interpolate(value, &self.variables);
+                if let Err(message) = (FIELDS[field].validate)(&resolved) {
+                    self.errors.push(FieldError {
+                        field,
+                        item,
+                        message,
+                    });
+                }
+            }
+        }
+        self.errors.is_empty()
+    }
+
+    pub fn error_at(&self, field: usize, item: usize) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|e| e.field == field && e.item == item)
+            .map(|e| e.message.as_str())
+    }
+
+    /// The fields that differ from what the form was opened with.
+    pub fn changes(&self) -> Vec<Change> {
+        FIELDS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.values[*i] != self.original[*i])
+            .map(|(i, field)| Change {
+                key: field.key,
+                list: field.list,
+                original: self.original[i].clone(),
+                updated: self.values[i].clone(),
+            })
+            .collect()
+    }
+}
+
+fn validate_image(value: &str) -> Result<(), String> {
+    if value.contains(char::is_whitespace) {
+        return Err("an image reference has no spaces".to_string());
+    }
+    Ok(())
+}
+
+fn validate_container_name(value: &str) -> Result<(), String> {
+    let mut chars = value.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+    if !valid {
+        return Err("use letters, digits, _ . - and start with a letter or digit".to_string());
+    }
+    Ok(())
+}
+
+fn validate_hostname(value: &str) -> Result<(), String> {
+    let valid = value.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !valid {
+        return Err("not a valid hostname".to_string());
+    }
+    Ok(())
+}
+
+fn validate_restart(value: &str) -> Result<(), String> {
+    if !lint::valid_restart(value) {
+        return Err("use no, always, unless-stopped or on-failure[:N]".to_string());
+    }
+    Ok(())
+}
+
+/*
+ * Short port syntax
+ *
+ * [[ip:]host:]container[/protocol], where host and container can be
+ * ranges (8000-8010) and an IPv6 address goes in brackets. A host range
+ * must be as long as the container range unless the latter is one port.
+ * */
+fn validate_port(value: &str) -> Result<(), String> {
+    let (spec, protocol) = match value.rsplit_once('/') {
+        Some((spec, protocol)) => (spec, Some(protocol)),
+        None => (value, None),
+    };
+    if let Some(protocol) = protocol
+        && !matches!(protocol, "tcp" | "udp" | "sctp")
+    {
+        return Err(format!("unknown protocol {}", protocol));
+    }
+
+    let (ip, ports) = match spec.strip_prefix('[') {
+        Some(rest) => {
+            let (ip, ports) = rest
+                .split_once("]:")
+                .ok_or_else(|| "expected [ip]:host:container".to_string())?;
+            (Some(ip), ports)
+        }
+        None => match spec.matches(':').count() {
+            0 | 1 => (None, spec),
+            2 => {
+                let (ip, ports) = spec.split_once(':').unwrap_or_default();
+                (Some(ip), ports)
+            }
+            _ => return Err("expected [[ip:]host:]container[/protocol]".to_string()),
+        },
+    };
+    if let Some(ip) = ip
+        && ip.parse::<IpAddr>().is_err()
+    {
+        return Err(format!("{} is not an IP address", ip));
+    }
+
+    let (host, container) = match ports.split_once(':') {
+        Some((host, container)) => (Some(host), container),
+        None => (None, ports),
+    };
+    let container = port_range(container)?;
+    if let Some(host) = host.filter(|h| !h.is_empty()) {
+        let host = port_range(host)?;
+        let len = |(a, b): (u16, u16)| b - a;
+        if len(container) != 0 && len(host) != len(container) {
+            return Err("host and container ranges differ in length".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn port_range(value: &str) -> Result<(u16, u16), String> {
+    let port = |p: &str| match p.parse::<u16>() {
+        Ok(p) if p > 0 => Ok(p),
+        _ => Err(format!("{} is not a port", p)),
+    };
+    match value.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (port(start)?, port(end)?);
+            if start > end {
+                return Err(format!("range {} goes backwards", value));
+            }
+            Ok((start, end))
+        }
+        None => port(value).map(|p| (p, p)),
+    }
+}
+
+/// `target`, `source:target` or `source:target:mode`, with an absolute
+/// target and mode options such as `ro`, `rw`, `z` or `cached`.
+fn validate_volume(value: &str) -> Result<(), String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let (source, target, mode) = match parts.as_slice() {
+        [target] => (None, *target, None),
+        [source, target] => (Some(*source), *target, None),
+        [source, target, mode] => (Some(*source), *target, Some(*mode)),
+        _ => return Err("expected [source:]target[:mode]".to_string()),
+    };
+    if source.is_some_and(str::is_empty) {
+        return Err("source is empty".to_string());
+    }
+    if !target.starts_with('/') {
+        return Err(format!("target {} is not an absolute path", target));
+    }
+    if let Some(mode) = mode {
+        const MODES: &[&str] = &[
+            "ro",
+            "rw",
+            "z",
+            "Z",
+            "cached",
+            "delegated",
+            "consistent",
+            "nocopy",
+        ];
+        if let Some(bad) = mode.split(',').find(|m| !MODES.contains(m)) {
+            return Err(format!("unknown mode {}", bad));
+        }
+    }
+    Ok(())
+}
+
+fn validate_network(value: &str) -> Result<(), String> {
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        return Err("not a network name".to_string());
+    }
+    Ok(())
+}
+
+fn validate_env(value: &str) -> Result<(), String> {
+    let key = value.split('=').next().unwrap_or_default();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err("expected KEY=value".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORTS: usize = 4;
+    const ENV: usize = 7;
+
+    fn form(raw: RawService) -> Form {
+        Form::new(0, &raw, Arc::new(HashMap::new()))
+    }
+
+    /// Selects entry `item` of field `field`.
+    fn select(form: &mut Form, field: usize, item: usize) {
+        form.selected = form
+            .rows()
+            .iter()
+            .position(|r| *r == (field, Some(item)))
+            .unwrap();
+    }
+
+    #[test]
+    fn reveals_follow_their_entries() {
+        let mut form = form(RawService {
+            environment: ["A=1", "DB_PASSWORD=x", "API_TOKEN=y"]
+                .map(String::from)
+                .to_vec(),
+            ..Default::default()
+        });
+        for item in [1, 2] {
+            select(&mut form, ENV, item);
+            form.toggle_reveal();
+        }
+
+        select(&mut form, ENV, 0);
+        form.add_item();
+        assert_eq!(form.values[ENV][2], "DB_PASSWORD=x");
+        assert!(!form.is_revealed(ENV, 1));
+        assert!(form.is_revealed(ENV, 2) && form.is_revealed(ENV, 3));
+
+        // The blank entry goes again.
+        form.cancel_edit();
+        assert!(form.is_revealed(ENV, 1) && form.is_revealed(ENV, 2));
+
+        select(&mut form, ENV, 1);
+        form.delete_item();
+        assert_eq!(form.values[ENV], ["A=1", "API_TOKEN=y"]);
+        assert!(form.is_revealed(ENV, 1));
+        assert!(!form.is_revealed(ENV, 2));
+    }
+
+    /// Runs `validate` over (value, expected error) cases.
+    fn check(validate: fn(&str) -> Result<(), String>, cases: &[(&str, Option<&str>)]) {
+        for (value, expected) in cases {
+            assert_eq!(validate(value).err().as_deref(), *expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn validate_port_cases() {
+        check(
+            validate_port,
+            &[
+                ("80", None),
+                ("8080:80", None),
+                ("127.0.0.1:8080:80", None),
+                ("127.0.0.1::80", None),
+                ("[::1]:80:80", None),
+                ("8000-8001:80-81", None),
+                ("8000-8010:80", None),
+                ("53:53/udp", None),
+                ("9000:9000/sctp", None),
+                (
+                    "8000-8002:80-81",
+                    Some("host and container ranges differ in length"),
+                ),
+                ("81-80", Some("range 81-80 goes backwards")),
+                ("0", Some("0 is not a port")),
+                ("8080:70000", Some("70000 is not a port")),
+                ("80/http", Some("unknown protocol http")),
+                ("localhost:8080:80", Some("localhost is not an IP address")),
+                ("[::1:80", Some("expected [ip]:host:container")),
+                ("1:2:3:4", Some("expected [[ip:]host:]container[/protocol]")),
+            ],
+        );
+    }
+
+    #[test]
+    fn validate_volume_cases() {
+        check(
+            validate_volume,
+            &[
+                ("/data", None),
+                ("data:/var/lib/data", None),
+                ("./src:/app:ro", None),
+                ("./src:/app:ro,z", None),
+                ("./src:/app:cached", None),
+                ("./src:app", Some("target app is not an absolute path")),
+                (":/app", Some("source is empty")),
+                ("./src:/app:ro,fast", Some("unknown mode fast")),
+                ("a:/b:ro:extra", Some("expected [source:]target[:mode]")),
+            ],
+        );
+    }
+
+    #[test]
+    fn validate_hostname_and_restart_cases() {
+        check(
+            validate_hostname,
+            &[
+                ("web", None),
+                ("db-1.internal", None),
+                ("-web", Some("not a valid hostname")),
+                ("web-", Some("not a valid hostname")),
+                ("db..internal", Some("not a valid hostname")),
+                ("under_score", Some("not a valid hostname")),
+            ],
+        );
+        let restart = Some("use no, always, unless-stopped or on-failure[:N]");
+        check(
+            validate_restart,
+            &[
+                ("no", None),
+                ("unless-stopped", None),
+                ("on-failure", None),
+                ("on-failure:3", None),
+                ("on-failure:x", restart),
+                ("sometimes", restart),
+            ],
+        );
+    }
+
+    #[test]
+    fn edits_show_up_as_changes() {
+        let mut form = form(RawService {
+            image: "nginx".to_string(),
+            ports: vec!["8080:80".to_string()],
+            ..Default::default()
+        });
+        assert!(form.changes().is_empty());
+
+        select(&mut form, PORTS, 0);
+        form.add_item();
+        form.buffer = "0".to_string();
+        form.confirm_edit();
+        assert_eq!(form.values[PORTS], ["8080:80", "0"]);
+        assert_eq!(
+            form.errors
+                .iter()
+                .map(|e| (e.field, e.item))
+                .collect::<Vec<_>>(),
+            [(PORTS, 1)]
+        );
+
+        form.start_edit();
+        form.buffer = "8443:443".to_string();
+        form.confirm_edit();
+        assert!(form.errors.is_empty());
+
+        select(&mut form, PORTS, 0);
+        form.delete_item();
+        let changes = form.changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "ports");
+        assert!(changes[0].list);
+        assert_eq!(changes[0].original, ["8080:80"]);
+        assert_eq!(changes[0].updated, ["8443:443"]);
+
+        // Emptying the image removes it.
+        form.selected = 0;
+        form.start_edit();
+        form.buffer.clear();
+        form.confirm_edit();
+        let changes = form.changes();
+        assert_eq!(changes[0].key, "image");
+        assert!(changes[0].updated.is_empty());
+    }
+}
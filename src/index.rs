@@ -0,0 +1,393 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::app::{COMPOSE_FILE_NAMES, DockerCompose};
+use crate::config;
+use crate::dotenv::DOTENV_FILE;
+
+/// Bumped whenever what is stored changes shape; an index written by
+/// another version is ignored.
+const VERSION: u32 = 4;
+
+const INDEX_FILE: &str = "index.json";
+
+/*
+ * Workspace index
+ *
+ * The services of every project found by the last search, as the runner
+ * reported them, so the next start can show them straight away. Each
+ * project keeps the modification time of each file that can change what
+ * compose reads there (see `project_file_names`) and of the files the
+ * runner says it pulled in from elsewhere (env_file, extends, include),
+ * including ones that did not exist, plus a hash of the environment
+ * variables its files use. When any of them differs the project is read
+ * again.
+ * */
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Index {
+    version: u32,
+    pub projects: Vec<Entry>,
+}
+
+/// One project as it was last read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub dir: String,
+    stamps: Vec<Stamp>,
+    /// Variables `${VAR}` in the project's files refers to.
+    variables: Vec<String>,
+    /// `env_hash` of `variables` when the project was read.
+    env: u64,
+    pub services: Vec<DockerCompose>,
+}
+
+/// A file of a project directory (by name) or one it refers to (by path)
+/// and its modification time in nanoseconds since the epoch; None when it
+/// did not exist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stamp {
+    name: String,
+    modified: Option<u64>,
+}
+
+impl Index {
+    /// The index on disk; empty when there is none or it cannot be used.
+    pub fn load() -> Self {
+        match path() {
+            Some(path) => Self::read(&path),
+            None => Self::default(),
+        }
+    }
+
+    /// Replaces the index on disk.
+    pub fn save(projects: Vec<Entry>) -> Result<()> {
+        let path = path().context("No cache directory (HOME is not set)")?;
+        let index = Index {
+            version: VERSION,
+            projects,
+        };
+        index.write(&path)
+    }
+
+    fn read(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Index>(&raw).ok())
+            .filter(|index| index.version == VERSION)
+            .unwrap_or_default()
+    }
+
+    /// Written to a temporary file first, so a crash never leaves half an
+    /// index behind. The services carry their interpolated environment,
+    /// secrets included, so only the user may read the file and the
+    /// directory it is in.
+    fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            create_private_dir(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        write_private(&tmp, serde_json::to_string(self)?.as_bytes())
+            .with_context(|| format!("Cannot write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Cannot write {}", path.display()))
+    }
+
+    /// What is stored for `dir`, if none of its files and variables
+    /// changed since.
+    pub fn fresh(&self, dir: &str) -> Option<&Entry> {
+        let entry = self.projects.iter().find(|entry| entry.dir == dir)?;
+        let mut stamps = stamps(dir);
+        stamps.extend(reference_stamps(&entry.services));
+        (entry.stamps == stamps && entry.env == env_hash(&entry.variables)).then_some(entry)
+    }
+}
+
+impl Entry {
+    /// `dir` as just read: `stamps` of its own files, taken before reading
+    /// so a change while it was read is noticed next time, and `services`.
+    pub fn new(dir: String, mut stamps: Vec<Stamp>, services: Vec<DockerCompose>) -> Self {
+        stamps.extend(reference_stamps(&services));
+        let variables = mentioned_variables(&services);
+        Self {
+            dir,
+            stamps,
+            env: env_hash(&variables),
+            variables,
+            services,
+        }
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    config::cache_dir().map(|dir| dir.join(INDEX_FILE))
+}
+
+/// Compose files, their overrides (`compose.override.yaml`, ...) and the
+/// .env file: everything in a project directory that changes what compose
+/// reads from it.
+pub fn project_file_names() -> Vec<String> {
+    let mut names = Vec::new();
+    for file in COMPOSE_FILE_NAMES {
+        names.push(file.to_string());
+        if let Some((stem, ext)) = file.rsplit_once('.') {
+            names.push(format!("{}.override.{}", stem, ext));
+        }
+    }
+    names.push(DOTENV_FILE.to_string());
+    names
+}
+
+/// The current stamps of the project files in `dir`.
+pub fn stamps(dir: &str) -> Vec<Stamp> {
+    project_file_names()
+        .into_iter()
+        .map(|name| {
+            let modified = modified(&Path::new(dir).join(&name));
+            Stamp { name, modified }
+        })
+        .collect()
+}
+
+/// The current stamps of the files `services` refer to.
+fn reference_stamps(services: &[DockerCompose]) -> Vec<Stamp> {
+    let references: BTreeSet<&String> = services.iter().flat_map(|s| &s.references).collect();
+    references
+        .into_iter()
+        .map(|path| Stamp {
+            name: path.clone(),
+            modified: modified(Path::new(path)),
+        })
+        .collect()
+}
+
+/// Creates `dir` and its parents, and makes `dir` itself private to the
+/// user.
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Writes `path` afresh, readable by the user only.
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    // A file left behind keeps its mode when opened again.
+    let _ = std::fs::remove_file(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+fn modified(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_nanos() as u64)
+}
+
+/// Names of the variables the files `services` were read from refer to
+/// (`$VAR`, `${VAR...}`), plus COMPOSE_PROJECT_NAME, which names the
+/// project.
+fn mentioned_variables(services: &[DockerCompose]) -> Vec<String> {
+    let files: BTreeSet<&String> = services
+        .iter()
+        .flat_map(|s| s.files.iter().chain(&s.references))
+        .collect();
+    let mut names = BTreeSet::from(["COMPOSE_PROJECT_NAME".to_string()]);
+    for file in files {
+        let Ok(source) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let mut rest = source.as_str();
+        while let Some(pos) = rest.find('$') {
+            rest = &rest[pos + 1..];
+            if let Some(after) = rest.strip_prefix('$') {
+                rest = after;
+                continue;
+            }
+            let name_start = rest.strip_prefix('{').unwrap_or(rest);
+            let len = name_start
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(name_start.len());
+            if len > 0 {
+                names.insert(name_start[..len].to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// FNV-1a over the process values of `variables` (unset ones included),
+/// stable across builds unlike `DefaultHasher`.
+fn env_hash(variables: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for name in variables {
+        let value = std::env::var(name).ok();
+        let entry = match &value {
+            Some(value) => format!("{}={}\0", name, value),
+            None => format!("{}\0", name),
+        };
+        for byte in entry.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    struct Dir(PathBuf);
+
+    impl Dir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "easydocker-index-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Dir(dir)
+        }
+
+        fn root(&self) -> String {
+            self.0.display().to_string()
+        }
+
+        fn path(&self, file: &str) -> String {
+            self.0.join(file).display().to_string()
+        }
+    }
+
+    impl Drop for Dir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// An index holding the project in `dir` as it is now, whose service
+    /// refers to `references`.
+    fn indexed(dir: &Dir, references: &[String]) -> Index {
+        let root = dir.root();
+        let services = vec![DockerCompose::sample(
+            &root,
+            "web",
+            serde_json::json!({ "references": references }),
+        )];
+        Index {
+            version: VERSION,
+            projects: vec![Entry::new(root.clone(), stamps(&root), services)],
+        }
+    }
+
+    fn is_fresh(index: &Index, dir: &Dir) -> bool {
+        index.fresh(&dir.root()).is_some()
+    }
+
+    #[test]
+    fn fresh_until_a_project_file_is_modified() {
+        let dir = Dir::new("modified");
+        std::fs::write(dir.path("compose.yaml"), "services: {}\n").unwrap();
+        let index = indexed(&dir, &[]);
+        assert!(is_fresh(&index, &dir));
+
+        let file = File::options()
+            .write(true)
+            .open(dir.path("compose.yaml"))
+            .unwrap();
+        let modified = file.metadata().unwrap().modified().unwrap();
+        file.set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+        assert!(!is_fresh(&index, &dir));
+    }
+
+    #[test]
+    fn stale_when_a_project_file_appears() {
+        let dir = Dir::new("appears");
+        std::fs::write(dir.path("compose.yaml"), "services: {}\n").unwrap();
+        let index = indexed(&dir, &[]);
+
+        std::fs::write(dir.path(".env"), "TAG=1\n").unwrap();
+        assert!(!is_fresh(&index, &dir));
+    }
+
+    #[test]
+    fn stale_when_a_referenced_file_appears_or_disappears() {
+        let dir = Dir::new("references");
+        std::fs::write(dir.path("compose.yaml"), "services: {}\n").unwrap();
+        let present = dir.path("web.env");
+        let missing = dir.path("extra.env");
+        std::fs::write(&present, "A=1\n").unwrap();
+
+        let index = indexed(&dir, &[present.clone(), missing.clone()]);
+        assert!(is_fresh(&index, &dir));
+
+        std::fs::write(&missing, "B=2\n").unwrap();
+        assert!(!is_fresh(&index, &dir));
+
+        let index = indexed(&dir, &[present.clone(), missing.clone()]);
+        std::fs::remove_file(&present).unwrap();
+        assert!(!is_fresh(&index, &dir));
+    }
+
+    #[test]
+    fn stale_when_a_mentioned_variable_changes() {
+        let dir = Dir::new("variables");
+        std::fs::write(
+            dir.path("compose.yaml"),
+            "services:\n  web:\n    image: nginx:${EASYDOCKER_INDEX_TEST_TAG:-latest}\n",
+        )
+        .unwrap();
+        let index = indexed(&dir, &[]);
+        assert!(is_fresh(&index, &dir));
+
+        // Only this test uses the variable.
+        unsafe { std::env::set_var("EASYDOCKER_INDEX_TEST_TAG", "1.27") };
+        assert!(!is_fresh(&index, &dir));
+        unsafe { std::env::remove_var("EASYDOCKER_INDEX_TEST_TAG") };
+        assert!(is_fresh(&index, &dir));
+    }
+
+    #[test]
+    fn written_privately_and_read_back_only_by_the_same_version() {
+        let dir = Dir::new("write");
+        std::fs::write(dir.path("compose.yaml"), "services: {}\n").unwrap();
+        let path = dir.0.join("cache").join(INDEX_FILE);
+
+        let mut index = indexed(&dir, &[]);
+        index.write(&path).unwrap();
+        assert_eq!(Index::read(&path).projects.len(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+        }
+
+        index.version = VERSION + 1;
+        index.write(&path).unwrap();
+        assert!(Index::read(&path).projects.is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use crate::backend::{Canceller, Job};
+use crate::event::{AppEvent, EventSender};
+use crate::protocol::Message;
+
+/// Lines kept per job; older output is dropped first.
+const MAX_JOB_LOG: usize = 500;
+/// Finished jobs kept in the panel before the oldest are forgotten.
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+pub struct JobEntry {
+    pub id: u64,
+    pub label: String,
+    pub status: JobStatus,
+    /// Latest progress line, shown next to the job in the panel.
+    pub progress: String,
+    pub log: Vec<String>,
+    canceller: Option<Canceller>,
+    failed: bool,
+    cancel_requested: bool,
+}
+
+impl JobEntry {
+    pub fn is_running(&self) -> bool {
+        self.status == JobStatus::Running
+    }
+}
+
+/*
+ * Concurrent lifecycle jobs
+ *
+ * Every start/stop/create/rm/rmi gets its own entry with an id, so several
+ * can run at once without their output overwriting each other. The manager
+ * only tracks state; turning messages into text and applying side effects
+ * (rollbacks, refreshes) stays with the App.
+ * */
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Vec<JobEntry>,
+    next_id: u64,
+}
+
+impl JobManager {
+    /// Registers `job` and forwards its messages to `events` as
+    /// `AppEvent::Job`, to be fed back through `record`.
+    pub fn spawn(&mut self, label: String, job: Job, events: &EventSender) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        let Job { mut rx, cancel } = job;
+
+        let events = events.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if events.send(AppEvent::Job(id, message)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.jobs.push(JobEntry {
+            id,
+            label,
+            status: JobStatus::Running,
+            progress: String::new(),
+            log: Vec::new(),
+            canceller: Some(cancel),
+            failed: false,
+            cancel_requested: false,
+        });
+        self.prune();
+        id
+    }
+
+    /// Applies one message to job `id`, appending `text` to its log.
+    /// Returns the final status once the job is done.
+    pub fn record(
+        &mut self,
+        id: u64,
+        message: &Message,
+        text: Option<String>,
+    ) -> Option<JobStatus> {
+        let entry = self.jobs.iter_mut().find(|j| j.id == id)?;
+
+        match message {
+            Message::Progress { .. } => {
+                if let Some(text) = &text {
+                    entry.progress = text.clone();
+                }
+            }
+            Message::Result { exit_code, .. } if *exit_code != 0 => entry.failed = true,
+            Message::Error { .. } => entry.failed = true,
+            _ => {}
+        }
+
+        if let Some(text) = text {
+            entry.log.push(text);
+            if entry.log.len() > MAX_JOB_LOG {
+                let excess = entry.log.len() - MAX_JOB_LOG;
+                entry.log.drain(..excess);
+            }
+        }
+
+        if *message != Message::Done {
+            return None;
+        }
+
+        entry.canceller = None;
+        entry.status = match (entry.failed, entry.cancel_requested) {
+            (true, true) => JobStatus::Cancelled,
+            (true, false) => JobStatus::Failed,
+            (false, _) => JobStatus::Succeeded,
+        };
+        Some(entry.status)
+    }
+
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let Some(entry) = self.jobs.iter_mut().find(|j| j.id == id) else {
+            return false;
+        };
+        let Some(canceller) = entry.canceller.as_mut() else {
+            return false;
+        };
+        entry.cancel_requested = canceller.cancel();
+        entry.cancel_requested
+    }
+
+    /// Cancels everything still running and forgets all jobs.
+    pub fn clear(&mut self) {
+        for entry in &mut self.jobs {
+            if let Some(canceller) = entry.canceller.as_mut() {
+                canceller.cancel();
+            }
+        }
+        self.jobs.clear();
+    }
+
+    pub fn latest_running(&self) -> Option<u64> {
+        self.jobs
+            .iter()
+            .rev()
+            .find(|j| j.is_running())
+            .map(|j| j.id)
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.jobs.iter().any(|j| j.is_running())
+    }
+
+    pub fn jobs(&self) -> &[JobEntry] {
+        &self.jobs
+    }
+
+    /// Where job `id` is in `jobs`; spawning can forget older ones.
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.jobs.iter().position(|j| j.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    fn prune(&mut self) {
+        let finished = self.jobs.iter().filter(|j| !j.is_running()).count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|j| {
+            if excess > 0 && !j.is_running() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event;
+    use tokio::sync::{mpsc, oneshot};
+
+    fn idle() -> Job {
+        let (_, rx) = mpsc::channel(1);
+        let (cancel, _) = oneshot::channel();
+        Job::new(rx, cancel)
+    }
+
+    #[tokio::test]
+    async fn spawning_forgets_the_oldest_finished_jobs() {
+        let (events, _rx) = event::channel();
+        let mut jobs = JobManager::default();
+        let running = jobs.spawn("running".to_string(), idle(), &events);
+        let finished: Vec<u64> = (0..MAX_FINISHED_JOBS)
+            .map(|i| {
+                let id = jobs.spawn(format!("job {}", i), idle(), &events);
+                jobs.record(id, &Message::Done, None);
+                id
+            })
+            .collect();
+        assert_eq!(jobs.position(finished[1]), Some(2));
+
+        let id = jobs.spawn("one more".to_string(), idle(), &events);
+        jobs.record(id, &Message::Done, None);
+        let _ = jobs.spawn("and another".to_string(), idle(), &events);
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 2);
+        assert_eq!(jobs.position(running), Some(0));
+        assert_eq!(jobs.position(finished[0]), None);
+        assert_eq!(jobs.position(finished[1]), Some(1));
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::app::DockerCompose;
+use crate::project::project_dir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Something wrong with one service.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub severity: Severity,
+    /// Index into the service list.
+    pub service: usize,
+    pub message: String,
+}
+
+const RESTART_POLICIES: &[&str] = &["no", "always", "unless-stopped", "on-failure"];
+
+/*
+ * Workspace lint
+ *
+ * Checks each discovered service on its own (build context, Dockerfile,
+ * bind mounts, restart policy) and against its project (depends_on,
+ * networks, volumes, secrets and configs it does not define; the runner
+ * lets those through), then the workspace as a whole for container names
+ * used twice. Services in `disabled` (no active
+ * profile) are not checked and do not count. Errors come first, then
+ * warnings, each in service order.
+ * */
+pub fn check(services: &[DockerCompose], disabled: &HashSet<usize>) -> Vec<Problem> {
+    let enabled = || {
+        services
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !disabled.contains(idx))
+    };
+    let mut problems = Vec::new();
+    for (idx, service) in enabled() {
+        let mut report = |severity, message: String| {
+            problems.push(Problem {
+                severity,
+                service: idx,
+                message,
+            })
+        };
+
+        if !service.build_context.is_empty() && !is_remote(&service.build_context) {
+            let context = resolve(service, &service.build_context);
+            if !context.is_dir() {
+                report(
+                    Severity::Error,
+                    format!("Build context {} does not exist", context.display()),
+                );
+            } else {
+                let dockerfile = match service.dockerfile.as_str() {
+                    "" => "Dockerfile",
+                    name => name,
+                };
+                let path = context.join(dockerfile);
+                if !path.is_file() {
+                    report(
+                        Severity::Error,
+                        format!("Dockerfile {} does not exist", path.display()),
+                    );
+                }
+            }
+        }
+
+        // Disabled services still count as defined; starting one that
+        // depends on them is up to the dependency resolution.
+        let dir = project_dir(service);
+        for dependency in &service.depends_on {
+            let defined = services
+                .iter()
+                .any(|s| s.service == dependency.service && project_dir(s) == dir);
+            if !defined {
+                report(
+                    Severity::Error,
+                    format!(
+                        "depends_on service {} is not defined in the project",
+                        dependency.service
+                    ),
+                );
+            }
+        }
+
+        // Older runners do not report what the project defines.
+        let sections = [
+            ("Network", &service.networks, &service.project_networks),
+            ("Volume", &service.named_volumes, &service.project_volumes),
+            ("Secret", &service.secrets, &service.project_secrets),
+            ("Config", &service.configs, &service.project_configs),
+        ];
+        for (kind, used, defined) in sections {
+            let Some(defined) = defined else {
+                continue;
+            };
+            for name in used {
+                // Every project has a default network, defined or not.
+                let implicit = kind == "Network" && name == "default";
+                if !implicit && !defined.contains(name) {
+                    report(
+                        Severity::Error,
+                        format!("{} {} is not defined in the project", kind, name),
+                    );
+                }
+            }
+        }
+
+        for bind in &service.binds {
+            let path = resolve(service, bind);
+            if !path.exists() {
+                report(
+                    Severity::Warning,
+                    format!("Bind mount source {} does not exist", path.display()),
+                );
+            }
+        }
+
+        if !valid_restart(&service.restart) {
+            report(
+                Severity::Error,
+                format!("Invalid restart policy {:?}", service.restart),
+            );
+        }
+    }
+
+    for (idx, service) in enabled() {
+        let name = &service.container_name;
+        if name.is_empty() {
+            continue;
+        }
+        let others: Vec<String> = enabled()
+            .filter(|(other, s)| *other != idx && s.container_name == *name)
+            .map(|(_, s)| format!("{} ({})", s.service, s.name))
+            .collect();
+        if !others.is_empty() {
+            problems.push(Problem {
+                severity: Severity::Error,
+                service: idx,
+                message: format!(
+                    "container_name {} is also used by {}",
+                    name,
+                    others.join(", ")
+                ),
+            });
+        }
+    }
+
+    problems.sort_by_key(|p| (p.severity, p.service));
+    problems
+}
+
+/// `no`, `always`, `unless-stopped`, `on-failure` or `on-failure:N`; empty
+/// means the default, `no`.
+pub fn valid_restart(policy: &str) -> bool {
+    if policy.is_empty() || RESTART_POLICIES.contains(&policy) {
+        return true;
+    }
+    policy
+        .strip_prefix("on-failure:")
+        .is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+/// Build contexts can be git or https URLs, which are not checked.
+fn is_remote(context: &str) -> bool {
+    context.contains("://") || context.starts_with("git@") || context.starts_with("github.com/")
+}
+
+/// The runner reports paths resolved against the project directory;
+/// anything still relative is taken from there.
+fn resolve(service: &DockerCompose, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    service
+        .files
+        .first()
+        .and_then(|file| Path::new(file).parent())
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A service of /srv/app, which defines the volume `data` and nothing
+    // else.
+    fn service(name: &str, mut fields: serde_json::Value) -> DockerCompose {
+        let defined = serde_json::json!({
+            "project_networks": [],
+            "project_volumes": ["data"],
+            "project_secrets": [],
+            "project_configs": [],
+        });
+        for (key, value) in defined.as_object().unwrap() {
+            if fields.get(key).is_none() {
+                fields[key] = value.clone();
+            }
+        }
+        DockerCompose::sample("/srv/app", name, fields)
+    }
+
+    fn messages(services: &[DockerCompose]) -> Vec<String> {
+        check(services, &HashSet::new())
+            .into_iter()
+            .map(|p| format!("{}: {}", services[p.service].service, p.message))
+            .collect()
+    }
+
+    #[test]
+    fn undefined_references() {
+        let services = [
+            service(
+                "web",
+                serde_json::json!({
+                    "depends_on": [
+                        {"service": "db", "condition": "service_started"},
+                        {"service": "cache", "condition": "service_started"},
+                    ],
+                    "networks": ["default", "front"],
+                    "named_volumes": ["data", "logs"],
+                    "secrets": ["token"],
+                    "configs": ["nginx"],
+                }),
+            ),
+            service("db", serde_json::json!({})),
+            // Same service name in another project does not count.
+            service(
+                "cache",
+                serde_json::json!({"files": ["/srv/other/compose.yaml"]}),
+            ),
+        ];
+        assert_eq!(
+            messages(&services),
+            [
+                "web: depends_on service cache is not defined in the project",
+                "web: Network front is not defined in the project",
+                "web: Volume logs is not defined in the project",
+                "web: Secret token is not defined in the project",
+                "web: Config nginx is not defined in the project",
+            ]
+        );
+    }
+
+    #[test]
+    fn older_runners_skip_project_checks() {
+        let services = [service(
+            "web",
+            serde_json::json!({
+                "named_volumes": ["logs"],
+                "project_volumes": null,
+                "secrets": ["token"],
+                "project_secrets": null,
+            }),
+        )];
+        assert!(messages(&services).is_empty());
+    }
+}
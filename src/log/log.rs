@@ -1,9 +1,7 @@
 // Copyright 2026 Fitrian Musya
 // SPDX-License-Identifier: MIT
 
-use std::io::{self, Result};
-
-use tokio::{fs::File, io::AsyncWriteExt};
+use crate::secrets::Masker;
 
 pub enum LogType {
     Info,
@@ -19,6 +17,8 @@ pub struct Log {
 
 pub struct LogList {
     log: Log,
+    // Secrets are masked as messages come in, so they are never kept.
+    masker: Masker,
 }
 
 impl Default for LogList {
@@ -28,11 +28,16 @@ impl Default for LogList {
                 status: "".to_string(),
                 message: "".to_string(),
             },
+            masker: Masker::default(),
         }
     }
 }
 
 impl LogList {
+    pub fn set_masker(&mut self, masker: Masker) {
+        self.masker = masker;
+    }
+
     pub fn print_mes(&mut self, status: LogType, message: &str) {
         let matching = match status {
             LogType::Info => "[Info]",
@@ -41,7 +46,7 @@ impl LogList {
         };
         self.log = Log {
             status: matching.to_string(),
-            message: message.to_string(),
+            message: self.masker.text(message),
         };
     }
 
@@ -52,12 +57,4 @@ impl LogList {
             format!("{} {}", self.log.status, self.log.message)
         }
     }
-
-    pub async fn local(&mut self, message: &str) -> io::Result<()> {
-        let mut file = File::create("/tmp/easydocker.txt").await?;
-
-        file.write_all(message.as_bytes()).await?;
-
-        Ok(())
-    }
 }
@@ -1,4 +1,5 @@
 // Copyright 2026 Fitrian Musya
 // SPDX-License-Identifier: MIT
 
+#[allow(clippy::module_inception)]
 pub mod log;
@@ -2,19 +2,33 @@
 // SPDX-License-Identifier: MIT
 
 mod app;
+mod backend;
+mod compose;
+mod config;
+mod dotenv;
+mod event;
+mod form;
+mod index;
+mod jobs;
+mod lint;
 mod log;
+mod ports;
+mod project;
+mod protocol;
+mod secrets;
 mod ui;
+mod watch;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{Event as TermEvent, KeyCode, KeyEventKind},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use rand::Rng;
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
-use std::time::Duration;
 
 use app::App;
+use config::Config;
+use event::{Event, EventLoop};
 use ui::draw_ui;
 
 use crate::log::log::LogType;
@@ -28,189 +42,325 @@ async fn main() -> io::Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::default();
-
-    app.loading = true;
-    app.log.print_mes(LogType::Info, "Fetching Containers");
+    app.set_config(Config::load());
+    // Needs no runner, so the last list is drawn before the handshake,
+    // which may take a while.
+    app.load_index();
     terminal.draw(|f| draw_ui(f, &mut app))?;
 
-    app.fetch_containers().await;
-    if !app.loading {
-        app.log.print_mes(LogType::Info, "Container loaded");
-    } else {
-        app.log
-            .print_mes(LogType::Error, "Failed to fetching container");
+    if app.connect_runner().await {
+        app.log.print_mes(LogType::Info, "Fetching Containers");
+        app.fetch_containers();
+        app.watch_workspace();
     }
 
+    let mut events = EventLoop::new(app.take_events().expect("event receiver already taken"));
+    let mut dirty = true;
+
     loop {
-        app.poll_logs();
-        app.poll_analytics(); // Always poll analytics to keep graph moving
+        if app.current_tab == app::Tab::Deployments
+            && let Some(ref container_id) = app.selected_container_id.clone()
+        {
+            app.start_analytics_stream(container_id);
+        }
 
-        if app.current_tab == app::Tab::Deployments {
-            if let Some(ref container_id) = app.selected_container_id.clone() {
-                app.start_analytics_stream(&container_id);
-            }
+        // Every event below decides whether it changed what is on screen.
+        if dirty {
+            terminal.draw(|f| draw_ui(f, &mut app))?;
         }
 
-        terminal.draw(|f| draw_ui(f, &mut app))?;
+        let Some(event) = events.next(app.is_ticking()).await else {
+            break;
+        };
+        let key = match event {
+            Event::App(event) => {
+                app.handle_event(event);
+                dirty = true;
+                continue;
+            }
+            Event::Tick => {
+                dirty = app.tick();
+                continue;
+            }
+            Event::Terminal(TermEvent::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Event::Terminal(TermEvent::Key(_)) => {
+                dirty = false;
+                continue;
+            }
+            Event::Terminal(_) => {
+                // Resize, focus and the like.
+                dirty = true;
+                continue;
+            }
+        };
+        dirty = true;
 
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if app.env_editor_open {
-                        match key.code {
-                            KeyCode::Esc => {
-                                if app.env_editor_editing {
-                                    app.env_editor_editing = false;
-                                    app.env_editor_buffer = String::new();
-                                } else {
-                                    app.close_env_editor();
-                                }
-                            }
-                            KeyCode::Enter => {
-                                if app.env_editor_editing {
-                                    app.env_editor_confirm_edit();
-                                } else {
-                                    app.env_editor_start_edit();
-                                }
-                            }
-                            KeyCode::Up | KeyCode::Char('k') if !app.env_editor_editing => {
-                                app.env_editor_move_up();
-                            }
-                            KeyCode::Down | KeyCode::Char('j') if !app.env_editor_editing => {
-                                app.env_editor_move_down();
-                            }
-                            KeyCode::Char('a') if !app.env_editor_editing => {
-                                app.env_editor_add_line();
-                            }
-                            KeyCode::Char('d') | KeyCode::Char('x')
-                                if !app.env_editor_editing =>
-                            {
-                                app.env_editor_delete_line();
-                            }
-                            KeyCode::Char('s') if !app.env_editor_editing => {
-                                app.save_env_editor();
-                            }
-                            KeyCode::Char(c) if app.env_editor_editing => {
-                                app.env_editor_input_char(c);
-                            }
-                            KeyCode::Backspace if app.env_editor_editing => {
-                                app.env_editor_backspace();
-                            }
-                            _ => {}
-                        }
+        if app.runner_error.is_some() {
+            match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    let connected = app.connect_runner().await;
+                    if connected {
+                        app.fetch_containers();
+                        app.watch_workspace();
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(input) = app.workspace_input.as_mut() {
+            match key.code {
+                KeyCode::Esc => app.workspace_input = None,
+                KeyCode::Enter => app.add_workspace_root(),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        } else if app.workspace_open {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('w') => app.workspace_open = false,
+                KeyCode::Up | KeyCode::Char('k') => app.workspace_prev(),
+                KeyCode::Down | KeyCode::Char('j') => app.workspace_next(),
+                KeyCode::Char('a') => app.workspace_input = Some(String::new()),
+                KeyCode::Char('d') | KeyCode::Char('x') => app.remove_workspace_root(),
+                KeyCode::Char('r') => app.rescan(),
+                _ => {}
+            }
+        } else if app.profiles_open {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('P') => app.profiles_open = false,
+                KeyCode::Up | KeyCode::Char('k') => app.profiles_prev(),
+                KeyCode::Down | KeyCode::Char('j') => app.profiles_next(),
+                KeyCode::Char(' ') | KeyCode::Enter => app.toggle_profile(),
+                _ => {}
+            }
+        } else if app.lint_open {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('L') => app.lint_open = false,
+                KeyCode::Up | KeyCode::Char('k') => app.lint_prev(),
+                KeyCode::Down | KeyCode::Char('j') => app.lint_next(),
+                KeyCode::Enter => app.jump_to_problem(),
+                _ => {}
+            }
+        } else if app.port_conflicts_open {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('p')) {
+                app.port_conflicts_open = false;
+            }
+        } else if app.context_picker_open {
+            match key.code {
+                KeyCode::Esc => app.context_picker_open = false,
+                KeyCode::Up | KeyCode::Char('k') => app.context_prev(),
+                KeyCode::Down | KeyCode::Char('j') => app.context_next(),
+                KeyCode::Enter => app.switch_context(),
+                _ => {}
+            }
+        } else if !app.save_preview.is_empty() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => app.confirm_save(),
+                KeyCode::Char('n') | KeyCode::Esc => app.cancel_save(),
+                KeyCode::Up | KeyCode::Char('k') => app.save_preview_scroll_up(),
+                KeyCode::Down | KeyCode::Char('j') => app.save_preview_scroll_down(),
+                _ => {}
+            }
+        } else if let Some(form) = app.form.as_mut() {
+            if form.editing {
+                match key.code {
+                    KeyCode::Esc => form.cancel_edit(),
+                    KeyCode::Enter => form.confirm_edit(),
+                    KeyCode::Backspace => {
+                        form.buffer.pop();
+                    }
+                    KeyCode::Char(c) => form.buffer.push(c),
+                    _ => {}
+                }
+            } else {
+                match key.code {
+                    KeyCode::Esc => app.form = None,
+                    KeyCode::Up | KeyCode::Char('k') => form.move_up(),
+                    KeyCode::Down | KeyCode::Char('j') => form.move_down(),
+                    KeyCode::Enter => form.start_edit(),
+                    KeyCode::Char('a') => form.add_item(),
+                    KeyCode::Char('d') | KeyCode::Char('x') => form.delete_item(),
+                    KeyCode::Char('v') => form.toggle_reveal(),
+                    KeyCode::Char('s') => app.save_form(),
+                    _ => {}
+                }
+            }
+        } else if app.env_editor_open {
+            match key.code {
+                KeyCode::Esc => {
+                    if app.env_editor_editing {
+                        app.env_editor_editing = false;
+                        app.env_editor_buffer = String::new();
                     } else {
-                        match key.code {
-                            KeyCode::Char('q') => break,
-                            KeyCode::Char('r') | KeyCode::Char('R') => match app.current_tab {
-                                app::Tab::Containers => app.fetch_containers().await,
-                                app::Tab::Images => app.fetch_images().await,
-                                app::Tab::Deployments => app.fetch_running_containers().await,
-                            },
-                            KeyCode::Tab => {
-                                app.next_tab();
-                                if app.current_tab == app::Tab::Images && app.images.is_empty() {
-                                    app.fetch_images().await;
-                                }
-                                if app.current_tab == app::Tab::Deployments
-                                    && app.running_containers.is_empty()
-                                {
-                                    app.fetch_running_containers().await;
-                                }
-                            }
-                            KeyCode::BackTab => {
-                                app.prev_tab();
-                                if app.current_tab == app::Tab::Images && app.images.is_empty() {
-                                    app.fetch_images().await;
-                                }
-                                if app.current_tab == app::Tab::Deployments
-                                    && app.running_containers.is_empty()
-                                {
-                                    app.fetch_running_containers().await;
-                                }
-                            }
-                            KeyCode::Char('d') => app.delete().await,
-                            KeyCode::Up | KeyCode::Char('k') => match app.current_tab {
-                                app::Tab::Containers => {
-                                    if app.expanded_index.is_some() {
-                                        app.menu_prev();
-                                    } else {
-                                        app.select_prev_container();
-                                    }
-                                }
-                                app::Tab::Images => {
-                                    if app.image_expanded_index.is_some() {
-                                        app.image_menu_prev();
-                                    } else {
-                                        app.select_prev_image();
-                                    }
-                                }
-                                app::Tab::Deployments => app.select_prev_running_container(),
-                            },
-                            KeyCode::Down | KeyCode::Char('j') => match app.current_tab {
-                                app::Tab::Containers => {
-                                    if app.expanded_index.is_some() {
-                                        app.menu_next();
-                                    } else {
-                                        app.select_next_container();
-                                    }
-                                }
-                                app::Tab::Images => {
-                                    if app.image_expanded_index.is_some() {
-                                        app.image_menu_next();
-                                    } else {
-                                        app.select_next_image();
-                                    }
-                                }
-                                app::Tab::Deployments => app.select_next_running_container(),
-                            },
-                            KeyCode::Enter => match app.current_tab {
-                                app::Tab::Containers => {
-                                    if app.expanded_index.is_some() {
-                                        app.execute_menu_action().await;
-                                    } else {
-                                        app.toggle_expand();
-                                    }
-                                }
-                                app::Tab::Images => {
-                                    if app.image_expanded_index.is_some() {
-                                        app.execute_image_menu_action().await;
-                                    } else {
-                                        app.toggle_image_expand();
-                                    }
-                                }
-                                app::Tab::Deployments => {
-                                    app.select_running_container();
-                                }
-                            },
-                            KeyCode::Esc => {
-                                app.expanded_index = None;
-                                app.menu_selection = 0;
-                                app.image_expanded_index = None;
-                                app.image_menu_selection = 0;
-                            }
-                            KeyCode::Left | KeyCode::Char('h') => {
-                                if app.expanded_index.is_some() {
-                                    app.menu_prev();
-                                } else {
-                                    app.scroll_log_left();
-                                }
-                            }
-                            KeyCode::Right | KeyCode::Char('l') => {
-                                if app.expanded_index.is_some() {
-                                    app.menu_next();
-                                } else {
-                                    app.scroll_log_right();
-                                }
-                            }
-                            KeyCode::Char('e') => {
-                                if app.current_tab == app::Tab::Containers {
-                                    app.open_env_editor();
-                                }
-                            }
-                            _ => {}
+                        app.close_env_editor();
+                    }
+                }
+                KeyCode::Enter => {
+                    if app.env_editor_editing {
+                        app.env_editor_confirm_edit();
+                    } else {
+                        app.env_editor_start_edit();
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') if !app.env_editor_editing => {
+                    app.env_editor_move_up();
+                }
+                KeyCode::Down | KeyCode::Char('j') if !app.env_editor_editing => {
+                    app.env_editor_move_down();
+                }
+                KeyCode::Char('a') if !app.env_editor_editing => {
+                    app.env_editor_add_line();
+                }
+                KeyCode::Char('d') | KeyCode::Char('x') if !app.env_editor_editing => {
+                    app.env_editor_delete_line();
+                }
+                KeyCode::Char('s') if !app.env_editor_editing => {
+                    app.save_env_editor();
+                }
+                KeyCode::Char('v') if !app.env_editor_editing => {
+                    app.env_editor_toggle_reveal();
+                }
+                KeyCode::Char(c) if app.env_editor_editing => {
+                    app.env_editor_input_char(c);
+                }
+                KeyCode::Backspace if app.env_editor_editing => {
+                    app.env_editor_backspace();
+                }
+                _ => {}
+            }
+        } else {
+            match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('r') | KeyCode::Char('R') => match app.current_tab {
+                    app::Tab::Containers => app.fetch_containers(),
+                    app::Tab::Images => app.fetch_images(),
+                    app::Tab::Deployments => app.fetch_running_containers(),
+                    app::Tab::Jobs => {}
+                },
+                KeyCode::Tab => {
+                    app.next_tab();
+                    if app.current_tab == app::Tab::Images && app.images.is_empty() {
+                        app.fetch_images();
+                    }
+                    if app.current_tab == app::Tab::Deployments && app.running_containers.is_empty()
+                    {
+                        app.fetch_running_containers();
+                    }
+                }
+                KeyCode::BackTab => {
+                    app.prev_tab();
+                    if app.current_tab == app::Tab::Images && app.images.is_empty() {
+                        app.fetch_images();
+                    }
+                    if app.current_tab == app::Tab::Deployments && app.running_containers.is_empty()
+                    {
+                        app.fetch_running_containers();
+                    }
+                }
+                KeyCode::Char('d') => app.delete(),
+                KeyCode::Char('c') => app.cancel_job(),
+                KeyCode::Char('x') => app.open_context_picker(),
+                KeyCode::Char('L') => app.open_lint(),
+                KeyCode::Up | KeyCode::Char('k') => match app.current_tab {
+                    app::Tab::Containers => {
+                        if app.expanded_index.is_some() {
+                            app.menu_prev();
+                        } else {
+                            app.select_prev_container();
+                        }
+                    }
+                    app::Tab::Images => {
+                        if app.image_expanded_index.is_some() {
+                            app.image_menu_prev();
+                        } else {
+                            app.select_prev_image();
+                        }
+                    }
+                    app::Tab::Deployments => app.select_prev_running_container(),
+                    app::Tab::Jobs => app.select_prev_job(),
+                },
+                KeyCode::Down | KeyCode::Char('j') => match app.current_tab {
+                    app::Tab::Containers => {
+                        if app.expanded_index.is_some() {
+                            app.menu_next();
+                        } else {
+                            app.select_next_container();
+                        }
+                    }
+                    app::Tab::Images => {
+                        if app.image_expanded_index.is_some() {
+                            app.image_menu_next();
+                        } else {
+                            app.select_next_image();
+                        }
+                    }
+                    app::Tab::Deployments => app.select_next_running_container(),
+                    app::Tab::Jobs => app.select_next_job(),
+                },
+                KeyCode::Enter => match app.current_tab {
+                    app::Tab::Containers => {
+                        if app.expanded_index.is_some() {
+                            app.execute_menu_action();
+                        } else {
+                            app.toggle_expand();
+                        }
+                    }
+                    app::Tab::Images => {
+                        if app.image_expanded_index.is_some() {
+                            app.execute_image_menu_action();
+                        } else {
+                            app.toggle_image_expand();
                         }
                     }
+                    app::Tab::Deployments => {
+                        app.select_running_container();
+                    }
+                    app::Tab::Jobs => {}
+                },
+                KeyCode::Esc => {
+                    app.expanded_index = None;
+                    app.menu_selection = 0;
+                    app.image_expanded_index = None;
+                    app.image_menu_selection = 0;
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    if app.expanded_index.is_some() {
+                        app.menu_prev();
+                    } else {
+                        app.scroll_log_left();
+                    }
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    if app.expanded_index.is_some() {
+                        app.menu_next();
+                    } else {
+                        app.scroll_log_right();
+                    }
+                }
+                KeyCode::Char('e') if app.current_tab == app::Tab::Containers => {
+                    app.open_env_editor();
+                }
+                KeyCode::Char('w') if app.current_tab == app::Tab::Containers => {
+                    app.open_workspace();
+                }
+                KeyCode::Char('f') if app.current_tab == app::Tab::Containers => {
+                    app.open_form();
+                }
+                KeyCode::Char('E') if app.current_tab == app::Tab::Containers => {
+                    app.open_dotenv_editor();
+                }
+                KeyCode::Char('p') if app.current_tab == app::Tab::Containers => {
+                    app.open_port_conflicts();
+                }
+                KeyCode::Char('P') if app.current_tab == app::Tab::Containers => {
+                    app.open_profiles();
+                }
+                KeyCode::Char(' ') if app.current_tab == app::Tab::Containers => {
+                    app.toggle_project();
                 }
+                _ => {}
             }
         }
     }
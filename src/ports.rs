@@ -0,0 +1,297 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+
+use crate::app::{DockerCompose, RunningContainer};
+
+/// Something holding a host port.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Owner {
+    /// A compose service, by index into the service list.
+    Service(usize),
+    /// A running container no compose service accounts for.
+    Container(String),
+}
+
+/// An owner's claim on a host port.
+#[derive(Debug, Clone)]
+pub struct Claim {
+    pub owner: Owner,
+    /// Whether a running container holds the port for this owner.
+    pub running: bool,
+}
+
+/// A host port wanted by more than one owner.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub port: u16,
+    pub claims: Vec<Claim>,
+}
+
+impl Conflict {
+    /// Whether the port is in use, so starting anything else on it fails.
+    pub fn taken(&self) -> bool {
+        self.claims.iter().any(|c| c.running)
+    }
+}
+
+/// Host ports a service publishes. The runner reports them comma separated,
+/// already interpolated; ranges such as `8000-8002` are expanded.
+pub fn host_ports(service: &DockerCompose) -> Vec<u16> {
+    let mut ports = Vec::new();
+    for published in service.ports.split(',').map(str::trim) {
+        let range = match published.split_once('-') {
+            Some((start, end)) => start.parse::<u16>().ok().zip(end.parse::<u16>().ok()),
+            None => published.parse::<u16>().ok().map(|p| (p, p)),
+        };
+        if let Some((start, end)) = range {
+            ports.extend(start..=end);
+        }
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Public ports of a running container, once each (Docker lists a port
+/// for IPv4 and IPv6 separately).
+fn public_ports(container: &RunningContainer) -> Vec<u16> {
+    let mut ports: Vec<u16> = Vec::new();
+    if container.state != "running" {
+        return ports;
+    }
+    for port in container.ports.iter().flatten() {
+        if port.public_port != 0 && !ports.contains(&port.public_port) {
+            ports.push(port.public_port);
+        }
+    }
+    ports
+}
+
+/// The service a container was created from, if any: the one whose
+/// target name is exactly the container's name. `myapp-web` is not
+/// service `web` of project `app`.
+fn service_of(services: &[DockerCompose], container: &RunningContainer) -> Option<usize> {
+    services
+        .iter()
+        .position(|s| container.has_name(&s.target_name()))
+}
+
+/*
+ * Port conflicts
+ *
+ * Every host port is collected with what claims it: each compose service
+ * publishing it, and each running container that publishes it. A running
+ * container created from a service counts as that service, so a service
+ * never conflicts with its own container. Services in `disabled` (no
+ * active profile) claim nothing until they run. Ports with more than one
+ * owner are conflicts, listed by port.
+ * */
+pub fn conflicts(
+    services: &[DockerCompose],
+    disabled: &HashSet<usize>,
+    running: &[RunningContainer],
+) -> Vec<Conflict> {
+    let mut claims: Vec<(u16, Claim)> = Vec::new();
+    for (idx, service) in services.iter().enumerate() {
+        if disabled.contains(&idx) {
+            continue;
+        }
+        for port in host_ports(service) {
+            let owner = Owner::Service(idx);
+            claims.push((
+                port,
+                Claim {
+                    owner,
+                    running: false,
+                },
+            ));
+        }
+    }
+    for container in running {
+        let owner = match service_of(services, container) {
+            Some(idx) => Owner::Service(idx),
+            None => Owner::Container(container_name(container)),
+        };
+        for port in public_ports(container) {
+            match claims
+                .iter_mut()
+                .find(|(p, c)| *p == port && c.owner == owner)
+            {
+                Some((_, claim)) => claim.running = true,
+                None => {
+                    let owner = owner.clone();
+                    claims.push((
+                        port,
+                        Claim {
+                            owner,
+                            running: true,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut ports: Vec<u16> = claims.iter().map(|(p, _)| *p).collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+        .into_iter()
+        .filter_map(|port| {
+            let held: Vec<Claim> = claims
+                .iter()
+                .filter(|(p, _)| *p == port)
+                .map(|(_, c)| c.clone())
+                .collect();
+            (held.len() > 1).then_some(Conflict { port, claims: held })
+        })
+        .collect()
+}
+
+/// Conflicts that starting `service` runs into: ports it publishes that
+/// another owner claims too. Only the other owners are kept.
+pub fn conflicts_for(
+    services: &[DockerCompose],
+    disabled: &HashSet<usize>,
+    running: &[RunningContainer],
+    service: usize,
+) -> Vec<Conflict> {
+    let own = Owner::Service(service);
+    conflicts(services, disabled, running)
+        .into_iter()
+        .filter(|c| c.claims.iter().any(|claim| claim.owner == own))
+        .map(|mut c| {
+            c.claims.retain(|claim| claim.owner != own);
+            c
+        })
+        .collect()
+}
+
+/// How an owner is named in messages.
+pub fn describe(services: &[DockerCompose], owner: &Owner) -> String {
+    match owner {
+        Owner::Service(idx) => {
+            let service = &services[*idx];
+            format!("service {} ({})", service.service, service.name)
+        }
+        Owner::Container(name) => format!("container {}", name),
+    }
+}
+
+fn container_name(container: &RunningContainer) -> String {
+    container
+        .names
+        .first()
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| container.id.chars().take(12).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ContainerPort;
+
+    fn service(dir: &str, name: &str, ports: &str) -> DockerCompose {
+        DockerCompose::sample(dir, name, serde_json::json!({ "ports": ports }))
+    }
+
+    // Docker lists a published port once for IPv4 and once for IPv6.
+    fn running(name: &str, ports: &[u16]) -> RunningContainer {
+        let ports = ports
+            .iter()
+            .flat_map(|&port| {
+                ["0.0.0.0", "::"].map(|ip| ContainerPort {
+                    ip: ip.to_string(),
+                    private_port: 80,
+                    public_port: port,
+                    port_type: "tcp".to_string(),
+                })
+            })
+            .collect();
+        RunningContainer {
+            id: "0123456789ab".to_string(),
+            image: "nginx".to_string(),
+            command: String::new(),
+            created: 0,
+            status: "Up 1 minute".to_string(),
+            state: "running".to_string(),
+            ports: Some(ports),
+            names: vec![format!("/{}", name)],
+            health: None,
+        }
+    }
+
+    // Each conflict as "port: owner, owner", running owners marked `*`.
+    fn summary(services: &[DockerCompose], conflicts: &[Conflict]) -> Vec<String> {
+        conflicts
+            .iter()
+            .map(|c| {
+                let owners: Vec<String> = c
+                    .claims
+                    .iter()
+                    .map(|claim| {
+                        let mark = if claim.running { "*" } else { "" };
+                        format!("{}{}", describe(services, &claim.owner), mark)
+                    })
+                    .collect();
+                format!("{}: {}", c.port, owners.join(", "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn own_container_is_no_conflict() {
+        let services = [service("/srv/app", "web", "8080")];
+        let running = [running("app-web", &[8080])];
+        assert!(conflicts(&services, &HashSet::new(), &running).is_empty());
+        assert!(conflicts_for(&services, &HashSet::new(), &running, 0).is_empty());
+    }
+
+    #[test]
+    fn foreign_container_conflicts() {
+        let services = [service("/srv/app", "web", "8080")];
+        let running = [running("myapp-web", &[8080])];
+        let found = conflicts(&services, &HashSet::new(), &running);
+        assert_eq!(
+            summary(&services, &found),
+            ["8080: service web (app), container myapp-web*"]
+        );
+        assert!(found[0].taken());
+
+        let found = conflicts_for(&services, &HashSet::new(), &running, 0);
+        assert_eq!(summary(&services, &found), ["8080: container myapp-web*"]);
+    }
+
+    #[test]
+    fn disabled_services_claim_nothing_until_they_run() {
+        let services = [
+            service("/srv/app", "web", "8080"),
+            service("/srv/app", "debug", "8080"),
+        ];
+        let disabled = HashSet::from([1]);
+        assert!(conflicts(&services, &disabled, &[]).is_empty());
+
+        let running = [running("app-debug", &[8080])];
+        let found = conflicts_for(&services, &disabled, &running, 0);
+        assert_eq!(summary(&services, &found), ["8080: service debug (app)*"]);
+    }
+
+    #[test]
+    fn port_ranges_conflict_where_they_overlap() {
+        let services = [
+            service("/srv/app", "web", "8000-8002, 9000"),
+            service("/srv/other", "api", "8002"),
+            service("/srv/other", "docs", "8003-8004"),
+        ];
+        assert_eq!(host_ports(&services[0]), [8000, 8001, 8002, 9000]);
+        let found = conflicts(&services, &HashSet::new(), &[]);
+        assert_eq!(
+            summary(&services, &found),
+            ["8002: service web (app), service api (other)"]
+        );
+        assert!(!found[0].taken());
+        assert!(conflicts_for(&services, &HashSet::new(), &[], 2).is_empty());
+    }
+}
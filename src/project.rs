@@ -0,0 +1,283 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Result, bail};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::app::DockerCompose;
+
+/// A Compose project: the services loaded from one project directory.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    /// Directory of the compose files, or the name when the runner did not
+    /// report any. Two projects can share a name but never a directory.
+    pub dir: String,
+    /// Indexes into the service list, in the order they were read. Only
+    /// services enabled by the active profiles; everything acting on the
+    /// project goes by these.
+    pub services: Vec<usize>,
+    /// Services none of whose profiles is active.
+    pub disabled: Vec<usize>,
+}
+
+/// One line of the Containers tab.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerRow {
+    /// Index into the project list.
+    Project(usize),
+    /// Index into the service list.
+    Service(usize),
+}
+
+/*
+ * Project → service tree
+ *
+ * The runner reports a flat list of services carrying their project name.
+ * They are grouped back by project directory, keeping the order the
+ * projects were discovered in, and flattened into rows for the list
+ * widget; a collapsed project shows only its own row. Services `enabled`
+ * rejects are kept aside and get no row.
+ * */
+pub fn group(services: &[DockerCompose], enabled: impl Fn(&DockerCompose) -> bool) -> Vec<Project> {
+    let mut projects: Vec<Project> = Vec::new();
+    for (idx, service) in services.iter().enumerate() {
+        let dir = project_dir(service);
+        let project = match projects.iter().position(|p| p.dir == dir) {
+            Some(p) => &mut projects[p],
+            None => {
+                projects.push(Project {
+                    name: service.name.clone(),
+                    dir,
+                    services: Vec::new(),
+                    disabled: Vec::new(),
+                });
+                projects.last_mut().unwrap()
+            }
+        };
+        if enabled(service) {
+            project.services.push(idx);
+        } else {
+            project.disabled.push(idx);
+        }
+    }
+    projects
+}
+
+/// Every profile the project's services name, sorted.
+pub fn profiles(services: &[DockerCompose], project: &Project) -> Vec<String> {
+    let mut profiles: Vec<String> = project
+        .services
+        .iter()
+        .chain(&project.disabled)
+        .flat_map(|&s| services[s].profiles.iter().cloned())
+        .collect();
+    profiles.sort();
+    profiles.dedup();
+    profiles
+}
+
+pub fn rows(projects: &[Project], collapsed: &HashSet<String>) -> Vec<ContainerRow> {
+    let mut rows = Vec::new();
+    for (idx, project) in projects.iter().enumerate() {
+        rows.push(ContainerRow::Project(idx));
+        if !collapsed.contains(&project.dir) {
+            rows.extend(project.services.iter().map(|&s| ContainerRow::Service(s)));
+        }
+    }
+    rows
+}
+
+/*
+ * depends_on ordering
+ *
+ * A depth-first walk over depends_on within the project: every service
+ * comes after the services it depends on, and `roots` come in the order
+ * given otherwise. Dependencies naming a service the project does not have
+ * are left out. One none of whose profiles is active is an error, as it is
+ * for `docker compose up`, naming the profiles that would enable it.
+ * Walking back into a service still on the current path is a cycle,
+ * reported with the path that closes it.
+ * */
+pub fn start_order(
+    services: &[DockerCompose],
+    project: &Project,
+    roots: &[usize],
+) -> Result<Vec<usize>> {
+    let mut order = Vec::new();
+    let mut path = Vec::new();
+    for &root in roots {
+        visit(services, project, root, &mut path, &mut order)?;
+    }
+    Ok(order)
+}
+
+fn visit(
+    services: &[DockerCompose],
+    project: &Project,
+    idx: usize,
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> Result<()> {
+    if order.contains(&idx) {
+        return Ok(());
+    }
+    if let Some(pos) = path.iter().position(|&p| p == idx) {
+        let cycle: Vec<&str> = path[pos..]
+            .iter()
+            .chain(std::iter::once(&idx))
+            .map(|&s| services[s].service.as_str())
+            .collect();
+        bail!(
+            "Dependency cycle in project {}: {}",
+            project.name,
+            cycle.join(" → ")
+        );
+    }
+
+    path.push(idx);
+    for dep in &services[idx].depends_on {
+        if let Some(d) = find_service(services, project, &dep.service) {
+            visit(services, project, d, path, order)?;
+        } else if let Some(&d) = project
+            .disabled
+            .iter()
+            .find(|&&s| services[s].service == dep.service)
+        {
+            bail!(
+                "{} depends on {}, which is only enabled by profile {} in project {}",
+                services[idx].service,
+                dep.service,
+                services[d].profiles.join(" or "),
+                project.name
+            );
+        }
+    }
+    path.pop();
+    order.push(idx);
+    Ok(())
+}
+
+/// Services of `project` that depend on `idx`, directly or through others.
+pub fn dependents(services: &[DockerCompose], project: &Project, idx: usize) -> Vec<usize> {
+    let mut found = vec![idx];
+    loop {
+        let next = project.services.iter().copied().find(|s| {
+            !found.contains(s)
+                && services[*s]
+                    .depends_on
+                    .iter()
+                    .any(|dep| found.iter().any(|&f| services[f].service == dep.service))
+        });
+        match next {
+            Some(s) => found.push(s),
+            None => break,
+        }
+    }
+    found.split_off(1)
+}
+
+fn find_service(services: &[DockerCompose], project: &Project, name: &str) -> Option<usize> {
+    project
+        .services
+        .iter()
+        .copied()
+        .find(|&s| services[s].service == name)
+}
+
+/// Directory of the compose files `service` was loaded from.
+pub fn project_dir(service: &DockerCompose) -> String {
+    service
+        .files
+        .first()
+        .and_then(|file| Path::new(file).parent())
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| service.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Services of /srv/app named by the first entry of each pair, which
+    // depend on the services the second one names.
+    fn sample(graph: &[(&str, &[&str])]) -> (Vec<DockerCompose>, Project) {
+        let services: Vec<DockerCompose> = graph
+            .iter()
+            .map(|(name, deps)| {
+                let deps: Vec<_> = deps
+                    .iter()
+                    .map(|d| serde_json::json!({"service": d, "condition": "service_started"}))
+                    .collect();
+                DockerCompose::sample("/srv/app", name, serde_json::json!({"depends_on": deps}))
+            })
+            .collect();
+        let mut projects = group(&services, |_| true);
+        (services, projects.remove(0))
+    }
+
+    fn names(services: &[DockerCompose], order: &[usize]) -> Vec<String> {
+        order.iter().map(|&s| services[s].service.clone()).collect()
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first() {
+        let (services, project) = sample(&[
+            ("web", &["api", "cache"]),
+            ("api", &["db", "cache"]),
+            ("cache", &[]),
+            ("db", &[]),
+            ("worker", &["db", "queue"]),
+        ]);
+        let order = start_order(&services, &project, &project.services).unwrap();
+        assert_eq!(
+            names(&services, &order),
+            ["db", "cache", "api", "web", "worker"]
+        );
+        let order = start_order(&services, &project, &[1]).unwrap();
+        assert_eq!(names(&services, &order), ["db", "cache", "api"]);
+    }
+
+    #[test]
+    fn start_order_reports_cycles() {
+        let (services, project) = sample(&[("web", &["api"]), ("api", &["db"]), ("db", &["api"])]);
+        let err = start_order(&services, &project, &[0]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Dependency cycle in project app: api → db → api"
+        );
+
+        let (services, project) = sample(&[("web", &["web"])]);
+        assert!(start_order(&services, &project, &[0]).is_err());
+    }
+
+    #[test]
+    fn start_order_refuses_disabled_dependencies() {
+        let (mut services, _) = sample(&[("web", &["db", "debug"]), ("db", &[]), ("debug", &[])]);
+        services[2].profiles = vec!["debug".to_string(), "dev".to_string()];
+        let project = group(&services, |s| s.profiles.is_empty()).remove(0);
+        let err = start_order(&services, &project, &[0]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "web depends on debug, which is only enabled by profile debug or dev in project app"
+        );
+        let order = start_order(&services, &project, &[1]).unwrap();
+        assert_eq!(names(&services, &order), ["db"]);
+    }
+
+    #[test]
+    fn dependents_are_transitive() {
+        let (services, project) = sample(&[
+            ("web", &["api"]),
+            ("api", &["db"]),
+            ("db", &[]),
+            ("worker", &["db"]),
+            ("docs", &[]),
+        ]);
+        let mut found = names(&services, &dependents(&services, &project, 2));
+        found.sort();
+        assert_eq!(found, ["api", "web", "worker"]);
+        assert!(dependents(&services, &project, 0).is_empty());
+    }
+}
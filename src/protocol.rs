@@ -0,0 +1,182 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+/// Envelope version this build understands. Must match `protocol.Version`
+/// in runner/cmd/protocol/protocol.go.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/*
+ * Messages produced by lifecycle operations (create, start, stop, rm, rmi)
+ *
+ * Each runner stdout line is one envelope `{"v": 1, "type": ..., ...}`.
+ * Backends turn whatever they do into this stream and always finish it
+ * with `Done`, so the UI has a single place that consumes job output.
+ * */
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Progress {
+        stage: Stage,
+        #[serde(default)]
+        image: String,
+        #[serde(default)]
+        detail: String,
+    },
+    Log {
+        line: String,
+    },
+    Result {
+        exit_code: i32,
+        #[serde(default)]
+        status: String,
+        #[serde(default)]
+        id: String,
+    },
+    Error {
+        message: String,
+        #[serde(default)]
+        cause: String,
+    },
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Pulling,
+    Downloading,
+    Exists,
+    Building,
+    Completed,
+    Creating,
+    Starting,
+    Stopping,
+    Removing,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    v: u32,
+    #[serde(flatten)]
+    message: Message,
+}
+
+/*
+ * Replies of queries (list, images, search, read, stream)
+ *
+ * One `data` envelope per item, or an `error` one when the query fails,
+ * which also exits non-zero. Only stream finishes with `done`; the other
+ * replies end with the process.
+ * */
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Reply<T> {
+    Data {
+        data: T,
+    },
+    Error {
+        message: String,
+        #[serde(default)]
+        cause: String,
+    },
+    Done,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    v: u32,
+}
+
+/// Decodes one line of a query's reply into its item, none for `done`. An
+/// `error` envelope, another protocol version or anything unreadable is an
+/// error.
+pub fn decode_data<T: DeserializeOwned>(line: &str) -> Result<Option<T>> {
+    let version = serde_json::from_str::<Version>(line)
+        .map_err(|e| anyhow!("unreadable runner output: {}: {}", e, line))?;
+    if version.v != PROTOCOL_VERSION {
+        bail!(
+            "runner speaks protocol v{}, expected v{}",
+            version.v,
+            PROTOCOL_VERSION
+        );
+    }
+    match serde_json::from_str::<Reply<T>>(line) {
+        Ok(Reply::Data { data }) => Ok(Some(data)),
+        Ok(Reply::Done) => Ok(None),
+        Ok(Reply::Error { message, cause }) if cause.is_empty() => Err(anyhow!(message)),
+        Ok(Reply::Error { message, cause }) => Err(anyhow!("{}: {}", message, cause)),
+        Err(e) => Err(anyhow!("unreadable runner output: {}: {}", e, line)),
+    }
+}
+
+impl Message {
+    /// Decodes one runner line. Anything that is not a valid envelope for
+    /// this protocol version comes back as an `Error` describing why.
+    pub fn decode(line: &str) -> Message {
+        match serde_json::from_str::<Envelope>(line) {
+            Ok(envelope) if envelope.v == PROTOCOL_VERSION => envelope.message,
+            Ok(envelope) => Message::Error {
+                message: format!(
+                    "runner speaks protocol v{}, expected v{}",
+                    envelope.v, PROTOCOL_VERSION
+                ),
+                cause: line.to_string(),
+            },
+            Err(e) => Message::Error {
+                message: "unreadable runner output".to_string(),
+                cause: format!("{}: {}", e, line),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        id: String,
+    }
+
+    #[test]
+    fn decode_data_cases() {
+        let item = decode_data::<Item>(r#"{"v":1,"type":"data","data":{"id":"abc"}}"#);
+        assert_eq!(
+            item.unwrap(),
+            Some(Item {
+                id: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            decode_data::<Item>(r#"{"v":1,"type":"done"}"#).unwrap(),
+            None
+        );
+
+        let errors = [
+            (
+                r#"{"v":1,"type":"error","message":"failed to list containers","cause":"EOF"}"#,
+                "failed to list containers: EOF",
+            ),
+            (
+                r#"{"v":2,"type":"data","data":{"id":"abc"}}"#,
+                "runner speaks protocol v2, expected v1",
+            ),
+            (
+                r#"{"id":"abc"}"#,
+                "unreadable runner output: missing field `v` at line 1 column 12: {\"id\":\"abc\"}",
+            ),
+        ];
+        for (line, expected) in errors {
+            let err = decode_data::<Item>(line).unwrap_err();
+            assert_eq!(format!("{:#}", err), expected);
+        }
+        assert!(decode_data::<Item>(r#"{"v":1,"type":"data","data":{"name":"abc"}}"#).is_err());
+    }
+}
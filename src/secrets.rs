@@ -0,0 +1,251 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use crate::config::Secrets;
+
+/// What a secret value is shown as.
+pub const MASK: &str = "••••••";
+
+/// Secret values shorter than this are only masked after their key; as
+/// free text they are too likely to be ordinary words or numbers.
+const MIN_VALUE_LEN: usize = 6;
+
+/*
+ * Secret masking
+ *
+ * A variable is secret when its key contains one of the configured
+ * patterns, ignoring case (`DB_PASSWORD`, `api_token`). Its value is
+ * replaced by `MASK` wherever it is shown: `KEY=VALUE` entries, and in
+ * free text both `KEY=VALUE` spelled out and the values themselves, which
+ * is how they turn up in runner output and errors.
+ * */
+#[derive(Debug, Clone)]
+pub struct Masker {
+    patterns: Vec<String>,
+    // Known secret values, longest first so a value containing another
+    // is masked whole.
+    values: Vec<String>,
+}
+
+impl Default for Masker {
+    fn default() -> Self {
+        Self::new(&Secrets::default())
+    }
+}
+
+impl Masker {
+    pub fn new(secrets: &Secrets) -> Self {
+        Self {
+            patterns: secrets
+                .patterns
+                .iter()
+                .filter(|p| !p.is_empty())
+                .map(|p| p.to_uppercase())
+                .collect(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_uppercase();
+        self.patterns.iter().any(|p| key.contains(p.as_str()))
+    }
+
+    /// Remembers the values of the secret `KEY=VALUE` entries among
+    /// `entries`, to be masked in free text.
+    pub fn set_values<'a>(&mut self, entries: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        let mut values: Vec<String> = entries
+            .into_iter()
+            .filter(|(key, value)| value.len() >= MIN_VALUE_LEN && self.is_secret(key))
+            .map(|(_, value)| value.to_string())
+            .collect();
+        values.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        values.dedup();
+        self.values = values;
+    }
+
+    /// `entry` (`KEY=VALUE`, optionally `export KEY=VALUE`) with the value
+    /// masked when the key is secret. Anything else is returned as is.
+    pub fn entry(&self, entry: &str) -> String {
+        let Some((before, value)) = entry.split_once('=') else {
+            return entry.to_string();
+        };
+        let key = before.trim();
+        let key = key.strip_prefix("export ").unwrap_or(key).trim();
+        if key.starts_with('#') || value.is_empty() || !self.is_secret(key) {
+            return entry.to_string();
+        }
+        format!("{}={}", before, MASK)
+    }
+
+    /// A line of a compose or .env file, or of a diff of one, with the
+    /// value masked when it sets a secret: `KEY=value`, `- KEY=value` or
+    /// `KEY: value`. The free-text masking of `text` applies as well.
+    pub fn line(&self, line: &str) -> String {
+        let Some(sep) = line.find(['=', ':']) else {
+            return self.text(line);
+        };
+        let (before, value) = (&line[..sep], line[sep + 1..].trim());
+        let key_start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let key = &before[key_start..];
+        let comment = before.trim_start_matches(['+', '-', ' ']).starts_with('#');
+        if comment || value.is_empty() || value == MASK || !self.is_secret(key) {
+            return self.text(line);
+        }
+        // Quotes around the value, or closing a quoted entry, stay.
+        let quoted = |c: Option<char>| c.filter(|c| matches!(c, '"' | '\'')).map(String::from);
+        let open = quoted(value.chars().next()).filter(|_| value.len() > 1);
+        let close = quoted(value.chars().last());
+        let space = if &line[sep..=sep] == ":" { " " } else { "" };
+        format!(
+            "{}{}{}{}{}{}",
+            before,
+            &line[sep..=sep],
+            space,
+            open.unwrap_or_default(),
+            MASK,
+            close.unwrap_or_default()
+        )
+    }
+
+    /// `text` with every secret it contains masked.
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for value in &self.values {
+            if text.contains(value.as_str()) {
+                text = text.replace(value.as_str(), MASK);
+            }
+        }
+
+        let mut masked = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(eq) = rest.find('=') {
+            let before = &rest[..eq];
+            let after = &rest[eq + 1..];
+            masked.push_str(before);
+            masked.push('=');
+
+            let key_start = before
+                .char_indices()
+                .rev()
+                .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_'))
+                .map_or(0, |(i, c)| i + c.len_utf8());
+            let key = &before[key_start..];
+            // A quoted value runs to its closing quote, which is kept.
+            let quote = after.chars().next().filter(|c| matches!(c, '"' | '\''));
+            let unquoted = if quote.is_some() { &after[1..] } else { after };
+            let end = match quote {
+                Some(q) => unquoted.find(q),
+                None => unquoted.find(|c: char| {
+                    c.is_whitespace() || matches!(c, ',' | ';' | '"' | '\'' | ')' | ']')
+                }),
+            }
+            .unwrap_or(unquoted.len());
+            let value = &unquoted[..end];
+
+            if !key.is_empty() && !value.is_empty() && value != MASK && self.is_secret(key) {
+                masked.push_str(&after[..after.len() - unquoted.len()]);
+                masked.push_str(MASK);
+                rest = &unquoted[end..];
+            } else {
+                rest = after;
+            }
+        }
+        masked.push_str(rest);
+        masked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masker() -> Masker {
+        let mut masker = Masker::default();
+        masker.set_values([
+            ("DB_PASSWORD", "hunter22secret"),
+            ("API_TOKEN", "hunter22"),
+            ("HOST", "db.internal"),
+            ("SHORT_KEY", "abc"),
+        ]);
+        masker
+    }
+
+    #[test]
+    fn entry_cases() {
+        let masker = masker();
+        // (entry, expected)
+        let cases = [
+            ("DB_PASSWORD=hunter22secret", "DB_PASSWORD=••••••"),
+            ("export API_TOKEN=hunter22", "export API_TOKEN=••••••"),
+            ("db_password=x", "db_password=••••••"),
+            ("HOST=db.internal", "HOST=db.internal"),
+            ("#DB_PASSWORD=x", "#DB_PASSWORD=x"),
+            ("DB_PASSWORD=", "DB_PASSWORD="),
+            ("DB_PASSWORD", "DB_PASSWORD"),
+        ];
+        for (entry, expected) in cases {
+            assert_eq!(masker.entry(entry), expected, "{}", entry);
+        }
+    }
+
+    #[test]
+    fn line_cases() {
+        let masker = masker();
+        // (line, expected)
+        let cases = [
+            ("DB_PASSWORD=x", "DB_PASSWORD=••••••"),
+            ("export DB_PASSWORD=x", "export DB_PASSWORD=••••••"),
+            ("DB_PASSWORD=\"a b\"", "DB_PASSWORD=\"••••••\""),
+            ("DB_PASSWORD='a b'", "DB_PASSWORD='••••••'"),
+            ("      API_TOKEN: x", "      API_TOKEN: ••••••"),
+            ("      API_TOKEN: \"x y\"", "      API_TOKEN: \"••••••\""),
+            ("      - DB_PASSWORD=x", "      - DB_PASSWORD=••••••"),
+            ("+DB_PASSWORD=x", "+DB_PASSWORD=••••••"),
+            ("-DB_PASSWORD=x", "-DB_PASSWORD=••••••"),
+            // A quoted list entry keeps its quotes.
+            ("      - \"SHORT_KEY=x\"", "      - \"SHORT_KEY=••••••\""),
+            // Comments are not entries, but spelled out secrets in them are.
+            ("# DB_PASSWORD=x", "# DB_PASSWORD=••••••"),
+            ("+# note: hunter22 rotated", "+# note: •••••• rotated"),
+            ("    image: nginx", "    image: nginx"),
+            ("HOST=db.internal", "HOST=db.internal"),
+            ("DB_PASSWORD=••••••", "DB_PASSWORD=••••••"),
+            ("      API_TOKEN:", "      API_TOKEN:"),
+            ("no separator hunter22secret", "no separator ••••••"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(masker.line(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn text_cases() {
+        let masker = masker();
+        // (text, expected)
+        let cases = [
+            ("auth failed for hunter22secret", "auth failed for ••••••"),
+            ("auth failed for hunter22", "auth failed for ••••••"),
+            // Too short to look for on its own, but masked after its key.
+            ("abc is fine", "abc is fine"),
+            ("SHORT_KEY=abc rejected", "SHORT_KEY=•••••• rejected"),
+            (
+                "run -e API_TOKEN='a b' -e HOST=db",
+                "run -e API_TOKEN='••••••' -e HOST=db",
+            ),
+            (
+                "env: [OTHER_SECRET=zz, X=1]",
+                "env: [OTHER_SECRET=••••••, X=1]",
+            ),
+            ("DB_PASSWORD=•••••• again", "DB_PASSWORD=•••••• again"),
+            ("no secrets here", "no secrets here"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(masker.text(text), expected, "{}", text);
+        }
+    }
+}
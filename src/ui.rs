@@ -5,10 +5,19 @@ use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Sparkline, Table},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table,
+        Wrap,
+    },
 };
 
-use crate::app::{App, Tab};
+use crate::app::{App, EnvTarget, Tab};
+use crate::form::FIELDS;
+use crate::jobs::JobStatus;
+use crate::lint::Severity;
+use crate::ports;
+use crate::project::{self, ContainerRow};
 
 fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -27,9 +36,15 @@ fn format_bytes(bytes: u64) -> String {
 }
 
 pub fn draw_ui(f: &mut Frame, app: &mut App) {
+    if let Some(err) = &app.runner_error {
+        draw_runner_error(f, err);
+        return;
+    }
+
     let root = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
+            Constraint::Length(1),
             Constraint::Min(1),
             Constraint::Length(3),
             Constraint::Length(2),
@@ -42,13 +57,14 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
             Constraint::Length(36),
             Constraint::Fill(1),
         ])
-        .split(root[0]);
+        .split(root[1]);
 
     let left = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(34),
-            Constraint::Percentage(33),
+            Constraint::Percentage(28),
+            Constraint::Percentage(24),
+            Constraint::Percentage(24),
             Constraint::Fill(1),
         ])
         .split(content[0]);
@@ -56,13 +72,86 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
     draw_left_containers(f, left[0], app);
     draw_left_images(f, left[1], app);
     draw_left_running(f, left[2], app);
+    draw_left_jobs(f, left[3], app);
     draw_right_panel(f, content[1], app);
-    draw_logs(f, root[1], app);
-    draw_keybindings(f, root[2], app);
+    draw_header(f, root[0], app);
+    draw_logs(f, root[2], app);
+    draw_keybindings(f, root[3], app);
 
     if app.env_editor_open {
         draw_env_editor(f, app);
     }
+    if app.form.is_some() {
+        draw_form(f, app);
+    }
+    if !app.save_preview.is_empty() {
+        draw_save_preview(f, app);
+    }
+    if app.port_conflicts_open {
+        draw_port_conflicts(f, app);
+    }
+    if app.lint_open {
+        draw_lint(f, app);
+    }
+    if app.workspace_open {
+        draw_workspace(f, app);
+    }
+    if app.profiles_open {
+        draw_profiles(f, app);
+    }
+    if app.context_picker_open {
+        draw_context_picker(f, app);
+    }
+}
+
+fn draw_header(f: &mut Frame, area: Rect, app: &App) {
+    let text = format!(
+        " easydocker  context: {}  {} @ {}",
+        app.target.context,
+        app.target.runtime,
+        app.target.endpoint.host()
+    );
+    f.render_widget(
+        Paragraph::new(text).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        area,
+    );
+}
+
+fn draw_runner_error(f: &mut Frame, message: &str) {
+    let area = centered_rect(70, 50, f.area());
+
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(" easydocker-runner unavailable ")
+        .border_style(Style::default().fg(Color::Red));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    f.render_widget(
+        Paragraph::new(message.to_string())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false }),
+        chunks[0],
+    );
+    f.render_widget(
+        Paragraph::new(" r: Retry  q: Quit").style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+// Panel title with a spinner while its tab is refreshing in the background.
+fn loading_title(title: &str, loading: bool) -> String {
+    if loading {
+        format!("{} ⏳", title)
+    } else {
+        title.to_string()
+    }
 }
 
 fn active_border(is_active: bool) -> Style {
@@ -79,7 +168,9 @@ fn draw_left_containers(f: &mut Frame, area: Rect, app: &mut App) {
 
     let mut items: Vec<ListItem> = Vec::new();
 
-    if app.loading && is_active {
+    let loading = app.is_loading(Tab::Containers);
+
+    if loading && app.containers.is_empty() {
         items.push(ListItem::new("⏳ Loading..."));
     } else if app.containers.is_empty() {
         items.push(
@@ -87,21 +178,52 @@ fn draw_left_containers(f: &mut Frame, area: Rect, app: &mut App) {
                 .style(Style::default().fg(Color::DarkGray)),
         );
     } else {
-        for (idx, container) in app.containers.iter().enumerate() {
-            let display = format!("🖿 {}", container.name);
-            items.push(
-                ListItem::new(display).style(
-                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-                ),
-            );
+        for (idx, row) in app.container_rows.iter().enumerate() {
+            match *row {
+                ContainerRow::Project(p) => {
+                    let project = &app.projects[p];
+                    let fold = if app.is_collapsed(project) { "▸" } else { "▾" };
+                    let running = app.running_services(project);
+                    let total = project.services.len();
+                    let summary_style = if running == 0 {
+                        Style::default().fg(Color::DarkGray)
+                    } else if running < total {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::Green)
+                    };
+                    items.push(ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{} 🖿 {}", fold, project.name),
+                            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(format!("  {}/{} running", running, total), summary_style),
+                    ])));
+                }
+                ContainerRow::Service(s) => {
+                    let service = &app.containers[s];
+                    let health = app.container_of(service).and_then(|c| c.health_status());
+                    let style = if health == Some("unhealthy") {
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    let mut spans = vec![Span::styled(format!("    {}", service.service), style)];
+                    if !service.profiles.is_empty() {
+                        spans.push(Span::styled(
+                            format!("  [{}]", service.profiles.join(", ")),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                    if let Some(status) = health {
+                        let (icon, health) = health_style(status);
+                        spans.push(Span::styled(format!("  {} {}", icon, status), health));
+                    }
+                    items.push(ListItem::new(Line::from(spans)));
+                }
+            }
             if app.expanded_index == Some(idx) {
-                let menu_items = [
-                    "  Build & Start",
-                    "  Start",
-                    "  Stop",
-                    "  Delete Container",
-                ];
-                for (menu_idx, menu_item) in menu_items.iter().enumerate() {
+                for (menu_idx, (label, _)) in app.menu_items().iter().enumerate() {
                     let style = if menu_idx == app.menu_selection {
                         Style::default()
                             .fg(Color::Yellow)
@@ -109,7 +231,7 @@ fn draw_left_containers(f: &mut Frame, area: Rect, app: &mut App) {
                     } else {
                         Style::default().fg(Color::Gray)
                     };
-                    items.push(ListItem::new(*menu_item).style(style));
+                    items.push(ListItem::new(format!("      {}", label)).style(style));
                 }
             }
         }
@@ -126,7 +248,7 @@ fn draw_left_containers(f: &mut Frame, area: Rect, app: &mut App) {
             Block::default()
                 .border_type(ratatui::widgets::BorderType::Rounded)
                 .borders(Borders::ALL)
-                .title("Containers")
+                .title(loading_title("Containers", loading))
                 .border_style(border_style),
         );
 
@@ -143,7 +265,9 @@ fn draw_left_images(f: &mut Frame, area: Rect, app: &mut App) {
 
     let mut items: Vec<ListItem> = Vec::new();
 
-    if app.loading && is_active {
+    let loading = app.is_loading(Tab::Images);
+
+    if loading && app.images.is_empty() {
         items.push(ListItem::new("⏳ Loading images..."));
     } else if app.images.is_empty() {
         items.push(
@@ -183,7 +307,7 @@ fn draw_left_images(f: &mut Frame, area: Rect, app: &mut App) {
             Block::default()
                 .border_type(ratatui::widgets::BorderType::Rounded)
                 .borders(Borders::ALL)
-                .title("Images")
+                .title(loading_title("Images", loading))
                 .border_style(border_style),
         );
 
@@ -200,7 +324,9 @@ fn draw_left_running(f: &mut Frame, area: Rect, app: &mut App) {
 
     let mut items: Vec<ListItem> = Vec::new();
 
-    if app.loading && is_active {
+    let loading = app.is_loading(Tab::Deployments);
+
+    if loading && app.running_containers.is_empty() {
         items.push(ListItem::new("⏳ Loading..."));
     } else if app.running_containers.is_empty() {
         items.push(
@@ -216,12 +342,19 @@ fn draw_left_running(f: &mut Frame, area: Rect, app: &mut App) {
                 .unwrap_or_else(|| container.id.clone());
             let is_sel = app.selected_container_id.as_ref() == Some(&container.id);
             let prefix = if is_sel { "● " } else { "  " };
-            let style = if is_sel {
+            let style = if container.is_unhealthy() {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else if is_sel {
                 Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(Color::Cyan)
             };
-            items.push(ListItem::new(format!("{}{}", prefix, name)).style(style));
+            let mut spans = vec![Span::styled(format!("{}{}", prefix, name), style)];
+            if let Some(status) = container.health_status() {
+                let (icon, health) = health_style(status);
+                spans.push(Span::styled(format!("  {} {}", icon, status), health));
+            }
+            items.push(ListItem::new(Line::from(spans)));
         }
     }
 
@@ -236,7 +369,7 @@ fn draw_left_running(f: &mut Frame, area: Rect, app: &mut App) {
             Block::default()
                 .border_type(ratatui::widgets::BorderType::Rounded)
                 .borders(Borders::ALL)
-                .title("Running")
+                .title(loading_title("Running", loading))
                 .border_style(border_style),
         );
 
@@ -247,11 +380,122 @@ fn draw_left_running(f: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+fn job_status_style(status: JobStatus) -> (&'static str, Style) {
+    match status {
+        JobStatus::Running   => ("⏳", Style::default().fg(Color::Yellow)),
+        JobStatus::Succeeded => ("✔", Style::default().fg(Color::Green)),
+        JobStatus::Failed    => ("✖", Style::default().fg(Color::Red)),
+        JobStatus::Cancelled => ("⊘", Style::default().fg(Color::DarkGray)),
+    }
+}
+
+fn health_style(status: &str) -> (&'static str, Style) {
+    match status {
+        "healthy"   => ("✔", Style::default().fg(Color::Green)),
+        "unhealthy" => ("✖", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        _           => ("⏳", Style::default().fg(Color::Yellow)),
+    }
+}
+
+fn draw_left_jobs(f: &mut Frame, area: Rect, app: &mut App) {
+    let is_active = app.current_tab == Tab::Jobs;
+    let border_style = active_border(is_active);
+
+    let mut items: Vec<ListItem> = Vec::new();
+
+    if app.jobs.is_empty() {
+        items.push(
+            ListItem::new("(no jobs)")
+                .style(Style::default().fg(Color::DarkGray)),
+        );
+    } else {
+        for job in app.jobs.jobs() {
+            let (icon, style) = job_status_style(job.status);
+            items.push(ListItem::new(format!("{} #{} {}", icon, job.id, job.label)).style(style));
+        }
+    }
+
+    let running = app.jobs.jobs().iter().filter(|j| j.is_running()).count();
+    let title = if running > 0 {
+        format!("Jobs ({} running)", running)
+    } else {
+        "Jobs".to_string()
+    };
+
+    let list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("→ ")
+        .block(
+            Block::default()
+                .border_type(ratatui::widgets::BorderType::Rounded)
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(border_style),
+        );
+
+    if is_active {
+        f.render_stateful_widget(list, area, &mut app.job_state);
+    } else {
+        f.render_widget(list, area);
+    }
+}
+
+fn draw_job_detail(f: &mut Frame, area: Rect, app: &mut App) {
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title("Job Output")
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(job) = app.job_state.selected().and_then(|idx| app.jobs.jobs().get(idx)) else {
+        f.render_widget(
+            Paragraph::new("Select a job on the left.")
+                .style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(inner);
+
+    let (icon, style) = job_status_style(job.status);
+    let rows = vec![
+        Row::new(vec![Cell::from("Job"),      Cell::from(":"), Cell::from(format!("#{} {}", job.id, job.label))]),
+        Row::new(vec![Cell::from("Status"),   Cell::from(":"), Cell::from(format!("{} {:?}", icon, job.status)).style(style)]),
+        Row::new(vec![Cell::from("Progress"), Cell::from(":"), Cell::from(job.progress.clone())]),
+    ];
+    let table = Table::new(rows, &[
+        Constraint::Length(12),
+        Constraint::Length(2),
+        Constraint::Fill(1),
+    ]);
+    f.render_widget(table, chunks[0]);
+
+    // Follow the tail of the output.
+    let height = chunks[1].height as usize;
+    let start = job.log.len().saturating_sub(height);
+    f.render_widget(
+        Paragraph::new(app.masker.text(&job.log[start..].join("\n")))
+            .block(Block::default().borders(Borders::TOP)),
+        chunks[1],
+    );
+}
+
 fn draw_right_panel(f: &mut Frame, area: Rect, app: &mut App) {
     match app.current_tab {
         Tab::Containers => draw_container_detail(f, area, app),
         Tab::Images => draw_image_detail(f, area, app),
         Tab::Deployments => draw_analytics(f, area, app),
+        Tab::Jobs => draw_job_detail(f, area, app),
     }
 }
 
@@ -264,35 +508,147 @@ fn draw_container_detail(f: &mut Frame, area: Rect, app: &mut App) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.loading {
+    if app.is_loading(Tab::Containers) && app.containers.is_empty() {
         f.render_widget(Paragraph::new("⏳ Loading..."), inner);
         return;
     }
 
-    if let Some(idx) = app.container_idx {
-        if let Some(ctn) = app.containers.get(idx) {
-            let rows = vec![
-                Row::new(vec![Cell::from("Name"),       Cell::from(":"), Cell::from(ctn.name.clone())]),
-                Row::new(vec![Cell::from("Service"),    Cell::from(":"), Cell::from(ctn.service.clone())]),
-                Row::new(vec![Cell::from("Container"),  Cell::from(":"), Cell::from(ctn.container_name.clone())]),
-                Row::new(vec![Cell::from("Hostname"),   Cell::from(":"), Cell::from(ctn.hostname.clone())]),
-                Row::new(vec![Cell::from("Image"),      Cell::from(":"), Cell::from(ctn.image.clone())]),
-                Row::new(vec![Cell::from("Port"),       Cell::from(":"), Cell::from(ctn.ports.clone())]),
-                Row::new(vec![Cell::from("Build Ctx"),  Cell::from(":"), Cell::from(ctn.build_context.clone())]),
-                Row::new(vec![Cell::from("Dockerfile"), Cell::from(":"), Cell::from(ctn.dockerfile.clone())]),
-                Row::new(vec![Cell::from("Env"),        Cell::from(":"), Cell::from(ctn.environment.join(", "))]),
-                Row::new(vec![Cell::from("Volumes"),    Cell::from(":"), Cell::from(ctn.volumes.join(", "))]),
-                Row::new(vec![Cell::from("Networks"),   Cell::from(":"), Cell::from(ctn.networks.join(", "))]),
-                Row::new(vec![Cell::from("Restart"),    Cell::from(":"), Cell::from(ctn.restart.clone())]),
-            ];
-            let table = Table::new(rows, &[
-                Constraint::Length(12),
-                Constraint::Length(2),
-                Constraint::Fill(1),
-            ]);
-            f.render_widget(table, inner);
-            return;
+    if let Some(ContainerRow::Project(p)) = app.selected_row()
+        && let Some(project) = app.projects.get(p)
+    {
+        let services: Vec<&str> = project
+            .services
+            .iter()
+            .filter_map(|&s| app.containers.get(s))
+            .map(|s| s.service.as_str())
+            .collect();
+        let files = project
+            .services
+            .first()
+            .and_then(|&s| app.containers.get(s))
+            .map(|s| s.files.join(", "))
+            .unwrap_or_default();
+        let running = format!("{}/{}", app.running_services(project), project.services.len());
+        let mut services = services.join(", ");
+        if !project.disabled.is_empty() {
+            services.push_str(&format!("  (+{} in inactive profiles)", project.disabled.len()));
         }
+        let active = app.active_profiles(&project.dir);
+        let profiles: Vec<String> = project::profiles(&app.containers, project)
+            .into_iter()
+            .map(|p| if active.contains(&p) { format!("● {}", p) } else { format!("○ {}", p) })
+            .collect();
+        let rows = vec![
+            Row::new(vec![Cell::from("Project"),    Cell::from(":"), Cell::from(project.name.clone())]),
+            Row::new(vec![Cell::from("Directory"),  Cell::from(":"), Cell::from(project.dir.clone())]),
+            Row::new(vec![Cell::from("Services"),   Cell::from(":"), Cell::from(services)]),
+            Row::new(vec![Cell::from("Profiles"),   Cell::from(":"), Cell::from(profiles.join("  "))]),
+            Row::new(vec![Cell::from("Running"),    Cell::from(":"), Cell::from(running)]),
+            Row::new(vec![Cell::from("Files"),      Cell::from(":"), Cell::from(files)]),
+        ];
+        let table = Table::new(rows, &[
+            Constraint::Length(12),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ]);
+        f.render_widget(table, inner);
+        return;
+    }
+
+    if let Some(idx) = app.container_idx
+        && let Some(ctn) = app.containers.get(idx)
+    {
+        let depends_on: Vec<String> = ctn
+            .depends_on
+            .iter()
+            .map(|dep| match dep.condition.as_str() {
+                "" | "service_started" => dep.service.clone(),
+                condition => format!("{} ({})", dep.service, condition),
+            })
+            .collect();
+        let depends_on = depends_on.join(", ");
+
+        // Raw values next to what they interpolate to. Variables coming
+        // from env_file only exist in the runner's list.
+        let image = if ctn.raw.image.is_empty() {
+            ctn.image.clone()
+        } else {
+            ctn.interpolated(&ctn.raw.image)
+        };
+        let ports = if ctn.raw.ports.is_empty() {
+            ctn.ports.clone()
+        } else {
+            ctn.raw.ports.iter().map(|p| ctn.interpolated(p)).collect::<Vec<_>>().join(", ")
+        };
+        let env_key = |e: &String| e.split('=').next().unwrap_or_default().to_string();
+        let raw_keys: Vec<String> = ctn.raw.environment.iter().map(env_key).collect();
+        // Secret values stay masked here, also where a variable puts one
+        // into another entry; the env editor can reveal them.
+        let env: Vec<String> = ctn
+            .raw
+            .environment
+            .iter()
+            .map(|e| app.masker.text(&ctn.interpolated(&app.masker.entry(e))))
+            .chain(
+                ctn.environment
+                    .iter()
+                    .filter(|e| !raw_keys.contains(&env_key(e)))
+                    .map(|e| app.masker.entry(e)),
+            )
+            .collect();
+
+        let healthcheck = ctn.healthcheck.as_ref().map(|h| h.describe()).unwrap_or_default();
+        let running = app.container_of(ctn);
+        let health = match running.and_then(|c| c.health_status()) {
+            Some(status) => {
+                let (icon, style) = health_style(status);
+                let streak = running
+                    .and_then(|c| c.health.as_ref())
+                    .filter(|h| h.failing_streak > 0)
+                    .map(|h| format!(" ({} failed in a row)", h.failing_streak))
+                    .unwrap_or_default();
+                Span::styled(format!("{} {}{}", icon, status, streak), style)
+            }
+            None if running.is_some() => Span::raw("no healthcheck"),
+            None => Span::styled("not running", Style::default().fg(Color::DarkGray)),
+        };
+        // Probes often print several lines; the table shows one.
+        let probe = running
+            .and_then(|c| c.health.as_ref())
+            .filter(|h| !h.output.is_empty())
+            .map(|h| {
+                let output: Vec<&str> = h.output.split_whitespace().collect();
+                app.masker.text(&format!("exit {}: {}", h.exit_code, output.join(" ")))
+            })
+            .unwrap_or_default();
+
+        let rows = vec![
+            Row::new(vec![Cell::from("Name"),       Cell::from(":"), Cell::from(ctn.name.clone())]),
+            Row::new(vec![Cell::from("Service"),    Cell::from(":"), Cell::from(ctn.service.clone())]),
+            Row::new(vec![Cell::from("Profiles"),   Cell::from(":"), Cell::from(ctn.profiles.join(", "))]),
+            Row::new(vec![Cell::from("Container"),  Cell::from(":"), Cell::from(ctn.container_name.clone())]),
+            Row::new(vec![Cell::from("Hostname"),   Cell::from(":"), Cell::from(ctn.hostname.clone())]),
+            Row::new(vec![Cell::from("Image"),      Cell::from(":"), Cell::from(image)]),
+            Row::new(vec![Cell::from("Port"),       Cell::from(":"), Cell::from(ports)]),
+            Row::new(vec![Cell::from("Build Ctx"),  Cell::from(":"), Cell::from(ctn.build_context.clone())]),
+            Row::new(vec![Cell::from("Dockerfile"), Cell::from(":"), Cell::from(ctn.dockerfile.clone())]),
+            Row::new(vec![Cell::from("Env"),        Cell::from(":"), Cell::from(env.join(", "))]),
+            Row::new(vec![Cell::from("Volumes"),    Cell::from(":"), Cell::from(ctn.volumes.join(", "))]),
+            Row::new(vec![Cell::from("Networks"),   Cell::from(":"), Cell::from(ctn.networks.join(", "))]),
+            Row::new(vec![Cell::from("Restart"),    Cell::from(":"), Cell::from(ctn.restart.clone())]),
+            Row::new(vec![Cell::from("Check"),      Cell::from(":"), Cell::from(healthcheck)]),
+            Row::new(vec![Cell::from("Health"),     Cell::from(":"), Cell::from(health)]),
+            Row::new(vec![Cell::from("Last Probe"), Cell::from(":"), Cell::from(probe)]),
+            Row::new(vec![Cell::from("Depends On"), Cell::from(":"), Cell::from(depends_on)]),
+            Row::new(vec![Cell::from("Files"),      Cell::from(":"), Cell::from(ctn.files.join(", "))]),
+        ];
+        let table = Table::new(rows, &[
+            Constraint::Length(12),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ]);
+        f.render_widget(table, inner);
+        return;
     }
 
     f.render_widget(
@@ -311,28 +667,28 @@ fn draw_image_detail(f: &mut Frame, area: Rect, app: &mut App) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.loading {
+    if app.is_loading(Tab::Images) && app.images.is_empty() {
         f.render_widget(Paragraph::new("⏳ Loading..."), inner);
         return;
     }
 
-    if let Some(idx) = app.image_idx {
-        if let Some(image) = app.images.get(idx) {
-            let rows = vec![
-                Row::new(vec![Cell::from("Repository"), Cell::from(":"), Cell::from(image.repository.clone())]),
-                Row::new(vec![Cell::from("Tag"),        Cell::from(":"), Cell::from(image.tag.clone())]),
-                Row::new(vec![Cell::from("Image ID"),   Cell::from(":"), Cell::from(image.image_id.clone())]),
-                Row::new(vec![Cell::from("Created"),    Cell::from(":"), Cell::from(image.created.clone())]),
-                Row::new(vec![Cell::from("Size"),       Cell::from(":"), Cell::from(image.size.clone())]),
-            ];
-            let table = Table::new(rows, &[
-                Constraint::Length(12),
-                Constraint::Length(2),
-                Constraint::Fill(1),
-            ]);
-            f.render_widget(table, inner);
-            return;
-        }
+    if let Some(idx) = app.image_idx
+        && let Some(image) = app.images.get(idx)
+    {
+        let rows = vec![
+            Row::new(vec![Cell::from("Repository"), Cell::from(":"), Cell::from(image.repository.clone())]),
+            Row::new(vec![Cell::from("Tag"),        Cell::from(":"), Cell::from(image.tag.clone())]),
+            Row::new(vec![Cell::from("Image ID"),   Cell::from(":"), Cell::from(image.image_id.clone())]),
+            Row::new(vec![Cell::from("Created"),    Cell::from(":"), Cell::from(image.created.clone())]),
+            Row::new(vec![Cell::from("Size"),       Cell::from(":"), Cell::from(image.size.clone())]),
+        ];
+        let table = Table::new(rows, &[
+            Constraint::Length(12),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ]);
+        f.render_widget(table, inner);
+        return;
     }
 
     f.render_widget(
@@ -481,11 +837,15 @@ fn draw_logs(f: &mut Frame, area: Rect, app: &mut App) {
 }
 
 fn draw_keybindings(f: &mut Frame, area: Rect, app: &App) {
-    let text = if app.current_tab == Tab::Containers && !app.env_editor_open {
-        " q: Quit  r: Refresh  Tab: Switch  ↑↓: Navigate  Enter: Menu  e: Edit Env  Esc: Close"
+    let mut text = if app.current_tab == Tab::Containers && !app.env_editor_open {
+        " q: Quit  r: Refresh  Tab: Switch  ↑↓: Navigate  Enter: Menu  Space: Fold  e: Edit Env  E: .env  f: Form  w: Roots  P: Profiles  p: Ports  L: Lint  x: Context  Esc: Close"
     } else {
-        " q: Quit  r: Refresh  Tab/Shift+Tab: Switch Panel  ↑↓: Navigate  Enter: Menu  Esc: Close"
-    };
+        " q: Quit  r: Refresh  Tab/Shift+Tab: Switch Panel  ↑↓: Navigate  Enter: Menu  L: Lint  x: Context  Esc: Close"
+    }
+    .to_string();
+    if app.jobs.any_running() {
+        text.push_str("  c: Cancel");
+    }
     f.render_widget(
         Paragraph::new(text)
             .style(Style::default().fg(Color::DarkGray))
@@ -513,14 +873,102 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+fn draw_form(f: &mut Frame, app: &App) {
+    let Some(form) = &app.form else {
+        return;
+    };
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let service = app
+        .containers
+        .get(form.service)
+        .map(|c| c.service.as_str())
+        .unwrap_or_default();
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(format!(" Edit Service {} ", service))
+        .border_style(Style::default().fg(Color::Green));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    // The label only on a field's first row, so list entries line up
+    // under it.
+    let items: Vec<ListItem> = form
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (field, item))| {
+            let is_sel = i == form.selected;
+            let is_editing = is_sel && form.editing;
+            let label = if item.unwrap_or(0) == 0 { FIELDS[field].label } else { "" };
+
+            let value = if is_editing {
+                format!("{}▌", form.buffer)
+            } else {
+                match item {
+                    Some(i) if FIELDS[field].key == "environment" && !form.is_revealed(field, i) => {
+                        app.masker.entry(&form.values[field][i])
+                    }
+                    Some(i) => form.values[field][i].clone(),
+                    None => "(none)".to_string(),
+                }
+            };
+            let error = item.and_then(|i| form.error_at(field, i));
+
+            let style = if is_editing {
+                Style::default().fg(Color::Black).bg(Color::Green)
+            } else if is_sel {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if item.is_none() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            let mut spans = vec![
+                Span::styled(format!("{:<11}", label), Style::default().fg(Color::Cyan)),
+                Span::styled(value, style),
+            ];
+            if let Some(error) = error {
+                spans.push(Span::styled(format!("  ✗ {}", error), Style::default().fg(Color::Red)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(form.selected));
+    f.render_stateful_widget(List::new(items), chunks[0], &mut state);
+
+    let hint = if form.editing {
+        " Enter: confirm  Esc: cancel edit"
+    } else {
+        " Enter: edit  a: add  d/x: delete  v: reveal  s: review & save  Esc: close without saving"
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
 fn draw_env_editor(f: &mut Frame, app: &App) {
     let area = centered_rect(60, 70, f.area());
     f.render_widget(Clear, area);
 
+    let title = match &app.env_editor_target {
+        EnvTarget::Service => " Edit Environment Variables ".to_string(),
+        EnvTarget::DotEnv(path) => format!(" Edit {} ", path.display()),
+    };
     let block = Block::default()
         .border_type(ratatui::widgets::BorderType::Rounded)
         .borders(Borders::ALL)
-        .title(" Edit Environment Variables ")
+        .title(title)
         .border_style(Style::default().fg(Color::Green));
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -542,8 +990,10 @@ fn draw_env_editor(f: &mut Frame, app: &App) {
                 format!("{}▌", app.env_editor_buffer)
             } else if line.is_empty() {
                 "(empty)".to_string()
-            } else {
+            } else if app.env_editor_revealed.contains(&i) {
                 line.clone()
+            } else {
+                app.masker.entry(line)
             };
 
             let style = if is_sel {
@@ -565,10 +1015,329 @@ fn draw_env_editor(f: &mut Frame, app: &App) {
     let hint = if app.env_editor_editing {
         " Enter: confirm  Esc: cancel edit"
     } else {
-        " Enter: edit  a: add  d/x: delete  s: save & close  Esc: close without saving"
+        " Enter: edit  a: add  d/x: delete  v: reveal  s: review & save  Esc: close without saving"
     };
     f.render_widget(
         Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
         chunks[1],
     );
 }
+
+fn draw_save_preview(f: &mut Frame, app: &App) {
+    let title = match app.save_preview.as_slice() {
+        [edit] => format!(" Save to {} ", edit.path.display()),
+        edits => format!(" Save {} files ", edits.len()),
+    };
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let lines: Vec<Line> = app
+        .save_preview
+        .iter()
+        .flat_map(|edit| &edit.diff)
+        .map(|line| {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else if line.starts_with("@@") {
+                Style::default().fg(Color::Cyan)
+            } else if line.starts_with('+') {
+                Style::default().fg(Color::Green)
+            } else if line.starts_with('-') {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            Line::styled(app.masker.line(line), style)
+        })
+        .collect();
+    f.render_widget(
+        Paragraph::new(lines).scroll((app.save_preview_scroll, 0)),
+        chunks[0],
+    );
+
+    f.render_widget(
+        Paragraph::new(" y/Enter: write (keeps a .bak copy)  n/Esc: back to editor  ↑↓: scroll")
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn draw_context_picker(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(" Docker Contexts ")
+        .border_style(Style::default().fg(Color::Green));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let items: Vec<ListItem> = app
+        .contexts
+        .iter()
+        .enumerate()
+        .map(|(i, ctx)| {
+            let active = if ctx.name == app.target.context { "● " } else { "  " };
+            let host = if ctx.host.is_empty() { ctx.description.as_str() } else { ctx.host.as_str() };
+            let text = format!("{}{}  {}", active, ctx.name, host);
+
+            let style = if i == app.context_selected {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(text).style(style)
+        })
+        .collect();
+
+    f.render_widget(List::new(items), chunks[0]);
+    f.render_widget(
+        Paragraph::new(" Enter: switch  Esc: close").style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn draw_port_conflicts(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(" Port Conflicts ")
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    if app.port_conflicts.is_empty() {
+        f.render_widget(
+            Paragraph::new("No host port is claimed twice.").style(Style::default().fg(Color::Green)),
+            chunks[0],
+        );
+    } else {
+        // Red when the port is in use, so starting the others fails.
+        let items: Vec<ListItem> = app
+            .port_conflicts
+            .iter()
+            .map(|conflict| {
+                let owners: Vec<String> = conflict
+                    .claims
+                    .iter()
+                    .map(|claim| {
+                        let owner = ports::describe(&app.containers, &claim.owner);
+                        if claim.running { format!("{} (running)", owner) } else { owner }
+                    })
+                    .collect();
+                let color = if conflict.taken() { Color::Red } else { Color::Yellow };
+                ListItem::new(format!("{:>5}  {}", conflict.port, owners.join(", ")))
+                    .style(Style::default().fg(color))
+            })
+            .collect();
+        f.render_widget(List::new(items), chunks[0]);
+    }
+
+    f.render_widget(
+        Paragraph::new(" Esc: close").style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn draw_lint(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(80, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(format!(" Lint ({} problem(s)) ", app.lint_problems.len()))
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    if app.lint_problems.is_empty() {
+        f.render_widget(
+            Paragraph::new("No problems found.").style(Style::default().fg(Color::Green)),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = app
+            .lint_problems
+            .iter()
+            .map(|problem| {
+                let (label, color) = match problem.severity {
+                    Severity::Error => ("error", Color::Red),
+                    Severity::Warning => ("warn ", Color::Yellow),
+                };
+                let service = &app.containers[problem.service];
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{}  ", label), Style::default().fg(color)),
+                    Span::styled(
+                        format!("{}/{}  ", service.name, service.service),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(problem.message.clone()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+        f.render_stateful_widget(list, chunks[0], &mut app.lint_state);
+    }
+
+    f.render_widget(
+        Paragraph::new(" Enter: go to service  ↑↓: Navigate  Esc: close")
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn draw_profiles(f: &mut Frame, app: &App) {
+    let area = centered_rect(40, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let name = app
+        .projects
+        .iter()
+        .find(|p| p.dir == app.profiles_dir)
+        .map(|p| p.name.as_str())
+        .unwrap_or_default();
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(format!(" Profiles: {} ", name))
+        .border_style(Style::default().fg(Color::Green));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let items: Vec<ListItem> = app
+        .profile_choices()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (profile, active))| {
+            let mark = if active { "[x]" } else { "[ ]" };
+            let style = if i == app.profiles_selected {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if active {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(format!("{} {}", mark, profile)).style(style)
+        })
+        .collect();
+    f.render_widget(List::new(items), chunks[0]);
+
+    f.render_widget(
+        Paragraph::new(" Space: toggle  Esc: close").style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn draw_workspace(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let workspace = &app.config.workspace;
+    let block = Block::default()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(" Workspace Roots ")
+        .border_style(Style::default().fg(Color::Green));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let mut items: Vec<ListItem> = workspace
+        .roots
+        .iter()
+        .enumerate()
+        .map(|(i, root)| {
+            let style = if i == app.workspace_selected && app.workspace_input.is_none() {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(root.clone()).style(style)
+        })
+        .collect();
+    if workspace.roots.is_empty() {
+        items.push(
+            ListItem::new("(home directory)").style(Style::default().fg(Color::DarkGray)),
+        );
+    }
+    if let Some(input) = &app.workspace_input {
+        items.push(
+            ListItem::new(format!("{}▌", input))
+                .style(Style::default().fg(Color::Black).bg(Color::Green)),
+        );
+    }
+    f.render_widget(List::new(items), chunks[0]);
+
+    let depth = match workspace.max_depth {
+        0 => "unlimited".to_string(),
+        depth => depth.to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(format!(" Max depth: {}", depth)).style(Style::default().fg(Color::Gray)),
+        chunks[1],
+    );
+    f.render_widget(
+        Paragraph::new(format!(" Ignored: {}", workspace.ignore.join(", ")))
+            .style(Style::default().fg(Color::Gray)),
+        chunks[2],
+    );
+
+    let hint = if app.workspace_input.is_some() {
+        " Enter: add  Esc: cancel"
+    } else {
+        " a: add  d/x: remove  r: rescan  Esc: close"
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        chunks[3],
+    );
+}
@@ -0,0 +1,416 @@
+// Copyright 2026 Fitrian Musya
+// SPDX-License-Identifier: MIT
+
+use anyhow::Result;
+use tokio::task::JoinHandle;
+
+use crate::config::Workspace;
+use crate::event::EventSender;
+
+/*
+ * Workspace watcher
+ *
+ * Watches the directories a search would walk (the workspace roots down
+ * to `max_depth`, skipping ignored ones) and reports project directories
+ * whose compose files, override files or .env changed, as
+ * `AppEvent::ProjectsChanged`. Changes are collected until things have
+ * been quiet for a moment, since editors save a file in several steps.
+ * Directories created later are watched as they appear; one that is
+ * removed or moved away is reported itself, so the projects below it go.
+ *
+ * The task ends when the returned handle is aborted. Only Linux has
+ * inotify; elsewhere this fails and `r` is the way to refresh.
+ * */
+pub fn spawn(scope: Workspace, events: EventSender) -> Result<JoinHandle<()>> {
+    #[cfg(target_os = "linux")]
+    return linux::spawn(scope, events);
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (scope, events);
+        anyhow::bail!("Watching the workspace needs inotify (Linux); press r to refresh")
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use anyhow::{Context, Result};
+    use futures::StreamExt;
+    use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask, Watches};
+    use std::collections::{BTreeSet, HashMap};
+    use std::ffi::OsStr;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+    use tokio::task::JoinHandle;
+
+    use crate::app::COMPOSE_FILE_NAMES;
+    use crate::config::{self, Workspace};
+    use crate::event::{AppEvent, EventSender};
+    use crate::index;
+
+    /// Quiet time after the last change before it is reported.
+    const SETTLE: Duration = Duration::from_millis(300);
+
+    const MASK: WatchMask = WatchMask::CREATE
+        .union(WatchMask::DELETE)
+        .union(WatchMask::CLOSE_WRITE)
+        .union(WatchMask::MOVED_FROM)
+        .union(WatchMask::MOVED_TO);
+
+    pub fn spawn(scope: Workspace, events: EventSender) -> Result<JoinHandle<()>> {
+        let inotify = Inotify::init().context("Cannot start watching the workspace")?;
+        let mut stream = inotify
+            .into_event_stream(vec![0u8; 16 * 1024])
+            .context("Cannot start watching the workspace")?;
+        let watches = stream.watches();
+
+        Ok(tokio::spawn(async move {
+            // Walking the roots takes a while on a big home directory.
+            let setup = tokio::task::spawn_blocking(move || {
+                let mut watcher = Watcher::new(watches, scope);
+                for root in watcher.roots() {
+                    watcher.add_tree(&root, 0, &mut Vec::new());
+                }
+                watcher
+            });
+            let Ok(mut watcher) = setup.await else {
+                return;
+            };
+            watcher.report_errors(&events);
+
+            let mut pending: BTreeSet<String> = BTreeSet::new();
+            loop {
+                tokio::select! {
+                    event = stream.next() => match event {
+                        Some(Ok(event)) => {
+                            let new_dir = watcher.handle(event.wd, event.mask, event.name.as_deref(), &mut pending);
+                            if let Some((path, depth)) = new_dir {
+                                // A directory moved in can hold a big tree;
+                                // it is walked off the runtime like the roots.
+                                let walk = tokio::task::spawn_blocking(move || {
+                                    let mut found = Vec::new();
+                                    watcher.add_tree(&path, depth, &mut found);
+                                    (watcher, found)
+                                });
+                                let Ok((walked, found)) = walk.await else {
+                                    return;
+                                };
+                                watcher = walked;
+                                pending.extend(found);
+                            }
+                            watcher.report_errors(&events);
+                        }
+                        Some(Err(e)) => {
+                            let _ = events.send(AppEvent::WatchError(format!(
+                                "Stopped watching the workspace: {}",
+                                e
+                            )));
+                            return;
+                        }
+                        None => return,
+                    },
+                    _ = tokio::time::sleep(SETTLE), if !pending.is_empty() => {
+                        let dirs = std::mem::take(&mut pending).into_iter().collect();
+                        let _ = events.send(AppEvent::ProjectsChanged(dirs));
+                    }
+                }
+            }
+        }))
+    }
+
+    /// A watched directory and how far below its root it is.
+    struct Dir {
+        path: PathBuf,
+        depth: usize,
+    }
+
+    struct Watcher {
+        watches: Watches,
+        scope: Workspace,
+        dirs: HashMap<WatchDescriptor, Dir>,
+        // Problems not yet passed on to the app.
+        errors: Vec<String>,
+        out_of_watches: bool,
+    }
+
+    impl Watcher {
+        fn new(watches: Watches, scope: Workspace) -> Self {
+            Self {
+                watches,
+                scope,
+                dirs: HashMap::new(),
+                errors: Vec::new(),
+                out_of_watches: false,
+            }
+        }
+
+        /// The roots as absolute paths, the home directory when there are
+        /// none.
+        fn roots(&self) -> Vec<PathBuf> {
+            let mut roots = self.scope.expanded_roots();
+            if roots.is_empty() {
+                roots.push(config::expand_root("~"));
+            }
+            roots
+                .iter()
+                .filter_map(|root| std::path::absolute(root).ok())
+                .collect()
+        }
+
+        /// Watches `dir` and the directories below it a search would enter,
+        /// adding the project directories among them to `found`.
+        fn add_tree(&mut self, dir: &Path, depth: usize, found: &mut Vec<String>) {
+            match self.watches.add(dir, MASK) {
+                Ok(wd) => {
+                    self.dirs.insert(
+                        wd,
+                        Dir {
+                            path: dir.to_path_buf(),
+                            depth,
+                        },
+                    );
+                }
+                Err(e) if e.kind() == io::ErrorKind::StorageFull => {
+                    if !self.out_of_watches {
+                        self.out_of_watches = true;
+                        self.errors.push(
+                            "Not watching all of the workspace: out of inotify watches; \
+                             raise fs.inotify.max_user_watches or narrow the roots"
+                                .to_string(),
+                        );
+                    }
+                    return;
+                }
+                // Unreadable or already gone.
+                Err(_) => return,
+            }
+
+            if COMPOSE_FILE_NAMES
+                .iter()
+                .any(|name| dir.join(name).is_file())
+            {
+                found.push(dir.display().to_string());
+            }
+            if self.scope.max_depth != 0 && depth >= self.scope.max_depth {
+                return;
+            }
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                // Symlinks are not followed, as in the search.
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    let path = entry.path();
+                    if !self.ignored(&path) {
+                        self.add_tree(&path, depth + 1, found);
+                    }
+                }
+            }
+        }
+
+        fn ignored(&self, path: &Path) -> bool {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy())
+                .unwrap_or_default();
+            let full = path.to_string_lossy();
+            self.scope
+                .ignore
+                .iter()
+                .any(|pattern| glob_match(pattern, &name) || glob_match(pattern, &full))
+        }
+
+        /// Notes what `event` changed in `pending`. A directory that
+        /// appeared and has to be walked (with `add_tree`) is returned with
+        /// its depth instead, since that can take a while.
+        fn handle(
+            &mut self,
+            wd: WatchDescriptor,
+            mask: EventMask,
+            name: Option<&OsStr>,
+            pending: &mut BTreeSet<String>,
+        ) -> Option<(PathBuf, usize)> {
+            if mask.contains(EventMask::Q_OVERFLOW) {
+                self.errors
+                    .push("Missed some workspace changes; press r to rescan".to_string());
+                return None;
+            }
+            if mask.contains(EventMask::IGNORED) {
+                self.dirs.remove(&wd);
+                return None;
+            }
+            let (Some(dir), Some(name)) = (self.dirs.get(&wd), name) else {
+                return None;
+            };
+            let path = dir.path.join(name);
+            let depth = dir.depth + 1;
+
+            if !mask.contains(EventMask::ISDIR) {
+                if is_project_file(&name.to_string_lossy()) {
+                    pending.insert(dir.path.display().to_string());
+                }
+                return None;
+            }
+            if mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) {
+                let within = self.scope.max_depth == 0 || depth <= self.scope.max_depth;
+                if within && !self.ignored(&path) {
+                    return Some((path, depth));
+                }
+            } else if mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+                // Watches follow a moved directory; its old paths are stale.
+                let stale: Vec<WatchDescriptor> = self
+                    .dirs
+                    .iter()
+                    .filter(|(_, d)| d.path.starts_with(&path))
+                    .map(|(wd, _)| wd.clone())
+                    .collect();
+                for wd in stale {
+                    self.dirs.remove(&wd);
+                    let _ = self.watches.remove(wd);
+                }
+                pending.insert(path.display().to_string());
+            }
+            None
+        }
+
+        fn report_errors(&mut self, events: &EventSender) {
+            for error in self.errors.drain(..) {
+                let _ = events.send(AppEvent::WatchError(error));
+            }
+        }
+    }
+
+    fn is_project_file(name: &str) -> bool {
+        index::project_file_names().iter().any(|file| file == name)
+    }
+
+    /*
+     * Ignore globs
+     *
+     * Matched against the whole of `s` with the grammar of Go's
+     * filepath.Match, which the runner's search uses, so both skip the same
+     * directories: `*` is any run of characters but `/`, `?` one character
+     * but `/`, `[a-z0-9]` or `[^...]` one character of a class, and `\`
+     * takes the next character literally. A malformed pattern matches
+     * nothing, as it does in the search.
+     * */
+    fn glob_match(pattern: &str, s: &str) -> bool {
+        let (p, s): (Vec<char>, Vec<char>) = (pattern.chars().collect(), s.chars().collect());
+        let (mut pi, mut si) = (0, 0);
+        let mut star: Option<(usize, usize)> = None;
+        while si < s.len() {
+            let next = match p.get(pi) {
+                Some('*') => {
+                    star = Some((pi, si));
+                    pi += 1;
+                    continue;
+                }
+                Some(_) => match match_one(&p, pi, s[si]) {
+                    Some(next) => next,
+                    None => return false,
+                },
+                None => None,
+            };
+            match (next, star) {
+                (Some(next), _) => {
+                    pi = next;
+                    si += 1;
+                }
+                (None, Some((sp, ss))) if s[ss] != '/' => {
+                    star = Some((sp, ss + 1));
+                    pi = sp + 1;
+                    si = ss + 1;
+                }
+                (None, _) => return false,
+            }
+        }
+        p[pi..].iter().all(|&c| c == '*')
+    }
+
+    /// Matches the pattern element at `p[pi]` against `c`: Some(Some(end of
+    /// the element)) when it matches, Some(None) when it does not and None
+    /// when the element is malformed.
+    fn match_one(p: &[char], pi: usize, c: char) -> Option<Option<usize>> {
+        match p[pi] {
+            '?' => Some((c != '/').then_some(pi + 1)),
+            '\\' => {
+                let &literal = p.get(pi + 1)?;
+                Some((literal == c).then_some(pi + 2))
+            }
+            '[' => {
+                let mut i = pi + 1;
+                let negated = p.get(i) == Some(&'^');
+                if negated {
+                    i += 1;
+                }
+                let mut matched = false;
+                let mut first = true;
+                loop {
+                    if p.get(i) == Some(&']') && !first {
+                        i += 1;
+                        break;
+                    }
+                    first = false;
+                    let (lo, after) = class_char(p, i)?;
+                    let (hi, after) = if p.get(after) == Some(&'-') {
+                        class_char(p, after + 1)?
+                    } else {
+                        (lo, after)
+                    };
+                    matched |= lo <= c && c <= hi;
+                    i = after;
+                }
+                Some((matched != negated).then_some(i))
+            }
+            literal => Some((literal == c).then_some(pi + 1)),
+        }
+    }
+
+    /// A character of a class and what follows it; None for an unescaped
+    /// `-` or `]`, or the end of the pattern.
+    fn class_char(p: &[char], i: usize) -> Option<(char, usize)> {
+        match *p.get(i)? {
+            '-' | ']' => None,
+            '\\' => Some((*p.get(i + 1)?, i + 2)),
+            c => Some((c, i + 1)),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::glob_match;
+
+        #[test]
+        fn glob_match_follows_filepath_match() {
+            let cases = [
+                ("node_modules", "node_modules", true),
+                ("node_modules", "node_modules2", false),
+                ("*.cache", "pip.cache", true),
+                ("*", "a/b", false),
+                ("/home/*/tmp", "/home/me/tmp", true),
+                ("/home/*", "/home/me/tmp", false),
+                ("a*", "a", true),
+                ("?at", "cat", true),
+                ("?at", "/at", false),
+                ("[abc]at", "bat", true),
+                ("[^abc]at", "bat", false),
+                ("[^abc]at", "rat", true),
+                ("build-[0-9]", "build-7", true),
+                ("build-[0-9]", "build-x", false),
+                ("[a-c-]", "-", false),
+                ("\\*", "*", true),
+                ("\\*", "a", false),
+                ("[\\]]", "]", true),
+                // Malformed patterns match nothing.
+                ("[", "[", false),
+                ("[]a]", "a", false),
+                ("[a", "a", false),
+                ("\\", "\\", false),
+            ];
+            for (pattern, s, expected) in cases {
+                assert_eq!(glob_match(pattern, s), expected, "{} ~ {}", pattern, s);
+            }
+        }
+    }
+}